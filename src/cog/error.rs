@@ -38,6 +38,7 @@
 //! ```
 use super::compression::DecompressError;
 use crate::geotags::GeoTiffError;
+use crate::io::ObjectChangedError;
 use crate::projection::ProjectionError;
 use crate::raster::RasterError;
 use crate::tiff::TiffError;
@@ -66,6 +67,7 @@ pub type CloudTiffResult<T> = Result<T, CloudTiffError>;
 /// ## IO 错误
 /// * `ReadError` - 文件读取错误
 /// * `ReadRangeError` - 范围读取错误，包含错误描述
+/// * `RemoteObjectChanged` - 远程对象在读取过程中被替换
 ///
 /// ## 数据处理错误
 /// * `DecompresionError` - 数据解压缩错误
@@ -106,6 +108,8 @@ pub enum CloudTiffError {
     RegionOutOfBounds(((f64, f64, f64, f64), (f64, f64, f64, f64))),
     /// 范围读取错误,包含错误描述
     ReadRangeError(String),
+    /// 远程对象在读取过程中被替换,包含(期望版本,实际版本)
+    RemoteObjectChanged(ObjectChangedError),
     /// 互斥锁错误,包含错误描述
     MutexError(String),
     /// 不支持的操作,包含具体说明
@@ -133,11 +137,11 @@ impl std::error::Error for CloudTiffError {}
 
 /// 从 TIFF 错误转换
 ///
-/// 特殊处理了 TIFF 的 IO 错误，将其转换为 CloudTiffError::ReadError
+/// 特殊处理了 TIFF 的 IO 错误，按 IO 错误的规则转换
 impl From<TiffError> for CloudTiffError {
     fn from(e: TiffError) -> Self {
         match e {
-            TiffError::ReadError(io_error) => CloudTiffError::ReadError(io_error),
+            TiffError::ReadError(io_error) => CloudTiffError::from(io_error),
             tiff_error => CloudTiffError::BadTiff(tiff_error),
         }
     }
@@ -151,9 +155,17 @@ impl From<GeoTiffError> for CloudTiffError {
 }
 
 /// 从标准 IO 错误转换
+///
+/// 远程读取器报告的对象变更错误会转换为 RemoteObjectChanged
 impl From<io::Error> for CloudTiffError {
     fn from(e: io::Error) -> Self {
-        CloudTiffError::ReadError(e)
+        match e
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<ObjectChangedError>())
+        {
+            Some(changed) => CloudTiffError::RemoteObjectChanged(changed.clone()),
+            None => CloudTiffError::ReadError(e),
+        }
     }
 }
 
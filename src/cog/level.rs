@@ -31,6 +31,7 @@ use super::CloudTiffError;
 use crate::raster::{ExtraSamples, PhotometricInterpretation, Raster, SampleFormat};
 use crate::tiff::{Endian, Ifd, TagId, TiffError};
 use crate::{Region, UnitFloat};
+use std::collections::HashMap;
 use std::fmt::Display;
 
 /// 表示 COG 金字塔中的一个分辨率层级
@@ -90,6 +91,9 @@ pub struct Level {
 
     /// 分块数据的字节数
     pub byte_counts: Vec<usize>,
+
+    /// 无数据值,来自 GDAL_NODATA 标签
+    pub nodata: Option<f64>,
 }
 
 impl Level {
@@ -160,6 +164,17 @@ impl Level {
             )));
         }
 
+        // 无数据值,GDAL 以 ASCII 字符串保存
+        let nodata = ifd
+            .get_tag(TagId::GDALNoData)
+            .ok()
+            .and_then(|tag| tag.try_to_string())
+            .and_then(|s| {
+                s.trim_matches(|c: char| c == '\0' || c.is_whitespace())
+                    .parse()
+                    .ok()
+            });
+
         Ok(Self {
             overview: None,
             dimensions: (width, height),
@@ -174,6 +189,7 @@ impl Level {
             endian,
             offsets,
             byte_counts,
+            nodata,
         })
     }
 
@@ -334,42 +350,168 @@ impl Level {
     /// 可能返回解压缩错误或数据格式错误
     pub fn extract_tile_from_bytes(&self, bytes: &[u8]) -> Result<Raster, CloudTiffError> {
         // 1. 解压缩分块数据
-        let mut buffer = self.compression.decode(bytes)?;
+        let buffer = self.compression.decode(bytes)?;
 
-        // TODO: 处理字节序
+        // 2. 栅格化处理
+        // 将解压后的数据转换为栅格格式
+        // 参数包括:
+        // - 分块尺寸
+        // - 像素数据
+        // - 每个采样的位深度
+        // - 颜色解释方式
+        // - 采样格式
+        // - 额外采样信息
+        // - 文件的字节序
+        let mut raster = Raster::new(
+            (self.tile_width, self.tile_height),
+            buffer,
+            self.bits_per_sample.clone(),
+            self.interpretation,
+            self.sample_format.clone(),
+            self.extra_samples.clone(),
+            self.endian,
+        )?;
 
-        // 2. 应用预测器
+        // 3. 处理字节序
+        // 统一转换为本机字节序,之后读取样本时不需要再处理字节序
+        raster.convert_endian(Endian::native());
+
+        // 4. 应用预测器
         // 获取位深度(暂时只使用第一个采样的位深度)
         // TODO: 考虑不同采样可能有不同位深度的情况
         let bit_depth = self.bits_per_sample[0] as usize;
 
         // 对解压后的数据应用预测器
         self.predictor.predict(
-            buffer.as_mut_slice(),
+            raster.buffer.as_mut_slice(),
             self.tile_width as usize,
             bit_depth,
             self.bits_per_sample.len(),
         )?;
 
-        // 3. 栅格化处理
-        // 将处理后的数据转换为栅格格式
-        // 参数包括:
-        // - 分块尺寸
-        // - 像素数据
-        // - 每个采样的位深度
-        // - 颜色解释方式
-        // - 采样格式
-        // - 额外采样信息
-        // - 字节序(TODO: 应该在解压时处理)
-        Ok(Raster::new(
-            (self.tile_width, self.tile_height),
-            buffer,
+        Ok(raster)
+    }
+
+    /// 获取像素窗口覆盖的分块索引列表
+    ///
+    /// # 参数
+    ///
+    /// * `window` - 像素窗口,`[min, max)` 区间,超出图像的部分会被忽略
+    ///
+    /// # 返回值
+    ///
+    /// 返回与窗口相交的所有分块索引
+    pub fn tile_indices_within_window(&self, window: &Region<u32>) -> Vec<usize> {
+        // 限制在图像范围内
+        let x_max = window.x.max.min(self.width());
+        let y_max = window.y.max.min(self.height());
+        if window.x.min >= x_max || window.y.min >= y_max {
+            return vec![];
+        }
+
+        // 计算窗口覆盖的分块范围
+        let col_min = (window.x.min / self.tile_width) as usize;
+        let col_max = x_max.div_ceil(self.tile_width) as usize;
+        let row_min = (window.y.min / self.tile_height) as usize;
+        let row_max = y_max.div_ceil(self.tile_height) as usize;
+
+        let col_count = self.col_count();
+        (row_min..row_max)
+            .flat_map(|row| (col_min..col_max).map(move |col| row * col_count + col))
+            .collect()
+    }
+
+    /// 从分块中拼接像素窗口,不进行重采样
+    ///
+    /// # 参数
+    ///
+    /// * `tiles` - 已读取的分块,需要包含 [`tile_indices_within_window`](Self::tile_indices_within_window) 返回的全部分块
+    /// * `window` - 像素窗口,`[min, max)` 区间
+    ///
+    /// # 返回值
+    ///
+    /// 返回窗口尺寸的栅格,使用本机字节序
+    ///
+    /// # 错误
+    ///
+    /// 如果窗口为空或超出图像范围,返回 RegionOutOfBounds 错误;
+    /// 如果缺少分块或像素不是按字节对齐的,返回相应错误
+    pub fn window_from_tiles(
+        &self,
+        tiles: &HashMap<usize, Raster>,
+        window: &Region<u32>,
+    ) -> Result<Raster, CloudTiffError> {
+        self.check_window(window)?;
+        let bits_per_pixel: u32 = self.bits_per_sample.iter().map(|b| *b as u32).sum();
+        if !bits_per_pixel.is_multiple_of(8) {
+            return Err(CloudTiffError::NotSupported(format!(
+                "像素不是按字节对齐的: {bits_per_pixel} 位"
+            )));
+        }
+        let pixel_size = bits_per_pixel as usize / 8;
+
+        let (width, height) = (window.x.range(), window.y.range());
+        let mut raster = Raster::blank(
+            (width, height),
             self.bits_per_sample.clone(),
             self.interpretation,
             self.sample_format.clone(),
             self.extra_samples.clone(),
-            self.endian,
-        )?)
+            Endian::native(),
+        );
+        let row_size = width as usize * pixel_size;
+
+        // 逐个分块复制与窗口相交的行
+        let col_count = self.col_count();
+        for index in self.tile_indices_within_window(window) {
+            let tile = tiles
+                .get(&index)
+                .ok_or_else(|| CloudTiffError::NotSupported(format!("缺少分块 {index}")))?;
+            let tile_x = (index % col_count) as u32 * self.tile_width;
+            let tile_y = (index / col_count) as u32 * self.tile_height;
+
+            // 分块与窗口的交集,使用图像像素坐标
+            let x0 = window.x.min.max(tile_x);
+            let x1 = window.x.max.min(tile_x + self.tile_width);
+            let y0 = window.y.min.max(tile_y);
+            let y1 = window.y.max.min(tile_y + self.tile_height);
+            let span = (x1 - x0) as usize * pixel_size;
+            let tile_row_size = tile.dimensions.0 as usize * pixel_size;
+
+            for y in y0..y1 {
+                let src =
+                    (y - tile_y) as usize * tile_row_size + (x0 - tile_x) as usize * pixel_size;
+                let dst = (y - window.y.min) as usize * row_size
+                    + (x0 - window.x.min) as usize * pixel_size;
+                raster.buffer[dst..dst + span].copy_from_slice(&tile.buffer[src..src + span]);
+            }
+        }
+        Ok(raster)
+    }
+
+    /// 检查像素窗口是否非空且位于图像范围内
+    ///
+    /// # 错误
+    ///
+    /// 如果窗口为空或超出图像范围,返回 RegionOutOfBounds 错误
+    pub fn check_window(&self, window: &Region<u32>) -> Result<(), CloudTiffError> {
+        let (width, height) = self.dimensions;
+        if window.x.min >= window.x.max
+            || window.y.min >= window.y.max
+            || window.x.max > width
+            || window.y.max > height
+        {
+            return Err(CloudTiffError::RegionOutOfBounds((
+                (
+                    window.x.min as f64,
+                    window.y.min as f64,
+                    window.x.max as f64,
+                    window.y.max as f64,
+                ),
+                (0.0, 0.0, width as f64, height as f64),
+            )));
+        }
+        Ok(())
     }
 
     /// 获取指定分块的归一化边界
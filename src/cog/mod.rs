@@ -25,19 +25,24 @@
 //! - 云端 GIS 应用
 
 use crate::geotags::GeoTags;
+use crate::io::ReadRange;
 use crate::projection::Projection;
 use crate::tiff::Tiff;
 use crate::Region;
 use std::fmt::Display;
-use std::io::{BufReader, Read, Seek};
+use std::io::{BufReader, Cursor, ErrorKind, Read, Seek};
 
 mod compression;
 mod error;
 mod level;
+mod query;
+mod statistics;
+mod window;
 
 pub use compression::{Compression, DecompressError, Predictor};
 pub use error::{CloudTiffError, CloudTiffResult};
 pub use level::Level;
+pub use statistics::{BandStatistics, Histogram, StatisticsMode, StatisticsOptions};
 
 /// 表示一个 Cloud Optimized GeoTIFF 文件
 ///
@@ -102,6 +107,35 @@ impl CloudTiff {
         Self::from_tiff_and_geo(tiff, geo_tags)
     }
 
+    /// 从实现了 ReadRange 的数据源打开 COG 文件
+    ///
+    /// 按 4KB 逐步读取文件头,直到足够解析所有 IFD(最多 10 次)
+    ///
+    /// # 参数
+    ///
+    /// * `source` - 实现了 ReadRange trait 的数据源,例如阻塞 HTTP 读取器
+    pub fn open_from_range_reader<R: ReadRange>(source: &R) -> CloudTiffResult<Self> {
+        let fetch_size = 4096;
+        let mut result = Err(CloudTiffError::TODO);
+        let mut buffer = Vec::with_capacity(fetch_size);
+        for _i in 0..10 {
+            let mut bytes = vec![0; fetch_size];
+            let start = buffer.len();
+            let n = source.read_range(start as u64, &mut bytes)?;
+            buffer.extend_from_slice(&bytes[..n]);
+
+            let mut cursor = Cursor::new(&buffer);
+            result = Self::open(&mut cursor);
+            if let Err(CloudTiffError::ReadError(e)) = &result {
+                if n > 0 && matches!(e.kind(), ErrorKind::UnexpectedEof) {
+                    continue;
+                }
+            }
+            break;
+        }
+        result
+    }
+
     /// 从已解析的 TIFF 结构和地理标签创建 CloudTiff
     ///
     /// # 参数
@@ -159,6 +193,11 @@ impl CloudTiff {
         w as f64 / h as f64
     }
 
+    /// 获取波段数(包括 alpha 等额外样本)
+    pub fn band_count(&self) -> usize {
+        self.levels[0].bits_per_sample.len()
+    }
+
     /// 获取金字塔中最大的层级索引
     pub fn max_level(&self) -> usize {
         let n = self.levels.len();
@@ -200,12 +239,30 @@ impl CloudTiff {
                 // 除以像素数得到每个像素的比例
                 (
                     scale.0 / level.dimensions.0 as f64, // x 方向像素比例
-                    scale.1 / level.dimensions.0 as f64, // y 方向像素比例
+                    scale.1 / level.dimensions.1 as f64, // y 方向像素比例
                 )
             })
             .collect()
     }
 
+    /// 获取指定层级像素的地面尺寸(米)
+    ///
+    /// 由 [`pixel_scales`](Self::pixel_scales) 换算得到,地理坐标系按影像中心纬度换算为米,
+    /// 用于山体阴影、坡度等需要水平距离的计算
+    ///
+    /// # 参数
+    ///
+    /// * `level` - 层级索引,0 表示原始分辨率
+    ///
+    /// # 错误
+    ///
+    /// 如果层级索引超出范围,返回 TileLevelOutOfRange 错误
+    pub fn ground_pixel_size(&self, level: usize) -> CloudTiffResult<(f64, f64)> {
+        self.get_level(level)?;
+        let pixel_scale = self.pixel_scales()[level];
+        Ok(self.projection.ground_pixel_size(pixel_scale, 0.5))
+    }
+
     /// 根据目标像素比例选择最合适的层级
     ///
     /// # 参数
@@ -265,7 +322,6 @@ mod not_sync {
     use {
         super::*,
         crate::AsyncReadRange,
-        std::io::{Cursor, ErrorKind},
         tokio::io::{AsyncRead, AsyncReadExt},
     };
     impl CloudTiff {
@@ -0,0 +1,230 @@
+//! 点查询模块
+//!
+//! 本模块提供了在任意坐标系中查询单个点或一批点的像素值的功能,
+//! 例如查询某个经纬度的高程,无需渲染整个区域。
+//!
+//! 批量查询时先将所有点转换到影像的像素坐标,再按所需瓦片分组,
+//! 每个瓦片只读取和解压一次。
+
+use super::{CloudTiff, CloudTiffResult, Level};
+use crate::io::ReadRange;
+use crate::projection::ProjectionError;
+use crate::render::resample::{self, LevelSampler, Resampling};
+use crate::render::tiles;
+use proj4rs::Proj;
+
+/// 转换到层级像素坐标后的查询点,超出影像范围时为 None
+type LevelPoint = Option<(f64, f64)>;
+
+impl CloudTiff {
+    /// 查询指定坐标处的像素值(最近邻)
+    ///
+    /// # 参数
+    /// * `reader` - 范围读取器
+    /// * `epsg` - 坐标所在坐标系的 EPSG 代码,地理坐标系使用弧度
+    /// * `x`, `y` - 坐标
+    /// * `level` - 层级索引,0 表示原始分辨率
+    ///
+    /// # 返回
+    /// 每个波段的值,坐标位于影像范围外或无法转换时返回 None
+    ///
+    /// # 错误
+    /// 层级不存在、坐标系不受支持或瓦片读取失败时返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::sync::Mutex;
+    ///
+    /// # fn example(cog: &cloudtiff::CloudTiff) {
+    /// let reader = Mutex::new(File::open("dem.tif").unwrap());
+    /// let (lon, lat) = (-128.95_f64.to_radians(), 54.97_f64.to_radians());
+    /// let elevation = cog.sample_at(&reader, 4326, lon, lat, 0).unwrap();
+    /// # }
+    /// ```
+    pub fn sample_at<R: ReadRange>(
+        &self,
+        reader: &R,
+        epsg: u16,
+        x: f64,
+        y: f64,
+        level: usize,
+    ) -> CloudTiffResult<Option<Vec<f64>>> {
+        let mut values = self.sample_points(reader, epsg, &[(x, y)], level, Resampling::Nearest)?;
+        Ok(values.pop().flatten())
+    }
+
+    /// 批量查询多个坐标处的像素值
+    ///
+    /// 所需瓦片只读取一次。插值时像素值按像素中心插值,且不会按样本类型取整
+    ///
+    /// # 参数
+    /// * `reader` - 范围读取器
+    /// * `epsg` - 坐标所在坐标系的 EPSG 代码,地理坐标系使用弧度
+    /// * `points` - 坐标 (x, y) 列表
+    /// * `level` - 层级索引,0 表示原始分辨率
+    /// * `resampling` - 插值方法,面积方法等同于最近邻
+    ///
+    /// # 返回
+    /// 与输入顺序一致的每个点各波段的值,位于影像范围外或无法转换的点为 None
+    ///
+    /// # 错误
+    /// 层级不存在、坐标系不受支持或瓦片读取失败时返回错误
+    pub fn sample_points<R: ReadRange>(
+        &self,
+        reader: &R,
+        epsg: u16,
+        points: &[(f64, f64)],
+        level: usize,
+        resampling: Resampling,
+    ) -> CloudTiffResult<Vec<Option<Vec<f64>>>> {
+        let level = self.get_level(level)?;
+        let level_points = self.level_points(level, epsg, points)?;
+        let indices = point_tile_indices(level, &level_points, resampling)?;
+        let tile_cache = tiles::get_tiles_from(reader, level, indices)?;
+        let sampler = LevelSampler::new(level, &tile_cache, resampling);
+        Ok(sample_level_points(&sampler, &level_points))
+    }
+
+    /// 将坐标转换为层级的连续像素坐标
+    ///
+    /// 无法转换的点(如超出 Web 墨卡托纬度范围)与影像范围外的点一样为 None,
+    /// 只有源坐标系不受支持时返回错误
+    fn level_points(
+        &self,
+        level: &Level,
+        epsg: u16,
+        points: &[(f64, f64)],
+    ) -> CloudTiffResult<Vec<LevelPoint>> {
+        let (width, height) = (level.width() as f64, level.height() as f64);
+        // 源投影只创建一次
+        let from = Proj::from_epsg_code(epsg).map_err(ProjectionError::from)?;
+        Ok(points
+            .iter()
+            .map(|(x, y)| {
+                let (u, v, _) = self
+                    .projection
+                    .transform_from_proj(&from, *x, *y, 0.0)
+                    .ok()?;
+                let inside = (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v);
+                inside.then_some((u * width, v * height))
+            })
+            .collect())
+    }
+}
+
+/// 计算查询点所需的瓦片索引
+///
+/// 最近邻只需要点所在的瓦片,插值时还需要插值核覆盖的相邻瓦片
+fn point_tile_indices(
+    level: &Level,
+    points: &[LevelPoint],
+    resampling: Resampling,
+) -> CloudTiffResult<Vec<usize>> {
+    let points = points.iter().flatten();
+    match resampling {
+        Resampling::Bilinear | Resampling::Cubic | Resampling::Lanczos => {
+            Ok(resample::tile_indices_for_points(
+                level,
+                resampling,
+                points.map(|(x, y)| (*x, *y, (0.0, 0.0))),
+            ))
+        }
+        _ => {
+            let (width, height) = (level.width() as f64, level.height() as f64);
+            let mut indices = points
+                .map(|(x, y)| {
+                    // 使用点所在像素的中心,图像右边和下边上的点归入最后一个像素
+                    let x = x.floor().clamp(0.0, width - 1.0) + 0.5;
+                    let y = y.floor().clamp(0.0, height - 1.0) + 0.5;
+                    level
+                        .index_from_image_coords(x / width, y / height)
+                        .map(|(index, ..)| index)
+                })
+                .collect::<CloudTiffResult<Vec<_>>>()?;
+            indices.sort_unstable();
+            indices.dedup();
+            Ok(indices)
+        }
+    }
+}
+
+/// 在已读取的瓦片上采样所有查询点
+fn sample_level_points(sampler: &LevelSampler, points: &[LevelPoint]) -> Vec<Option<Vec<f64>>> {
+    points
+        .iter()
+        .map(|point| {
+            let (x, y) = (*point)?;
+            // 单点查询不需要按输出像素展开
+            sampler.sample_values(x, y, (0.0, 0.0))
+        })
+        .collect()
+}
+
+#[cfg(feature = "async")]
+mod not_sync {
+    use super::*;
+    use crate::io::AsyncReadRange;
+
+    impl CloudTiff {
+        /// 异步查询指定坐标处的像素值(最近邻)
+        ///
+        /// # 参数
+        /// * `reader` - 异步范围读取器
+        /// * `epsg` - 坐标所在坐标系的 EPSG 代码,地理坐标系使用弧度
+        /// * `x`, `y` - 坐标
+        /// * `level` - 层级索引,0 表示原始分辨率
+        ///
+        /// # 返回
+        /// 每个波段的值,坐标位于影像范围外或无法转换时返回 None
+        ///
+        /// # 错误
+        /// 层级不存在、坐标系不受支持或瓦片读取失败时返回错误
+        pub async fn sample_at_async<R: AsyncReadRange>(
+            &self,
+            reader: &R,
+            epsg: u16,
+            x: f64,
+            y: f64,
+            level: usize,
+        ) -> CloudTiffResult<Option<Vec<f64>>> {
+            let mut values = self
+                .sample_points_async(reader, epsg, &[(x, y)], level, Resampling::Nearest)
+                .await?;
+            Ok(values.pop().flatten())
+        }
+
+        /// 异步批量查询多个坐标处的像素值
+        ///
+        /// 所需瓦片并发读取,每个瓦片只读取一次
+        ///
+        /// # 参数
+        /// * `reader` - 异步范围读取器
+        /// * `epsg` - 坐标所在坐标系的 EPSG 代码,地理坐标系使用弧度
+        /// * `points` - 坐标 (x, y) 列表
+        /// * `level` - 层级索引,0 表示原始分辨率
+        /// * `resampling` - 插值方法,面积方法等同于最近邻
+        ///
+        /// # 返回
+        /// 与输入顺序一致的每个点各波段的值,位于影像范围外或无法转换的点为 None
+        ///
+        /// # 错误
+        /// 层级不存在、坐标系不受支持或瓦片读取失败时返回错误
+        pub async fn sample_points_async<R: AsyncReadRange>(
+            &self,
+            reader: &R,
+            epsg: u16,
+            points: &[(f64, f64)],
+            level: usize,
+            resampling: Resampling,
+        ) -> CloudTiffResult<Vec<Option<Vec<f64>>>> {
+            let level = self.get_level(level)?;
+            let level_points = self.level_points(level, epsg, points)?;
+            let indices = point_tile_indices(level, &level_points, resampling)?;
+            let tile_cache = tiles::get_tiles_from_async(reader, level, indices).await?;
+            let sampler = LevelSampler::new(level, &tile_cache, resampling);
+            Ok(sample_level_points(&sampler, &level_points))
+        }
+    }
+}
@@ -0,0 +1,541 @@
+//! 统计模块
+//!
+//! 本模块提供了按波段统计 COG 影像的功能,用于配置对比度拉伸等:
+//! - 最小值、最大值、均值和标准差
+//! - 直方图和百分位数
+//! - 近似模式:使用满足像素数要求的最粗概览层级
+//! - 精确模式:逐瓦片流式读取原始分辨率的全部数据,可选使用 rayon 并行
+//!
+//! 无数据值、NaN 和 Alpha 为 0 的透明像素不参与统计,Alpha 波段本身也不统计。
+
+use super::{CloudTiff, CloudTiffResult, Level};
+use crate::io::ReadRange;
+use crate::raster::{ExtraSamples, Raster, SampleType};
+
+/// 统计模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatisticsMode {
+    /// 使用像素数不少于 `min_pixels` 的最粗层级
+    Approximate {
+        /// 统计层级的最少像素数
+        min_pixels: u64,
+    },
+    /// 读取原始分辨率层级的全部瓦片
+    Exact,
+}
+
+/// 统计参数
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::cog::StatisticsOptions;
+/// use std::fs::File;
+/// use std::sync::Mutex;
+///
+/// # fn example(cog: &cloudtiff::CloudTiff) {
+/// let reader = Mutex::new(File::open("dem.tif").unwrap());
+/// let options = StatisticsOptions::exact().with_nodata(-9999.0).with_parallel(true);
+/// for band in cog.statistics(&reader, &options).unwrap() {
+///     println!("{} ~ {}, p98 = {}", band.min, band.max, band.histogram.percentile(98.0));
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatisticsOptions {
+    /// 统计模式
+    pub mode: StatisticsMode,
+    /// 无数据值,未设置时使用文件中的 GDAL_NODATA 标签
+    pub nodata: Option<f64>,
+    /// 直方图的分箱数
+    pub bins: usize,
+    /// 直方图的取值范围,超出范围的值计入两端的分箱
+    pub histogram_range: Option<(f64, f64)>,
+    /// 是否使用 rayon 并行解压和统计瓦片(需要 `rayon` 特性)
+    pub parallel: bool,
+}
+
+impl Default for StatisticsOptions {
+    fn default() -> Self {
+        Self::approximate()
+    }
+}
+
+impl StatisticsOptions {
+    /// 近似统计,使用像素数不少于 1024x1024 的最粗层级
+    pub fn approximate() -> Self {
+        Self {
+            mode: StatisticsMode::Approximate {
+                min_pixels: 1024 * 1024,
+            },
+            nodata: None,
+            bins: 256,
+            histogram_range: None,
+            parallel: false,
+        }
+    }
+
+    /// 精确统计,读取原始分辨率的全部瓦片
+    pub fn exact() -> Self {
+        Self {
+            mode: StatisticsMode::Exact,
+            ..Self::approximate()
+        }
+    }
+
+    /// 设置近似统计层级的最少像素数
+    pub fn with_min_pixels(mut self, min_pixels: u64) -> Self {
+        self.mode = StatisticsMode::Approximate { min_pixels };
+        self
+    }
+
+    /// 设置无数据值
+    pub fn with_nodata(mut self, nodata: f64) -> Self {
+        self.nodata = Some(nodata);
+        self
+    }
+
+    /// 设置直方图的分箱数
+    pub fn with_bins(mut self, bins: usize) -> Self {
+        self.bins = bins.max(1);
+        self
+    }
+
+    /// 设置直方图的取值范围
+    ///
+    /// 未设置时,8 位数据使用类型的完整范围;其他类型使用统计层级的数据范围,
+    /// 需要逐瓦片流式读取两遍:第一遍确定最小值和最大值,第二遍统计直方图
+    pub fn with_histogram_range(mut self, min: f64, max: f64) -> Self {
+        self.histogram_range = Some((min, max));
+        self
+    }
+
+    /// 设置是否并行统计
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+}
+
+/// 直方图
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// 取值范围 (最小值, 最大值)
+    pub range: (f64, f64),
+    /// 每个分箱的像素数
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    /// 创建空直方图
+    fn new(range: (f64, f64), bins: usize) -> Self {
+        Self {
+            range,
+            counts: vec![0; bins.max(1)],
+        }
+    }
+
+    /// 分箱的宽度
+    pub fn bin_width(&self) -> f64 {
+        (self.range.1 - self.range.0) / self.counts.len() as f64
+    }
+
+    /// 获取值所在的分箱索引,超出范围的值归入两端
+    pub fn bin(&self, value: f64) -> usize {
+        let (min, max) = self.range;
+        let last = self.counts.len() - 1;
+        if max <= min || value <= min {
+            return if value > min { last } else { 0 };
+        }
+        let t = (value - min) / (max - min);
+        ((t * self.counts.len() as f64) as usize).min(last)
+    }
+
+    /// 统计的总像素数
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// 估算百分位数,在分箱内线性插值
+    ///
+    /// # 参数
+    /// * `percentile` - 百分位,取值 0~100
+    pub fn percentile(&self, percentile: f64) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return f64::NAN;
+        }
+        let target = percentile.clamp(0.0, 100.0) / 100.0 * total as f64;
+        let mut cumulative = 0.0;
+        for (i, count) in self.counts.iter().enumerate() {
+            let next = cumulative + *count as f64;
+            if next >= target && *count > 0 {
+                let fraction = (target - cumulative) / *count as f64;
+                return self.range.0 + (i as f64 + fraction) * self.bin_width();
+            }
+            cumulative = next;
+        }
+        self.range.1
+    }
+
+    /// 合并另一个相同分箱的直方图
+    fn merge(&mut self, other: &Histogram) {
+        for (a, b) in self.counts.iter_mut().zip(&other.counts) {
+            *a += b;
+        }
+    }
+}
+
+/// 单个波段的统计结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct BandStatistics {
+    /// 有效像素数
+    pub count: u64,
+    /// 无数据像素数(包括 NaN 和透明像素)
+    pub nodata_count: u64,
+    /// 最小值,没有有效像素时为 NaN
+    pub min: f64,
+    /// 最大值,没有有效像素时为 NaN
+    pub max: f64,
+    /// 均值,没有有效像素时为 NaN
+    pub mean: f64,
+    /// 总体标准差,没有有效像素时为 NaN
+    pub std_dev: f64,
+    /// 直方图
+    pub histogram: Histogram,
+}
+
+/// 单个波段的累加器
+///
+/// 均值和方差使用 Welford 算法累加,可以合并并行计算的部分结果
+#[derive(Clone, Debug)]
+struct BandAccumulator {
+    count: u64,
+    nodata_count: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+    histogram: Histogram,
+}
+
+impl BandAccumulator {
+    fn new(histogram: Histogram) -> Self {
+        Self {
+            count: 0,
+            nodata_count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+            histogram,
+        }
+    }
+
+    /// 累加一个值
+    fn add(&mut self, value: f64, nodata: Option<f64>) {
+        if value.is_nan() || nodata == Some(value) {
+            self.nodata_count += 1;
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        let bin = self.histogram.bin(value);
+        self.histogram.counts[bin] += 1;
+    }
+
+    /// 合并另一个累加器
+    fn merge(mut self, other: Self) -> Self {
+        let count = self.count + other.count;
+        if count > 0 {
+            let delta = other.mean - self.mean;
+            self.mean += delta * other.count as f64 / count as f64;
+            self.m2 +=
+                other.m2 + delta * delta * self.count as f64 * other.count as f64 / count as f64;
+        }
+        self.count = count;
+        self.nodata_count += other.nodata_count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.histogram.merge(&other.histogram);
+        self
+    }
+
+    fn finish(self) -> BandStatistics {
+        let valid = self.count > 0;
+        let or_nan = |v: f64| if valid { v } else { f64::NAN };
+        BandStatistics {
+            count: self.count,
+            nodata_count: self.nodata_count,
+            min: or_nan(self.min),
+            max: or_nan(self.max),
+            mean: or_nan(self.mean),
+            std_dev: or_nan((self.m2 / self.count.max(1) as f64).sqrt()),
+            histogram: self.histogram,
+        }
+    }
+}
+
+/// 累加一个瓦片的有效像素
+///
+/// 边缘瓦片超出图像范围的填充像素不参与统计。`accumulators` 按顺序对应除 Alpha 外的波段,
+/// Alpha 为 0 的透明像素计为无数据
+fn accumulate_tile(
+    level: &Level,
+    index: usize,
+    tile: &Raster,
+    nodata: Option<f64>,
+    accumulators: &mut [BandAccumulator],
+) -> CloudTiffResult<()> {
+    let col_count = level.col_count();
+    let (col, row) = ((index % col_count) as u32, (index / col_count) as u32);
+    let valid_width = level
+        .width()
+        .saturating_sub(col * level.tile_width)
+        .min(level.tile_width) as usize;
+    let valid_height = level
+        .height()
+        .saturating_sub(row * level.tile_height)
+        .min(level.tile_height) as usize;
+    let tile_width = tile.dimensions.0 as usize;
+
+    let alpha = tile.alpha_band();
+    let opaque = match alpha {
+        Some(band) => Some(
+            tile.band::<f64>(band)?
+                .map(|value| value != 0.0)
+                .collect::<Vec<_>>(),
+        ),
+        None => None,
+    };
+    let bands = (0..tile.band_count()).filter(|band| Some(*band) != alpha);
+    for (band, accumulator) in bands.zip(accumulators.iter_mut()) {
+        for (i, value) in tile.band::<f64>(band)?.enumerate() {
+            if i % tile_width >= valid_width || i / tile_width >= valid_height {
+                continue;
+            }
+            match &opaque {
+                Some(opaque) if !opaque[i] => accumulator.nodata_count += 1,
+                _ => accumulator.add(value, nodata),
+            }
+        }
+    }
+    Ok(())
+}
+
+impl CloudTiff {
+    /// 按波段统计影像
+    ///
+    /// # 参数
+    /// * `reader` - 范围读取器
+    /// * `options` - 统计参数
+    ///
+    /// # 返回
+    /// 除 Alpha 外每个波段的统计结果
+    ///
+    /// # 错误
+    /// 瓦片读取或解压失败、样本类型不受支持时返回错误
+    pub fn statistics<R: ReadRange + Sync>(
+        &self,
+        reader: &R,
+        options: &StatisticsOptions,
+    ) -> CloudTiffResult<Vec<BandStatistics>> {
+        let level = self.statistics_level(options.mode);
+        let nodata = options.nodata.or(level.nodata);
+
+        // 没有直方图范围时先流式统计一遍确定数据范围,第二遍重新读取瓦片统计直方图
+        let range = match (options.histogram_range, self.type_range(level)) {
+            (Some(range), _) | (None, Some(range)) => range,
+            (None, None) => {
+                let rough = statistics_of_level(reader, level, nodata, (0.0, 0.0), options)?;
+                data_range(&rough)
+            }
+        };
+        let statistics = statistics_of_level(reader, level, nodata, range, options)?;
+        Ok(statistics
+            .into_iter()
+            .map(BandAccumulator::finish)
+            .collect())
+    }
+
+    /// 根据统计模式选择层级
+    fn statistics_level(&self, mode: StatisticsMode) -> &Level {
+        match mode {
+            StatisticsMode::Exact => &self.levels[0],
+            StatisticsMode::Approximate { min_pixels } => self
+                .levels
+                .iter()
+                .rev()
+                .find(|level| level.width() as u64 * level.height() as u64 >= min_pixels)
+                .unwrap_or(&self.levels[0]),
+        }
+    }
+
+    /// 8 位数据类型的完整取值范围
+    fn type_range(&self, level: &Level) -> Option<(f64, f64)> {
+        let bits = *level.bits_per_sample.first()?;
+        let format = level.sample_format.first().copied()?;
+        match SampleType::from_format(format, bits)? {
+            SampleType::U8 => Some((0.0, 255.0)),
+            SampleType::I8 => Some((-128.0, 127.0)),
+            _ => None,
+        }
+    }
+}
+
+/// 逐瓦片流式统计一个层级,直方图使用给定的范围
+fn statistics_of_level<R: ReadRange + Sync>(
+    reader: &R,
+    level: &Level,
+    nodata: Option<f64>,
+    range: (f64, f64),
+    options: &StatisticsOptions,
+) -> CloudTiffResult<Vec<BandAccumulator>> {
+    let tile_count = level.offsets.len();
+    let band_count = data_band_count(level);
+
+    // 读取并累加一个瓦片
+    let tile_statistics = |index: usize| -> CloudTiffResult<_> {
+        let tile = read_tile(reader, level, index)?;
+        let mut accumulators = empty_accumulators(band_count, range, options.bins);
+        accumulate_tile(level, index, &tile, nodata, &mut accumulators)?;
+        Ok(accumulators)
+    };
+    let empty = empty_accumulators(band_count, range, options.bins);
+    #[cfg(feature = "rayon")]
+    if options.parallel {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+        return (0..tile_count)
+            .into_par_iter()
+            .map(tile_statistics)
+            .try_reduce(|| empty.clone(), |a, b| Ok(merge_accumulators(a, b)));
+    }
+    (0..tile_count).try_fold(empty, |total, index| {
+        Ok(merge_accumulators(total, tile_statistics(index)?))
+    })
+}
+
+/// 读取并解压单个瓦片
+fn read_tile<R: ReadRange>(reader: &R, level: &Level, index: usize) -> CloudTiffResult<Raster> {
+    let (start, end) = level.tile_byte_range(index)?;
+    let bytes = reader.read_range_to_vec(start, end)?;
+    level.extract_tile_from_bytes(&bytes)
+}
+
+/// 除 Alpha 外参与统计的波段数
+fn data_band_count(level: &Level) -> usize {
+    let band_count = level.bits_per_sample.len();
+    match ExtraSamples::alpha_band(band_count, &level.extra_samples) {
+        Some(_) => band_count - 1,
+        None => band_count,
+    }
+}
+
+/// 创建空的累加器
+fn empty_accumulators(band_count: usize, range: (f64, f64), bins: usize) -> Vec<BandAccumulator> {
+    (0..band_count)
+        .map(|_| BandAccumulator::new(Histogram::new(range, bins)))
+        .collect()
+}
+
+/// 合并两组累加器
+fn merge_accumulators(a: Vec<BandAccumulator>, b: Vec<BandAccumulator>) -> Vec<BandAccumulator> {
+    a.into_iter().zip(b).map(|(a, b)| a.merge(b)).collect()
+}
+
+/// 所有波段有效数据的取值范围
+fn data_range(accumulators: &[BandAccumulator]) -> (f64, f64) {
+    let (min, max) = accumulators
+        .iter()
+        .filter(|a| a.count > 0)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), a| {
+            (min.min(a.min), max.max(a.max))
+        });
+    if min.is_finite() && max.is_finite() {
+        (min, max)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+#[cfg(feature = "async")]
+mod not_sync {
+    use super::*;
+    use crate::io::AsyncReadRange;
+    use crate::render::tiles::DEFAULT_MAX_IN_FLIGHT;
+    use futures::StreamExt;
+
+    impl CloudTiff {
+        /// 异步按波段统计影像
+        ///
+        /// 瓦片并发读取,同时进行的请求数由读取器的策略决定
+        ///
+        /// # 参数
+        /// * `reader` - 异步范围读取器
+        /// * `options` - 统计参数
+        ///
+        /// # 错误
+        /// 瓦片读取或解压失败、样本类型不受支持时返回错误
+        pub async fn statistics_async<R: AsyncReadRange>(
+            &self,
+            reader: &R,
+            options: &StatisticsOptions,
+        ) -> CloudTiffResult<Vec<BandStatistics>> {
+            let level = self.statistics_level(options.mode);
+            let nodata = options.nodata.or(level.nodata);
+
+            let range = match (options.histogram_range, self.type_range(level)) {
+                (Some(range), _) | (None, Some(range)) => range,
+                (None, None) => {
+                    let rough =
+                        statistics_of_level_async(reader, level, nodata, (0.0, 0.0), options)
+                            .await?;
+                    data_range(&rough)
+                }
+            };
+            let statistics =
+                statistics_of_level_async(reader, level, nodata, range, options).await?;
+            Ok(statistics
+                .into_iter()
+                .map(BandAccumulator::finish)
+                .collect())
+        }
+    }
+
+    /// 异步统计一个层级的全部瓦片
+    async fn statistics_of_level_async<R: AsyncReadRange>(
+        reader: &R,
+        level: &Level,
+        nodata: Option<f64>,
+        range: (f64, f64),
+        options: &StatisticsOptions,
+    ) -> CloudTiffResult<Vec<BandAccumulator>> {
+        let band_count = data_band_count(level);
+        let max_in_flight = reader
+            .max_in_flight()
+            .unwrap_or(DEFAULT_MAX_IN_FLIGHT)
+            .max(1);
+
+        // 并发读取瓦片,读取完成后立即统计,不保留瓦片数据
+        let mut results = futures::stream::iter(0..level.offsets.len())
+            .map(|index| async move {
+                let (start, end) = level.tile_byte_range(index)?;
+                let bytes = reader.read_range_to_vec_async(start, end).await?;
+                let tile = level.extract_tile_from_bytes(&bytes)?;
+                let mut accumulators = empty_accumulators(band_count, range, options.bins);
+                accumulate_tile(level, index, &tile, nodata, &mut accumulators)?;
+                CloudTiffResult::Ok(accumulators)
+            })
+            .buffer_unordered(max_in_flight);
+
+        let mut total = empty_accumulators(band_count, range, options.bins);
+        while let Some(result) = results.next().await {
+            total = merge_accumulators(total, result?);
+        }
+        Ok(total)
+    }
+}
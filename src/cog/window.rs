@@ -0,0 +1,85 @@
+//! 像素窗口读取模块
+//!
+//! 本模块提供了按整数像素范围读取指定层级原始像素的功能。
+//! 与渲染不同,窗口读取不进行任何重采样,结果与文件中的像素完全一致,
+//! 边缘的不完整分块只取图像范围内的部分。
+
+use super::{CloudTiff, CloudTiffResult};
+use crate::io::ReadRange;
+use crate::raster::Raster;
+use crate::render::tiles;
+use crate::Region;
+
+impl CloudTiff {
+    /// 读取指定层级的像素窗口
+    ///
+    /// # 参数
+    /// * `reader` - 范围读取器
+    /// * `level` - 层级索引,0 表示原始分辨率
+    /// * `window` - 像素窗口,`[min, max)` 区间,例如 `Region::new(x0, y0, x1, y1)`
+    ///
+    /// # 返回
+    /// 窗口尺寸的栅格,使用本机字节序
+    ///
+    /// # 错误
+    /// 层级不存在、窗口为空或超出层级范围、分块读取失败时返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use cloudtiff::Region;
+    /// use std::fs::File;
+    /// use std::sync::Mutex;
+    ///
+    /// # fn example(cog: &cloudtiff::CloudTiff) {
+    /// let reader = Mutex::new(File::open("image.tif").unwrap());
+    /// // 原始分辨率下 x 为 100..612, y 为 200..456 的像素
+    /// let raster = cog.read_window(&reader, 0, Region::new(100, 200, 612, 456)).unwrap();
+    /// # }
+    /// ```
+    pub fn read_window<R: ReadRange>(
+        &self,
+        reader: &R,
+        level: usize,
+        window: Region<u32>,
+    ) -> CloudTiffResult<Raster> {
+        let level = self.get_level(level)?;
+        level.check_window(&window)?;
+        let indices = level.tile_indices_within_window(&window);
+        let tile_cache = tiles::get_tiles_from(reader, level, indices)?;
+        level.window_from_tiles(&tile_cache, &window)
+    }
+}
+
+#[cfg(feature = "async")]
+mod not_sync {
+    use super::*;
+    use crate::io::AsyncReadRange;
+
+    impl CloudTiff {
+        /// 异步读取指定层级的像素窗口
+        ///
+        /// # 参数
+        /// * `reader` - 异步范围读取器
+        /// * `level` - 层级索引,0 表示原始分辨率
+        /// * `window` - 像素窗口,`[min, max)` 区间
+        ///
+        /// # 返回
+        /// 窗口尺寸的栅格,使用本机字节序
+        ///
+        /// # 错误
+        /// 层级不存在、窗口为空或超出层级范围、分块读取失败时返回错误
+        pub async fn read_window_async<R: AsyncReadRange>(
+            &self,
+            reader: &R,
+            level: usize,
+            window: Region<u32>,
+        ) -> CloudTiffResult<Raster> {
+            let level = self.get_level(level)?;
+            level.check_window(&window)?;
+            let indices = level.tile_indices_within_window(&window);
+            let tile_cache = tiles::get_tiles_from_async(reader, level, indices).await?;
+            level.window_from_tiles(&tile_cache, &window)
+        }
+    }
+}
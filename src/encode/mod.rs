@@ -51,9 +51,11 @@
 use crate::cog::{Compression, Predictor};
 use crate::geotags::{GeoKeyId, GeoKeyValue, GeoTags};
 use crate::raster::{PlanarConfiguration, Raster, ResizeFilter};
+use crate::render::warp::GeoRaster;
 use crate::tiff::{Endian, TagData, TagId, Tiff, TiffVariant};
 use crate::Region;
 use image::DynamicImage;
+use proj4rs::Proj;
 use std::io::{Seek, SeekFrom, Write};
 
 pub mod error;
@@ -173,6 +175,43 @@ impl Encoder {
         })
     }
 
+    /// 从栅格创建编码器,默认参数与 [`Encoder::from_image`] 相同
+    ///
+    /// # 参数
+    ///
+    /// * `raster` - 源栅格数据
+    pub fn from_raster(raster: Raster) -> Self {
+        Self {
+            raster,
+            projection: None,
+            endian: Endian::Little,
+            variant: TiffVariant::Big,
+            compression: SupportedCompression::Lzw,
+            tile_dimensions: (512, 512),
+            filter: ResizeFilter::Nearest,
+        }
+    }
+
+    /// 从带有地理参考的栅格(如重投影结果)创建编码器,并设置其投影信息
+    ///
+    /// # 参数
+    ///
+    /// * `geo_raster` - 带有地理参考的栅格
+    ///
+    /// # 错误
+    ///
+    /// 仿射变换包含旋转项时返回不支持的投影错误(GeoTIFF 写入仅支持定位点和像素比例)
+    pub fn from_geo_raster(geo_raster: GeoRaster) -> EncodeResult<Self> {
+        if geo_raster.geo_transform.is_rotated() {
+            return Err(EncodeError::UnsupportedProjection(
+                geo_raster.epsg,
+                "Rotated geo transforms are not supported".into(),
+            ));
+        }
+        let region = geo_raster.bounds();
+        Ok(Self::from_raster(geo_raster.raster).with_projection(geo_raster.epsg, region))
+    }
+
     /// 设置地理空间投影信息
     ///
     /// # 参数
@@ -383,11 +422,30 @@ impl Encoder {
                     GeoKeyValue::Short(vec![9001]),
                 );
             }
+            // 其他投影坐标系,线性单位为米
+            _ if Proj::from_epsg_code(epsg)
+                .is_ok_and(|proj| !proj.is_latlong() && proj.to_meter() == 1.0) =>
+            {
+                // 设置模型类型为投影坐标系
+                geo.set_key(GeoKeyId::GTModelTypeGeoKey, GeoKeyValue::Short(vec![1]));
+                // 设置栅格类型为像素表示投影坐标
+                geo.set_key(GeoKeyId::GTRasterTypeGeoKey, GeoKeyValue::Short(vec![1]));
+                // 设置投影坐标系统
+                geo.set_key(
+                    GeoKeyId::ProjectedCSTypeGeoKey,
+                    GeoKeyValue::Short(vec![epsg]),
+                );
+                // 设置投影线性单位为米
+                geo.set_key(
+                    GeoKeyId::ProjLinearUnitsGeoKey,
+                    GeoKeyValue::Short(vec![9001]),
+                );
+            }
             // 不支持的投影系统
             _ => {
                 return Err(EncodeError::UnsupportedProjection(
                     epsg,
-                    "Only EPSG 4326 supported at this time".into(),
+                    "Only EPSG 4326 and metric projected coordinate systems are supported".into(),
                 ))
             }
         }
@@ -564,7 +622,9 @@ impl Encoder {
                     );
 
                     // 从图像中提取瓦片区域
-                    let tile_raster = img.get_region(region)?;
+                    let mut tile_raster = img.get_region(region)?;
+                    // 按文件的字节序写入样本
+                    tile_raster.convert_endian(endian);
 
                     // 压缩瓦片数据并写入文件
                     let tile_bytes = compression.encode(&tile_raster.buffer[..])?;
@@ -0,0 +1,219 @@
+//! MBTiles 写入模块
+//!
+//! MBTiles 是存储瓦片的 SQLite 数据库,瓦片行号使用 TMS 方案(原点位于南侧),
+//! 写入时由 XYZ 行号翻转得到。每次持久化提交一个事务,中断后已提交的瓦片可以续传。
+
+use super::{ExportError, TileFormat, TileIndex, TileSink, TilesetMetadata};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// MBTiles 写入器
+pub struct MbTilesWriter {
+    /// 数据库连接
+    connection: Connection,
+}
+
+impl MbTilesWriter {
+    /// 打开或创建 MBTiles 文件
+    ///
+    /// # 参数
+    /// * `path` - MBTiles 文件路径,文件已存在时保留其中的瓦片用于续传
+    ///
+    /// # 错误
+    /// 数据库无法打开或建表失败时返回错误
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ExportError> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS metadata (name TEXT, value TEXT);
+             CREATE UNIQUE INDEX IF NOT EXISTS metadata_name ON metadata (name);
+             CREATE TABLE IF NOT EXISTS tiles (
+                 zoom_level INTEGER, tile_column INTEGER, tile_row INTEGER, tile_data BLOB
+             );
+             CREATE UNIQUE INDEX IF NOT EXISTS tile_index
+                 ON tiles (zoom_level, tile_column, tile_row);",
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// 在需要时开始事务
+    fn begin(&self) -> Result<(), ExportError> {
+        if self.connection.is_autocommit() {
+            self.connection.execute_batch("BEGIN")?;
+        }
+        Ok(())
+    }
+}
+
+/// XYZ 与 TMS 行号互相转换,行号超出该级别范围时返回 None
+fn flip_row(z: u32, row: u32) -> Option<u32> {
+    1_u32.checked_shl(z)?.checked_sub(1)?.checked_sub(row)
+}
+
+impl TileSink for MbTilesWriter {
+    fn start(&mut self, format: TileFormat, tile_size: u32) -> Result<(), ExportError> {
+        // 瓦片格式和大小在创建时写入元数据,JPEG 还记录质量
+        let mut entries = vec![
+            ("format", format.extension().to_string()),
+            ("tile_size", tile_size.to_string()),
+        ];
+        if let TileFormat::Jpeg(quality) = format {
+            entries.push(("jpeg_quality", quality.to_string()));
+        }
+        let has_tiles =
+            self.connection
+                .query_row("SELECT EXISTS (SELECT 1 FROM tiles)", [], |row| row.get(0))?;
+        self.begin()?;
+        for (name, value) in entries {
+            let existing: Option<String> = self
+                .connection
+                .query_row(
+                    "SELECT value FROM metadata WHERE name = ?1",
+                    params![name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match existing {
+                Some(existing) if existing != value => {
+                    return Err(ExportError::InvalidArchive(format!(
+                        "元数据 {name} 为 {existing},与导出设置 {value} 不一致"
+                    )));
+                }
+                Some(_) => {}
+                None if has_tiles => {
+                    return Err(ExportError::InvalidArchive(format!(
+                        "已有瓦片但缺少元数据 {name}"
+                    )));
+                }
+                None => {
+                    self.connection.execute(
+                        "INSERT INTO metadata (name, value) VALUES (?1, ?2)",
+                        params![name, value],
+                    )?;
+                }
+            }
+        }
+        self.flush()
+    }
+
+    fn existing_tiles(&mut self) -> Result<HashSet<TileIndex>, ExportError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT zoom_level, tile_column, tile_row FROM tiles")?;
+        let mut tiles = HashSet::new();
+        for tile in statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))? {
+            // 跳过行号超出范围的瓦片,它们不在导出的瓦片树中
+            let (z, x, y) = tile?;
+            if let Some(y) = flip_row(z, y) {
+                tiles.insert((z, x, y));
+            }
+        }
+        Ok(tiles)
+    }
+
+    fn write_tile(&mut self, (z, x, y): TileIndex, data: &[u8]) -> Result<(), ExportError> {
+        let row = flip_row(z, y)
+            .ok_or_else(|| ExportError::InvalidTile(format!("行号超出范围: {z}/{x}/{y}")))?;
+        self.begin()?;
+        self.connection.execute(
+            "INSERT OR REPLACE INTO tiles (zoom_level, tile_column, tile_row, tile_data)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![z, x, row, data],
+        )?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ExportError> {
+        if !self.connection.is_autocommit() {
+            self.connection.execute_batch("COMMIT")?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, metadata: &TilesetMetadata) -> Result<(), ExportError> {
+        let (west, south, east, north) = metadata.wrapped_bounds();
+        let (lon, lat, zoom) = metadata.center();
+        let entries = [
+            ("name", metadata.name.clone()),
+            ("format", metadata.format.extension().to_string()),
+            ("bounds", format!("{west},{south},{east},{north}")),
+            ("center", format!("{lon},{lat},{zoom}")),
+            ("minzoom", metadata.min_zoom.to_string()),
+            ("maxzoom", metadata.max_zoom.to_string()),
+            ("type", "overlay".to_string()),
+            ("version", "1.3".to_string()),
+        ];
+        self.begin()?;
+        for (name, value) in entries {
+            self.connection.execute(
+                "INSERT OR REPLACE INTO metadata (name, value) VALUES (?1, ?2)",
+                params![name, value],
+            )?;
+        }
+        self.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flip_rows() {
+        assert_eq!(flip_row(0, 0), Some(0));
+        assert_eq!(flip_row(1, 0), Some(1));
+        assert_eq!(flip_row(3, 2), Some(5));
+        assert_eq!(flip_row(31, 0), Some((1 << 31) - 1));
+        assert_eq!(flip_row(2, 4), None);
+        assert_eq!(flip_row(32, 0), None);
+    }
+
+    #[test]
+    fn rows_are_stored_as_tms() {
+        let mut writer = MbTilesWriter::open(":memory:").unwrap();
+        writer.start(TileFormat::Png, 256).unwrap();
+        writer.write_tile((2, 1, 0), b"tile").unwrap();
+        writer.flush().unwrap();
+
+        let row: u32 = writer
+            .connection
+            .query_row("SELECT tile_row FROM tiles", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(row, 3);
+
+        // 行号超出范围的瓦片被跳过
+        writer
+            .connection
+            .execute(
+                "INSERT INTO tiles VALUES (1, 0, 5, x''), (40, 0, 0, x'')",
+                [],
+            )
+            .unwrap();
+        let expected: HashSet<TileIndex> = [(2, 1, 0)].into();
+        assert_eq!(writer.existing_tiles().unwrap(), expected);
+        assert!(matches!(
+            writer.write_tile((1, 0, 2), b"tile"),
+            Err(ExportError::InvalidTile(_))
+        ));
+    }
+
+    #[test]
+    fn resume_checks_format() {
+        let mut writer = MbTilesWriter::open(":memory:").unwrap();
+        writer.start(TileFormat::Jpeg(80), 256).unwrap();
+        writer.write_tile((0, 0, 0), b"tile").unwrap();
+        writer.flush().unwrap();
+
+        writer.start(TileFormat::Jpeg(80), 256).unwrap();
+        for (format, tile_size) in [
+            (TileFormat::Jpeg(90), 256),
+            (TileFormat::Png, 256),
+            (TileFormat::Jpeg(80), 512),
+        ] {
+            assert!(matches!(
+                writer.start(format, tile_size),
+                Err(ExportError::InvalidArchive(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,478 @@
+//! 瓦片包导出模块
+//!
+//! 本模块提供了将 COG 导出为离线瓦片包的功能:
+//! - 按 [`wmts::tile_tree_indices`] 遍历影像覆盖的所有 Web 墨卡托 z/x/y 瓦片
+//! - 每个瓦片渲染后编码为 PNG、JPEG 或 WebP
+//! - 输出到 MBTiles(需要 `mbtiles` 特性)或 PMTiles v3(需要 `pmtiles` 特性)
+//! - 使用 rayon 并行渲染(需要 `rayon` 特性)
+//! - 支持断点续传:再次导出到同一目标时跳过已写入的瓦片,瓦片格式和大小必须与此前一致
+//!
+//! 输出目标通过 [`TileSink`] 抽象,可以实现该特征写入其他存储。
+
+use crate::cog::{CloudTiff, CloudTiffError};
+use crate::io::ReadRange;
+use crate::raster::{Colormap, Raster, Stretch, Terrain};
+use crate::render::cutline::Cutline;
+use crate::render::resample::Resampling;
+use crate::render::wmts;
+use crate::Region;
+use image::DynamicImage;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Cursor};
+use std::sync::Arc;
+
+#[cfg(feature = "mbtiles")]
+mod mbtiles;
+#[cfg(feature = "pmtiles")]
+mod pmtiles;
+
+#[cfg(feature = "mbtiles")]
+pub use mbtiles::MbTilesWriter;
+#[cfg(feature = "pmtiles")]
+pub use pmtiles::PmTilesWriter;
+
+/// 每批并行渲染的瓦片数,每批完成后持久化一次
+const BATCH_SIZE: usize = 256;
+
+/// 瓦片索引 (z, x, y)
+pub type TileIndex = (u32, u32, u32);
+
+/// 导出错误
+#[derive(Debug)]
+pub enum ExportError {
+    /// 文件读写错误
+    Io(io::Error),
+    /// 瓦片渲染错误
+    Render(CloudTiffError),
+    /// 图像编码错误,包含错误描述
+    Encode(String),
+    /// SQLite 数据库错误
+    #[cfg(feature = "mbtiles")]
+    Sqlite(rusqlite::Error),
+    /// 续传用的中间文件无效,包含错误描述
+    InvalidArchive(String),
+    /// 瓦片索引无效,包含错误描述
+    InvalidTile(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<CloudTiffError> for ExportError {
+    fn from(e: CloudTiffError) -> Self {
+        ExportError::Render(e)
+    }
+}
+
+#[cfg(feature = "mbtiles")]
+impl From<rusqlite::Error> for ExportError {
+    fn from(e: rusqlite::Error) -> Self {
+        ExportError::Sqlite(e)
+    }
+}
+
+/// 瓦片图像格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TileFormat {
+    /// PNG,支持 8/16 位和透明度
+    Png,
+    /// JPEG,包含质量(1~100),透明度会被丢弃
+    Jpeg(u8),
+    /// 无损 WebP,支持透明度,16 位数据会转换为 8 位
+    Webp,
+}
+
+impl TileFormat {
+    /// 文件扩展名,同时用作 MBTiles 的 `format` 元数据
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TileFormat::Png => "png",
+            TileFormat::Jpeg(_) => "jpg",
+            TileFormat::Webp => "webp",
+        }
+    }
+
+    /// MIME 类型
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            TileFormat::Png => "image/png",
+            TileFormat::Jpeg(_) => "image/jpeg",
+            TileFormat::Webp => "image/webp",
+        }
+    }
+
+    /// 将渲染结果编码为图像
+    ///
+    /// # 参数
+    /// * `raster` - 渲染得到的瓦片栅格,浮点数据需要先进行拉伸或颜色映射
+    ///
+    /// # 错误
+    /// 栅格格式无法转换为图像或编码失败时返回错误
+    pub fn encode(&self, raster: Raster) -> Result<Vec<u8>, ExportError> {
+        let img = raster.into_image().map_err(ExportError::Encode)?;
+        let mut buffer = Cursor::new(Vec::new());
+        let result = match self {
+            TileFormat::Png => img.write_to(&mut buffer, image::ImageFormat::Png),
+            TileFormat::Jpeg(quality) => {
+                // JPEG 只支持 8 位灰度和 RGB
+                let img = match img {
+                    DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_) => img,
+                    DynamicImage::ImageLuma16(_) | DynamicImage::ImageLumaA8(_) => {
+                        DynamicImage::ImageLuma8(img.to_luma8())
+                    }
+                    _ => DynamicImage::ImageRgb8(img.to_rgb8()),
+                };
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, *quality)
+                    .encode_image(&img)
+            }
+            TileFormat::Webp => {
+                // 无损 WebP 只支持 8 位 RGB 和 RGBA
+                let img = if img.color().has_alpha() {
+                    DynamicImage::ImageRgba8(img.to_rgba8())
+                } else {
+                    DynamicImage::ImageRgb8(img.to_rgb8())
+                };
+                img.write_to(&mut buffer, image::ImageFormat::WebP)
+            }
+        };
+        result.map_err(|e| ExportError::Encode(e.to_string()))?;
+        Ok(buffer.into_inner())
+    }
+}
+
+/// 瓦片集元数据,在导出完成时写入瓦片包
+#[derive(Clone, Debug, PartialEq)]
+pub struct TilesetMetadata {
+    /// 瓦片集名称
+    pub name: String,
+    /// 瓦片图像格式
+    pub format: TileFormat,
+    /// 经纬度边界(度)
+    pub bounds: Region<f64>,
+    /// 最小缩放级别
+    pub min_zoom: u32,
+    /// 最大缩放级别
+    pub max_zoom: u32,
+}
+
+impl TilesetMetadata {
+    /// 写入瓦片包的经纬度边界 (西, 南, 东, 北)
+    ///
+    /// 跨越 ±180° 经线时东边界折回 [-180, 180],西边界大于东边界
+    pub fn wrapped_bounds(&self) -> (f64, f64, f64, f64) {
+        self.bounds.wrapped_bounds(180.0)
+    }
+
+    /// 默认视图的中心 (经度, 纬度, 缩放级别),经度位于 [-180, 180]
+    pub fn center(&self) -> (f64, f64, u32) {
+        let center = self.bounds.wrapped_center(180.0);
+        (center.x, center.y, self.min_zoom)
+    }
+}
+
+/// 瓦片包的写入目标
+///
+/// 导出器按批写入瓦片并在每批后调用 [`TileSink::flush`],
+/// 中断后再次导出时通过 [`TileSink::existing_tiles`] 跳过已持久化的瓦片
+pub trait TileSink {
+    /// 开始导出
+    ///
+    /// 新建的瓦片包记录瓦片格式和大小,续传时检查它们与已写入的瓦片一致
+    ///
+    /// # 错误
+    /// 瓦片格式或大小与已写入的瓦片不一致时返回 [`ExportError::InvalidArchive`]
+    fn start(&mut self, format: TileFormat, tile_size: u32) -> Result<(), ExportError>;
+
+    /// 已写入并持久化的瓦片索引
+    fn existing_tiles(&mut self) -> Result<HashSet<TileIndex>, ExportError>;
+
+    /// 写入一个编码后的瓦片
+    ///
+    /// # 参数
+    /// * `tile` - 瓦片索引 (z, x, y),y 轴原点位于北侧(XYZ 方案)
+    /// * `data` - 编码后的图像数据
+    fn write_tile(&mut self, tile: TileIndex, data: &[u8]) -> Result<(), ExportError>;
+
+    /// 持久化此前写入的瓦片
+    fn flush(&mut self) -> Result<(), ExportError>;
+
+    /// 写入元数据并完成瓦片包
+    fn finish(&mut self, metadata: &TilesetMetadata) -> Result<(), ExportError>;
+}
+
+/// 导出结果统计
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    /// 瓦片树中的瓦片总数
+    pub total: usize,
+    /// 本次渲染并写入的瓦片数
+    pub written: usize,
+    /// 因已存在而跳过的瓦片数
+    pub skipped: usize,
+}
+
+/// 瓦片包导出器
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::export::{TileExporter, TileFormat};
+/// use std::fs::File;
+/// use std::sync::{Arc, Mutex};
+///
+/// # fn example(cog: &cloudtiff::CloudTiff) {
+/// let reader = Arc::new(Mutex::new(File::open("image.tif").unwrap()));
+/// let summary = TileExporter::new(cog, reader)
+///     .with_format(TileFormat::Webp)
+///     .export_pmtiles("image.pmtiles")
+///     .unwrap();
+/// println!("写入 {} 个瓦片", summary.written);
+/// # }
+/// ```
+pub struct TileExporter<'a, R> {
+    /// COG 影像引用
+    cog: &'a CloudTiff,
+    /// 共享的范围读取器
+    reader: Arc<R>,
+    /// 瓦片集名称
+    name: String,
+    /// 瓦片图像格式
+    format: TileFormat,
+    /// 瓦片边长(像素)
+    tile_size: u32,
+    /// 缩放级别范围,None 时按影像分辨率计算
+    zoom_range: Option<(u32, u32)>,
+    /// 重采样方法
+    resampling: Resampling,
+    /// 对比度拉伸
+    stretch: Option<Stretch>,
+    /// 地形分析
+    terrain: Option<Terrain>,
+    /// 颜色映射
+    colormap: Option<Colormap>,
+    /// 近似变换的误差容限(源像素)
+    transform_tolerance: Option<f64>,
+    /// 裁切多边形
+    cutline: Option<Cutline>,
+    /// 是否并行渲染
+    parallel: bool,
+}
+
+impl<'a, R: ReadRange + Send + Sync + 'static> TileExporter<'a, R> {
+    /// 创建导出器,默认输出 256 像素的 PNG 瓦片并使用平均值重采样
+    ///
+    /// # 参数
+    /// * `cog` - COG 影像
+    /// * `reader` - 共享的范围读取器
+    pub fn new(cog: &'a CloudTiff, reader: Arc<R>) -> Self {
+        Self {
+            cog,
+            reader,
+            name: String::new(),
+            format: TileFormat::Png,
+            tile_size: 256,
+            zoom_range: None,
+            resampling: Resampling::Average,
+            stretch: None,
+            terrain: None,
+            colormap: None,
+            transform_tolerance: None,
+            cutline: None,
+            parallel: true,
+        }
+    }
+
+    /// 设置瓦片集名称
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// 设置瓦片图像格式
+    pub fn with_format(mut self, format: TileFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 设置瓦片边长(像素)
+    pub fn with_tile_size(mut self, tile_size: u32) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+
+    /// 设置缩放级别范围,默认按影像范围和分辨率计算
+    pub fn with_zoom_range(mut self, min_zoom: u32, max_zoom: u32) -> Self {
+        self.zoom_range = Some((min_zoom, max_zoom));
+        self
+    }
+
+    /// 设置重采样方法
+    pub fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    /// 设置对比度拉伸,参见 [`crate::render::RenderBuilder::with_stretch`]
+    pub fn with_stretch(mut self, stretch: Stretch) -> Self {
+        self.stretch = Some(stretch);
+        self
+    }
+
+    /// 设置地形分析,参见 [`crate::render::RenderBuilder::with_terrain`]
+    pub fn with_terrain(mut self, terrain: Terrain) -> Self {
+        self.terrain = Some(terrain);
+        self
+    }
+
+    /// 设置颜色映射,参见 [`crate::render::RenderBuilder::with_colormap`]
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = Some(colormap);
+        self
+    }
+
+    /// 使用近似变换,参见 [`crate::render::RenderBuilder::with_approximate_transform`]
+    pub fn with_approximate_transform(mut self, tolerance: f64) -> Self {
+        self.transform_tolerance = Some(tolerance);
+        self
+    }
+
+    /// 设置裁切多边形,参见 [`crate::render::RenderBuilder::with_cutline`]
+    pub fn with_cutline(mut self, cutline: Cutline) -> Self {
+        self.cutline = Some(cutline);
+        self
+    }
+
+    /// 设置是否并行渲染(需要 `rayon` 特性),默认开启
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// 导出到 MBTiles 文件,文件已存在时续传
+    ///
+    /// # 错误
+    /// 数据库读写、瓦片渲染或编码失败时返回错误
+    #[cfg(feature = "mbtiles")]
+    pub fn export_mbtiles(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<ExportSummary, ExportError> {
+        let mut sink = MbTilesWriter::open(path)?;
+        self.export(&mut sink)
+    }
+
+    /// 导出到 PMTiles v3 文件,存在未完成的中间文件时续传
+    ///
+    /// # 错误
+    /// 文件读写、瓦片渲染或编码失败时返回错误
+    #[cfg(feature = "pmtiles")]
+    pub fn export_pmtiles(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<ExportSummary, ExportError> {
+        let mut sink = PmTilesWriter::open(path)?;
+        self.export(&mut sink)
+    }
+
+    /// 导出到任意写入目标
+    ///
+    /// 跳过目标中已存在的瓦片,其余瓦片分批渲染、编码并写入,每批写入后持久化
+    ///
+    /// # 参数
+    /// * `sink` - 瓦片包写入目标
+    ///
+    /// # 错误
+    /// 写入、瓦片渲染或编码失败时返回错误,已持久化的瓦片可以续传
+    pub fn export<S: TileSink>(&self, sink: &mut S) -> Result<ExportSummary, ExportError> {
+        let bounds = self.cog.bounds_lat_lon_deg()?;
+        let tile_dim = (self.tile_size, self.tile_size);
+        let (z0_bounds, zoom_range) =
+            wmts::bounds_wmts(bounds, self.cog.full_dimensions(), tile_dim);
+        let zoom_range = self.zoom_range.unwrap_or(zoom_range);
+        let tiles = match self.zoom_range {
+            Some(zoom_range) => wmts::tile_indices_in_zoom_range(z0_bounds, zoom_range),
+            None => wmts::tile_tree_indices(bounds, self.cog.full_dimensions(), tile_dim),
+        };
+
+        sink.start(self.format, self.tile_size)?;
+        let existing = sink.existing_tiles()?;
+        let pending: Vec<TileIndex> = tiles
+            .iter()
+            .map(|&(x, y, z)| (z, x, y))
+            .filter(|tile| !existing.contains(tile))
+            .collect();
+        let mut summary = ExportSummary {
+            total: tiles.len(),
+            written: 0,
+            skipped: tiles.len() - pending.len(),
+        };
+
+        for batch in pending.chunks(BATCH_SIZE) {
+            for (tile, data) in batch.iter().zip(self.render_batch(batch)?) {
+                sink.write_tile(*tile, &data)?;
+            }
+            sink.flush()?;
+            summary.written += batch.len();
+        }
+
+        sink.finish(&TilesetMetadata {
+            name: self.name.clone(),
+            format: self.format,
+            bounds,
+            min_zoom: zoom_range.0,
+            max_zoom: zoom_range.1,
+        })?;
+        Ok(summary)
+    }
+
+    /// 渲染并编码一批瓦片
+    fn render_batch(&self, batch: &[TileIndex]) -> Result<Vec<Vec<u8>>, ExportError> {
+        #[cfg(feature = "rayon")]
+        if self.parallel {
+            use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+            return batch
+                .par_iter()
+                .map(|tile| self.render_tile(*tile))
+                .collect();
+        }
+        batch.iter().map(|tile| self.render_tile(*tile)).collect()
+    }
+
+    /// 渲染并编码单个瓦片
+    fn render_tile(&self, (z, x, y): TileIndex) -> Result<Vec<u8>, ExportError> {
+        let mut builder = self
+            .cog
+            .renderer()
+            .with_arc_range_reader(self.reader.clone())
+            .with_resampling(self.resampling);
+        if let Some(stretch) = &self.stretch {
+            builder = builder.with_stretch(stretch.clone());
+        }
+        if let Some(terrain) = self.terrain {
+            builder = builder.with_terrain(terrain);
+        }
+        if let Some(colormap) = &self.colormap {
+            builder = builder.with_colormap(colormap.clone());
+        }
+        if let Some(tolerance) = self.transform_tolerance {
+            builder = builder.with_approximate_transform(tolerance);
+        }
+        if let Some(cutline) = &self.cutline {
+            builder = builder.with_cutline(cutline.clone());
+        }
+        let raster = builder.render_tile(z, x, y, self.tile_size)?;
+        self.format.encode(raster)
+    }
+}
@@ -0,0 +1,786 @@
+//! PMTiles v3 写入模块
+//!
+//! PMTiles 是单文件的瓦片归档,目录按希尔伯特曲线瓦片 ID 排序,因此只能在所有瓦片写入后生成。
+//! 导出时瓦片先追加到两个中间文件(瓦片数据和索引),完成时按瓦片 ID 排序组装归档:
+//! 头部、根目录、元数据、叶目录、瓦片数据。中断后再次打开会读取中间文件续传,
+//! 索引文件开头记录了瓦片格式和大小,续传时检查与导出设置一致。
+//!
+//! 相邻瓦片 ID 内容相同时(例如大片空白瓦片)合并为一个游程条目,只存储一份数据。
+
+use super::{ExportError, TileFormat, TileIndex, TileSink, TilesetMetadata};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// 头部长度(字节)
+const HEADER_LEN: usize = 127;
+
+/// 头部和根目录必须位于文件的前 16 KiB 内
+const MAX_ROOT_LEN: usize = 16384 - HEADER_LEN;
+
+/// 索引记录长度:z、x、y、数据偏移、数据长度
+const RECORD_LEN: usize = 24;
+
+/// 索引文件头部的魔数
+const SPOOL_MAGIC: &[u8; 4] = b"CTPS";
+
+/// 索引文件头部长度:魔数、瓦片类型、JPEG 质量、瓦片大小
+const SPOOL_HEADER_LEN: usize = 10;
+
+/// 目录和元数据使用 gzip 压缩
+const COMPRESSION_GZIP: u8 = 2;
+
+/// 瓦片数据不再压缩(图像本身已压缩)
+const COMPRESSION_NONE: u8 = 1;
+
+/// 中间文件中的一个瓦片
+#[derive(Clone, Copy, Debug)]
+struct Record {
+    /// 瓦片索引 (z, x, y)
+    tile: TileIndex,
+    /// 在中间数据文件中的偏移
+    offset: u64,
+    /// 数据长度
+    length: u32,
+}
+
+impl Record {
+    /// 从索引文件的字节解析记录
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        Self {
+            tile: (u32_at(0), u32_at(4), u32_at(8)),
+            offset: u64::from_le_bytes(bytes[12..20].try_into().unwrap()),
+            length: u32_at(20),
+        }
+    }
+
+    /// 转换为索引文件的字节
+    fn to_bytes(self) -> [u8; RECORD_LEN] {
+        let mut bytes = [0; RECORD_LEN];
+        bytes[0..4].copy_from_slice(&self.tile.0.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.tile.1.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.tile.2.to_le_bytes());
+        bytes[12..20].copy_from_slice(&self.offset.to_le_bytes());
+        bytes[20..24].copy_from_slice(&self.length.to_le_bytes());
+        bytes
+    }
+}
+
+/// 目录条目
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    /// 起始瓦片 ID
+    tile_id: u64,
+    /// 数据偏移(瓦片数据或叶目录区内)
+    offset: u64,
+    /// 数据长度
+    length: u32,
+    /// 游程长度,0 表示指向叶目录
+    run_length: u32,
+}
+
+/// 中间文件
+struct Spool {
+    /// 瓦片数据
+    data: BufWriter<File>,
+    /// 瓦片索引
+    index: BufWriter<File>,
+}
+
+/// PMTiles v3 写入器
+pub struct PmTilesWriter {
+    /// 归档路径
+    path: PathBuf,
+    /// 中间文件,完成后为 None
+    spool: Option<Spool>,
+    /// 已写入的瓦片
+    records: Vec<Record>,
+    /// 中间数据文件的长度
+    data_len: u64,
+    /// 中间文件的瓦片格式和大小,开始导出前为 None
+    format: Option<(TileFormat, u32)>,
+}
+
+impl PmTilesWriter {
+    /// 创建 PMTiles 写入器
+    ///
+    /// 中间文件位于归档旁(`.tiles.tmp` 和 `.index.tmp` 后缀),存在时读取其中的瓦片用于续传
+    ///
+    /// # 参数
+    /// * `path` - 归档路径
+    ///
+    /// # 错误
+    /// 中间文件无法打开、读取或不是本写入器生成时返回错误
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ExportError> {
+        let path = path.as_ref().to_path_buf();
+        let open = |path: &Path| {
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(path)
+        };
+        let mut data = open(&with_suffix(&path, ".tiles.tmp"))?;
+        let mut index = open(&with_suffix(&path, ".index.tmp"))?;
+
+        // 头部不完整时中间文件中还没有瓦片
+        let mut bytes = vec![];
+        index.read_to_end(&mut bytes)?;
+        let format = match bytes.get(..SPOOL_HEADER_LEN) {
+            Some(header) => Some(parse_spool_header(header)?),
+            None => None,
+        };
+
+        // 读取完整的索引记录,丢弃中断时写了一半或数据不完整的记录
+        let data_file_len = data.metadata()?.len();
+        let records: Vec<Record> = match format {
+            Some(_) => bytes[SPOOL_HEADER_LEN..]
+                .chunks_exact(RECORD_LEN)
+                .map(Record::from_bytes)
+                .take_while(|r| r.offset + r.length as u64 <= data_file_len)
+                .collect(),
+            None => vec![],
+        };
+        let data_len = records.last().map_or(0, |r| r.offset + r.length as u64);
+        let header_len = if format.is_some() {
+            SPOOL_HEADER_LEN
+        } else {
+            0
+        };
+        index.set_len((header_len + records.len() * RECORD_LEN) as u64)?;
+        data.set_len(data_len)?;
+        index.seek(SeekFrom::End(0))?;
+        data.seek(SeekFrom::End(0))?;
+
+        Ok(Self {
+            path,
+            spool: Some(Spool {
+                data: BufWriter::new(data),
+                index: BufWriter::new(index),
+            }),
+            records,
+            data_len,
+            format,
+        })
+    }
+
+    /// 获取中间文件
+    fn spool(&mut self) -> Result<&mut Spool, ExportError> {
+        self.spool
+            .as_mut()
+            .ok_or_else(|| ExportError::InvalidArchive("归档已完成".to_string()))
+    }
+
+    /// 组装归档
+    fn write_archive(&self, metadata: &TilesetMetadata) -> Result<(), ExportError> {
+        // 按瓦片 ID 排序,同一瓦片多次写入时使用最后一次
+        let tiles: BTreeMap<u64, Record> = self
+            .records
+            .iter()
+            .map(|r| (tile_id(r.tile.0, r.tile.1, r.tile.2), *r))
+            .collect();
+
+        // 第一遍:计算目录条目和需要复制的瓦片数据
+        let mut data = File::open(with_suffix(&self.path, ".tiles.tmp"))?;
+        let mut entries: Vec<Entry> = vec![];
+        let mut contents: Vec<Record> = vec![];
+        let mut previous: Vec<u8> = vec![];
+        let mut tile_data_len = 0;
+        for (&id, record) in &tiles {
+            let content = read_record(&mut data, record)?;
+            match entries.last_mut() {
+                Some(last) if content == previous => {
+                    if id == last.tile_id + last.run_length as u64 {
+                        last.run_length += 1;
+                    } else {
+                        let (offset, length) = (last.offset, last.length);
+                        entries.push(Entry {
+                            tile_id: id,
+                            offset,
+                            length,
+                            run_length: 1,
+                        });
+                    }
+                }
+                _ => {
+                    entries.push(Entry {
+                        tile_id: id,
+                        offset: tile_data_len,
+                        length: record.length,
+                        run_length: 1,
+                    });
+                    contents.push(*record);
+                    tile_data_len += record.length as u64;
+                    previous = content;
+                }
+            }
+        }
+
+        let (root, leaves) = build_directories(&entries)?;
+        let metadata_json = gzip(metadata_json(metadata).as_bytes())?;
+
+        // 各部分依次排列:头部、根目录、元数据、叶目录、瓦片数据
+        let root_offset = HEADER_LEN as u64;
+        let metadata_offset = root_offset + root.len() as u64;
+        let leaves_offset = metadata_offset + metadata_json.len() as u64;
+        let tile_data_offset = leaves_offset + leaves.len() as u64;
+        let (center_lon, center_lat, center_zoom) = metadata.center();
+        let header = Header {
+            root: (root_offset, root.len() as u64),
+            metadata: (metadata_offset, metadata_json.len() as u64),
+            leaves: (leaves_offset, leaves.len() as u64),
+            tile_data: (tile_data_offset, tile_data_len),
+            addressed_tiles: tiles.len() as u64,
+            tile_entries: entries.len() as u64,
+            tile_contents: contents.len() as u64,
+            tile_type: tile_type(metadata.format),
+            zoom: (metadata.min_zoom as u8, metadata.max_zoom as u8),
+            bounds: metadata.wrapped_bounds(),
+            center: (center_zoom as u8, center_lon, center_lat),
+        };
+
+        // 先写入临时文件,完成后替换
+        let part_path = with_suffix(&self.path, ".part");
+        let mut out = BufWriter::new(File::create(&part_path)?);
+        out.write_all(&header.to_bytes())?;
+        out.write_all(&root)?;
+        out.write_all(&metadata_json)?;
+        out.write_all(&leaves)?;
+        // 第二遍:复制瓦片数据
+        for record in &contents {
+            out.write_all(&read_record(&mut data, record)?)?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&part_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl TileSink for PmTilesWriter {
+    fn start(&mut self, format: TileFormat, tile_size: u32) -> Result<(), ExportError> {
+        match self.format {
+            Some((existing, existing_size)) if (existing, existing_size) != (format, tile_size) => {
+                Err(ExportError::InvalidArchive(format!(
+                    "中间文件的瓦片格式 {existing:?} 和大小 {existing_size} 与导出设置不一致"
+                )))
+            }
+            Some(_) => Ok(()),
+            None => {
+                // 新建的中间文件先写入并持久化头部
+                let spool = self.spool()?;
+                spool.index.write_all(&spool_header(format, tile_size))?;
+                spool.index.flush()?;
+                spool.index.get_ref().sync_data()?;
+                self.format = Some((format, tile_size));
+                Ok(())
+            }
+        }
+    }
+
+    fn existing_tiles(&mut self) -> Result<HashSet<TileIndex>, ExportError> {
+        Ok(self.records.iter().map(|r| r.tile).collect())
+    }
+
+    fn write_tile(&mut self, tile: TileIndex, data: &[u8]) -> Result<(), ExportError> {
+        if self.format.is_none() {
+            return Err(ExportError::InvalidArchive("尚未开始导出".to_string()));
+        }
+        let record = Record {
+            tile,
+            offset: self.data_len,
+            length: data.len() as u32,
+        };
+        let spool = self.spool()?;
+        spool.data.write_all(data)?;
+        spool.index.write_all(&record.to_bytes())?;
+        self.data_len += data.len() as u64;
+        self.records.push(record);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ExportError> {
+        let spool = self.spool()?;
+        // 先持久化数据再持久化索引,索引不会指向不存在的数据
+        spool.data.flush()?;
+        spool.data.get_ref().sync_data()?;
+        spool.index.flush()?;
+        spool.index.get_ref().sync_data()?;
+        Ok(())
+    }
+
+    fn finish(&mut self, metadata: &TilesetMetadata) -> Result<(), ExportError> {
+        self.flush()?;
+        self.write_archive(metadata)?;
+        // 关闭并删除中间文件
+        self.spool = None;
+        fs::remove_file(with_suffix(&self.path, ".tiles.tmp"))?;
+        fs::remove_file(with_suffix(&self.path, ".index.tmp"))?;
+        Ok(())
+    }
+}
+
+/// PMTiles v3 头部
+struct Header {
+    /// 根目录 (偏移, 长度)
+    root: (u64, u64),
+    /// 元数据 (偏移, 长度)
+    metadata: (u64, u64),
+    /// 叶目录 (偏移, 长度)
+    leaves: (u64, u64),
+    /// 瓦片数据 (偏移, 长度)
+    tile_data: (u64, u64),
+    /// 可寻址的瓦片数
+    addressed_tiles: u64,
+    /// 目录条目数
+    tile_entries: u64,
+    /// 不同的瓦片内容数
+    tile_contents: u64,
+    /// 瓦片类型:2 为 PNG,3 为 JPEG,4 为 WebP
+    tile_type: u8,
+    /// (最小缩放级别, 最大缩放级别)
+    zoom: (u8, u8),
+    /// 经纬度边界 (西, 南, 东, 北)
+    bounds: (f64, f64, f64, f64),
+    /// 中心 (缩放级别, 经度, 纬度)
+    center: (u8, f64, f64),
+}
+
+impl Header {
+    /// 转换为头部字节
+    fn to_bytes(&self) -> Vec<u8> {
+        // 经纬度以 1e-7 度为单位存储
+        let e7 = |deg: f64| ((deg * 1e7).round() as i32).to_le_bytes();
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(b"PMTiles");
+        bytes.push(3);
+        for value in [
+            self.root.0,
+            self.root.1,
+            self.metadata.0,
+            self.metadata.1,
+            self.leaves.0,
+            self.leaves.1,
+            self.tile_data.0,
+            self.tile_data.1,
+            self.addressed_tiles,
+            self.tile_entries,
+            self.tile_contents,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        // 瓦片按 ID 顺序存储
+        bytes.push(1);
+        bytes.push(COMPRESSION_GZIP);
+        bytes.push(COMPRESSION_NONE);
+        bytes.push(self.tile_type);
+        bytes.push(self.zoom.0);
+        bytes.push(self.zoom.1);
+        bytes.extend_from_slice(&e7(self.bounds.0));
+        bytes.extend_from_slice(&e7(self.bounds.1));
+        bytes.extend_from_slice(&e7(self.bounds.2));
+        bytes.extend_from_slice(&e7(self.bounds.3));
+        bytes.push(self.center.0);
+        bytes.extend_from_slice(&e7(self.center.1));
+        bytes.extend_from_slice(&e7(self.center.2));
+        bytes
+    }
+}
+
+/// 头部中的瓦片类型:2 为 PNG,3 为 JPEG,4 为 WebP
+fn tile_type(format: TileFormat) -> u8 {
+    match format {
+        TileFormat::Png => 2,
+        TileFormat::Jpeg(_) => 3,
+        TileFormat::Webp => 4,
+    }
+}
+
+/// 生成索引文件头部
+fn spool_header(format: TileFormat, tile_size: u32) -> [u8; SPOOL_HEADER_LEN] {
+    let mut bytes = [0; SPOOL_HEADER_LEN];
+    bytes[0..4].copy_from_slice(SPOOL_MAGIC);
+    bytes[4] = tile_type(format);
+    if let TileFormat::Jpeg(quality) = format {
+        bytes[5] = quality;
+    }
+    bytes[6..10].copy_from_slice(&tile_size.to_le_bytes());
+    bytes
+}
+
+/// 解析索引文件头部,得到瓦片格式和大小
+fn parse_spool_header(bytes: &[u8]) -> Result<(TileFormat, u32), ExportError> {
+    let invalid = || ExportError::InvalidArchive("中间索引文件的头部无效".to_string());
+    if &bytes[0..4] != SPOOL_MAGIC {
+        return Err(invalid());
+    }
+    let format = match bytes[4] {
+        2 => TileFormat::Png,
+        3 => TileFormat::Jpeg(bytes[5]),
+        4 => TileFormat::Webp,
+        _ => return Err(invalid()),
+    };
+    let tile_size = u32::from_le_bytes(bytes[6..10].try_into().unwrap());
+    Ok((format, tile_size))
+}
+
+/// 计算瓦片 ID
+///
+/// ID 为更低缩放级别的瓦片总数加上瓦片在该级别希尔伯特曲线上的位置
+fn tile_id(z: u32, x: u32, y: u32) -> u64 {
+    let base = ((1_u64 << (2 * z)) - 1) / 3;
+    let n = 1_u64 << z;
+    let (mut x, mut y) = (x as u64, y as u64);
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u64;
+        let ry = (y & s > 0) as u64;
+        d += s * s * ((3 * rx) ^ ry);
+        // 旋转象限
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    base + d
+}
+
+/// 生成根目录和叶目录
+///
+/// 根目录放不下所有条目时将条目分组写入叶目录,逐步增大分组直到根目录足够小
+fn build_directories(entries: &[Entry]) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let root = serialize_directory(entries)?;
+    if root.len() <= MAX_ROOT_LEN {
+        return Ok((root, vec![]));
+    }
+    let mut leaf_size = (entries.len() / 3500).max(4096);
+    loop {
+        let mut leaves = vec![];
+        let mut root_entries = vec![];
+        for chunk in entries.chunks(leaf_size) {
+            let leaf = serialize_directory(chunk)?;
+            root_entries.push(Entry {
+                tile_id: chunk[0].tile_id,
+                offset: leaves.len() as u64,
+                length: leaf.len() as u32,
+                run_length: 0,
+            });
+            leaves.extend_from_slice(&leaf);
+        }
+        let root = serialize_directory(&root_entries)?;
+        if root.len() <= MAX_ROOT_LEN {
+            return Ok((root, leaves));
+        }
+        leaf_size += leaf_size / 5;
+    }
+}
+
+/// 序列化并压缩目录
+///
+/// 依次写入条目数、瓦片 ID 差值、游程长度、数据长度和偏移,
+/// 偏移紧接上一条目时写 0,否则写偏移加 1
+fn serialize_directory(entries: &[Entry]) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    write_varint(&mut bytes, entries.len() as u64);
+    let mut last_id = 0;
+    for entry in entries {
+        write_varint(&mut bytes, entry.tile_id - last_id);
+        last_id = entry.tile_id;
+    }
+    for entry in entries {
+        write_varint(&mut bytes, entry.run_length as u64);
+    }
+    for entry in entries {
+        write_varint(&mut bytes, entry.length as u64);
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        let contiguous = i > 0 && {
+            let previous = entries[i - 1];
+            entry.offset == previous.offset + previous.length as u64
+        };
+        write_varint(&mut bytes, if contiguous { 0 } else { entry.offset + 1 });
+    }
+    gzip(&bytes)
+}
+
+/// 写入无符号 LEB128 变长整数
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// gzip 压缩
+fn gzip(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+/// 生成元数据 JSON
+fn metadata_json(metadata: &TilesetMetadata) -> String {
+    let (west, south, east, north) = metadata.wrapped_bounds();
+    let (lon, lat, zoom) = metadata.center();
+    serde_json::json!({
+        "name": metadata.name,
+        "format": metadata.format.extension(),
+        "type": "overlay",
+        "minzoom": metadata.min_zoom,
+        "maxzoom": metadata.max_zoom,
+        "bounds": [west, south, east, north],
+        "center": [lon, lat, zoom],
+    })
+    .to_string()
+}
+
+/// 读取中间数据文件中的一个瓦片
+fn read_record(data: &mut File, record: &Record) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; record.length as usize];
+    data.seek(SeekFrom::Start(record.offset))?;
+    data.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// 在路径后追加后缀
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+
+    /// 读取无符号 LEB128 变长整数
+    fn read_varint(bytes: &mut &[u8]) -> u64 {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = bytes[0];
+            *bytes = &bytes[1..];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte < 0x80 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    /// 解压并解析目录
+    fn deserialize_directory(compressed: &[u8]) -> Vec<Entry> {
+        let mut bytes = vec![];
+        GzDecoder::new(compressed).read_to_end(&mut bytes).unwrap();
+        let mut bytes = bytes.as_slice();
+        let count = read_varint(&mut bytes) as usize;
+        let mut entries = vec![
+            Entry {
+                tile_id: 0,
+                offset: 0,
+                length: 0,
+                run_length: 0,
+            };
+            count
+        ];
+        let mut last_id = 0;
+        for entry in entries.iter_mut() {
+            last_id += read_varint(&mut bytes);
+            entry.tile_id = last_id;
+        }
+        for entry in entries.iter_mut() {
+            entry.run_length = read_varint(&mut bytes) as u32;
+        }
+        for entry in entries.iter_mut() {
+            entry.length = read_varint(&mut bytes) as u32;
+        }
+        for i in 0..count {
+            entries[i].offset = match read_varint(&mut bytes) {
+                0 => entries[i - 1].offset + entries[i - 1].length as u64,
+                offset => offset - 1,
+            };
+        }
+        assert!(bytes.is_empty());
+        entries
+    }
+
+    /// 测试用的临时归档路径
+    fn temp_archive(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("cloudtiff-{}-{name}.pmtiles", std::process::id()));
+        for suffix in ["", ".tiles.tmp", ".index.tmp"] {
+            let _ = fs::remove_file(with_suffix(&path, suffix));
+        }
+        path
+    }
+
+    #[test]
+    fn tile_ids() {
+        assert_eq!(tile_id(0, 0, 0), 0);
+        assert_eq!(tile_id(1, 0, 0), 1);
+        assert_eq!(tile_id(1, 0, 1), 2);
+        assert_eq!(tile_id(1, 1, 1), 3);
+        assert_eq!(tile_id(1, 1, 0), 4);
+        assert_eq!(tile_id(2, 0, 0), 5);
+        assert_eq!(tile_id(3, 7, 0), 84);
+    }
+
+    #[test]
+    fn varint() {
+        let mut bytes = vec![];
+        write_varint(&mut bytes, 300);
+        assert_eq!(bytes, [0xac, 0x02]);
+
+        for value in [0, 1, 0x7f, 0x80, 16383, 16384, u32::MAX as u64, u64::MAX] {
+            let mut bytes = vec![];
+            write_varint(&mut bytes, value);
+            let mut slice = bytes.as_slice();
+            assert_eq!(read_varint(&mut slice), value);
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn directory_round_trip() {
+        let entries = [
+            Entry {
+                tile_id: 0,
+                offset: 0,
+                length: 100,
+                run_length: 1,
+            },
+            // 紧接上一条目
+            Entry {
+                tile_id: 1,
+                offset: 100,
+                length: 50,
+                run_length: 3,
+            },
+            // 游程重复使用第一个瓦片的数据
+            Entry {
+                tile_id: 10,
+                offset: 0,
+                length: 100,
+                run_length: 1,
+            },
+            Entry {
+                tile_id: 1000,
+                offset: 150,
+                length: 70000,
+                run_length: 0,
+            },
+        ];
+        let decoded = deserialize_directory(&serialize_directory(&entries).unwrap());
+        assert_eq!(decoded.len(), entries.len());
+        for (a, b) in decoded.iter().zip(&entries) {
+            assert_eq!(
+                (a.tile_id, a.offset, a.length, a.run_length),
+                (b.tile_id, b.offset, b.length, b.run_length)
+            );
+        }
+        assert!(deserialize_directory(&serialize_directory(&[]).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn header_length() {
+        let header = Header {
+            root: (HEADER_LEN as u64, 10),
+            metadata: (137, 20),
+            leaves: (157, 0),
+            tile_data: (157, 1000),
+            addressed_tiles: 5,
+            tile_entries: 4,
+            tile_contents: 3,
+            tile_type: 2,
+            zoom: (0, 12),
+            bounds: (-180.0, -85.0, 180.0, 85.0),
+            center: (0, 0.0, 0.0),
+        };
+        let bytes = header.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN);
+        assert_eq!(&bytes[0..8], b"PMTiles\x03");
+    }
+
+    #[test]
+    fn resume_truncates_torn_record() {
+        let path = temp_archive("resume");
+        let mut writer = PmTilesWriter::open(&path).unwrap();
+        writer.start(TileFormat::Png, 256).unwrap();
+        writer.write_tile((0, 0, 0), b"first").unwrap();
+        writer.write_tile((1, 0, 0), b"second").unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        // 模拟中断:第三条记录只写了一半,数据也不完整
+        let mut index = OpenOptions::new()
+            .append(true)
+            .open(with_suffix(&path, ".index.tmp"))
+            .unwrap();
+        let record = Record {
+            tile: (1, 1, 0),
+            offset: 11,
+            length: 5,
+        };
+        index
+            .write_all(&record.to_bytes()[..RECORD_LEN / 2])
+            .unwrap();
+        drop(index);
+        let mut data = OpenOptions::new()
+            .append(true)
+            .open(with_suffix(&path, ".tiles.tmp"))
+            .unwrap();
+        data.write_all(b"th").unwrap();
+        drop(data);
+
+        let mut writer = PmTilesWriter::open(&path).unwrap();
+        let expected: HashSet<TileIndex> = [(0, 0, 0), (1, 0, 0)].into();
+        assert_eq!(writer.existing_tiles().unwrap(), expected);
+        assert_eq!(writer.data_len, 11);
+        assert_eq!(
+            fs::metadata(with_suffix(&path, ".index.tmp"))
+                .unwrap()
+                .len(),
+            (SPOOL_HEADER_LEN + 2 * RECORD_LEN) as u64
+        );
+
+        // 续传时瓦片格式或大小不一致
+        assert!(matches!(
+            writer.start(TileFormat::Png, 512),
+            Err(ExportError::InvalidArchive(_))
+        ));
+        assert!(matches!(
+            writer.start(TileFormat::Jpeg(90), 256),
+            Err(ExportError::InvalidArchive(_))
+        ));
+        writer.start(TileFormat::Png, 256).unwrap();
+
+        // 续写的瓦片紧接完整的数据
+        writer.write_tile((1, 1, 0), b"third").unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        let mut writer = PmTilesWriter::open(&path).unwrap();
+        assert_eq!(writer.existing_tiles().unwrap().len(), 3);
+        let mut data = File::open(with_suffix(&path, ".tiles.tmp")).unwrap();
+        assert_eq!(
+            read_record(&mut data, &writer.records[2]).unwrap(),
+            b"third"
+        );
+        drop(data);
+
+        writer.spool = None;
+        for suffix in [".tiles.tmp", ".index.tmp"] {
+            fs::remove_file(with_suffix(&path, suffix)).unwrap();
+        }
+    }
+}
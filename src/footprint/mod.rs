@@ -0,0 +1,468 @@
+//! 影像覆盖范围索引模块
+//!
+//! 服务大量 COG 时,需要根据请求的范围和分辨率快速选出需要读取的文件。本模块提供:
+//! - [`Footprint`] 记录单个 COG 的经纬度边界、原始和最粗概览的地面分辨率及自定义元数据
+//! - [`FootprintIndex`] 使用 R 树按范围检索覆盖范围,支持 Web 墨卡托和任意瓦片矩阵集的瓦片
+//! - 以 JSON 保存和加载索引,服务启动时无需重新读取每个文件的头部
+//!
+//! 跨越 ±180° 经线的影像(最大经度大于 180)在 R 树中拆分为两个条目,检索时自动合并。
+//!
+//! # 示例
+//!
+//! ```no_run
+//! use cloudtiff::footprint::{Footprint, FootprintIndex};
+//! use cloudtiff::CloudTiff;
+//! use std::fs::File;
+//!
+//! let mut index = FootprintIndex::new();
+//! for path in ["a.tif", "b.tif"] {
+//!     let cog = CloudTiff::open(&mut File::open(path).unwrap()).unwrap();
+//!     let footprint = Footprint::from_cog(path, &cog)
+//!         .unwrap()
+//!         .with_metadata("date", "2024-06-01");
+//!     index.insert(footprint);
+//! }
+//! index.save("index.json").unwrap();
+//!
+//! let index = FootprintIndex::load("index.json").unwrap();
+//! for footprint in index.search_tile(12, 654, 1321, 256) {
+//!     println!("{}", footprint.id);
+//! }
+//! ```
+
+use crate::cog::{CloudTiff, CloudTiffError};
+use crate::projection::geo_transform::GeoTransform;
+use crate::projection::{self, ProjectionError};
+use crate::render::tms::TileMatrixSet;
+use crate::render::wmts;
+use crate::Region;
+use proj4rs::Proj;
+use rstar::{RTree, RTreeObject, AABB};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 索引文件格式版本
+const FORMAT_VERSION: u32 = 1;
+
+/// 覆盖范围索引错误
+#[derive(Debug)]
+pub enum FootprintError {
+    /// 文件读写错误
+    Io(io::Error),
+    /// JSON 格式错误
+    Json(serde_json::Error),
+    /// 读取 COG 信息错误
+    CloudTiff(CloudTiffError),
+    /// 不支持的索引文件版本
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for FootprintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for FootprintError {}
+
+impl From<io::Error> for FootprintError {
+    fn from(e: io::Error) -> Self {
+        FootprintError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for FootprintError {
+    fn from(e: serde_json::Error) -> Self {
+        FootprintError::Json(e)
+    }
+}
+
+impl From<CloudTiffError> for FootprintError {
+    fn from(e: CloudTiffError) -> Self {
+        FootprintError::CloudTiff(e)
+    }
+}
+
+impl From<ProjectionError> for FootprintError {
+    fn from(e: ProjectionError) -> Self {
+        FootprintError::CloudTiff(e.into())
+    }
+}
+
+/// 单个 COG 的覆盖范围
+#[derive(Clone, Debug, PartialEq)]
+pub struct Footprint {
+    /// 文件标识,通常为路径或 URL
+    pub id: String,
+    /// 坐标系 EPSG 代码
+    pub epsg: u16,
+    /// 经纬度边界(度),跨越 ±180° 经线时最大经度大于 180
+    pub bounds: Region<f64>,
+    /// 原始分辨率图像的尺寸 (宽度, 高度)
+    pub dimensions: (u32, u32),
+    /// 原始分辨率像素的地面尺寸(米)
+    pub resolution: (f64, f64),
+    /// 最粗概览像素的地面尺寸(米)
+    pub overview_resolution: (f64, f64),
+    /// 波段数(包括 alpha 等额外样本)
+    pub band_count: usize,
+    /// 自定义元数据,例如采集日期或云量
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Footprint {
+    /// 从已打开的 COG 创建覆盖范围
+    ///
+    /// # 参数
+    /// * `id` - 文件标识,通常为路径或 URL
+    /// * `cog` - COG 影像
+    ///
+    /// # 错误
+    /// 坐标系不受支持时返回错误
+    pub fn from_cog(id: impl Into<String>, cog: &CloudTiff) -> Result<Self, FootprintError> {
+        Ok(Self {
+            id: id.into(),
+            epsg: cog.projection.epsg,
+            bounds: cog.bounds_lat_lon_deg()?,
+            dimensions: cog.full_dimensions(),
+            resolution: cog.ground_pixel_size(0)?,
+            overview_resolution: cog.ground_pixel_size(cog.max_level())?,
+            band_count: cog.band_count(),
+            metadata: BTreeMap::new(),
+        })
+    }
+
+    /// 添加一项元数据
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// 判断影像能否高效地以指定分辨率渲染
+    ///
+    /// 请求的分辨率比最粗的概览还粗时,渲染需要读取过多的瓦片,因此不可用。
+    /// 比原始分辨率更细的请求仍然可用(放大显示)
+    ///
+    /// # 参数
+    /// * `resolution` - 请求像素的地面尺寸(米)
+    pub fn serves_resolution(&self, resolution: f64) -> bool {
+        resolution <= self.overview_resolution.0.max(self.overview_resolution.1)
+    }
+
+    /// 拆分为不跨越 ±180° 经线的 R 树条目
+    fn entries(&self, index: usize) -> Vec<Entry> {
+        self.bounds
+            .split_wrapped(180.0)
+            .into_iter()
+            .map(|bounds| Entry {
+                index,
+                envelope: envelope(&bounds),
+            })
+            .collect()
+    }
+}
+
+/// R 树条目,指向索引中的覆盖范围
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    /// 覆盖范围在索引中的位置
+    index: usize,
+    /// 经纬度外包矩形(度)
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for Entry {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// 将区域转换为 R 树的外包矩形
+fn envelope(region: &Region<f64>) -> AABB<[f64; 2]> {
+    AABB::from_corners([region.x.min, region.y.min], [region.x.max, region.y.max])
+}
+
+/// 多个 COG 覆盖范围的空间索引
+///
+/// 检索结果按覆盖范围的添加顺序排列,可以直接作为镶嵌的数据源顺序
+#[derive(Clone, Debug, Default)]
+pub struct FootprintIndex {
+    /// 所有覆盖范围,按添加顺序排列
+    footprints: Vec<Footprint>,
+    /// 经纬度 R 树
+    tree: RTree<Entry>,
+}
+
+impl FootprintIndex {
+    /// 创建空索引
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 由覆盖范围批量创建索引
+    ///
+    /// 使用批量加载构建 R 树,比逐个插入更快且查询效率更高
+    pub fn from_footprints(footprints: Vec<Footprint>) -> Self {
+        let entries = footprints
+            .iter()
+            .enumerate()
+            .flat_map(|(index, footprint)| footprint.entries(index))
+            .collect();
+        Self {
+            footprints,
+            tree: RTree::bulk_load(entries),
+        }
+    }
+
+    /// 添加覆盖范围
+    pub fn insert(&mut self, footprint: Footprint) {
+        let index = self.footprints.len();
+        for entry in footprint.entries(index) {
+            self.tree.insert(entry);
+        }
+        self.footprints.push(footprint);
+    }
+
+    /// 覆盖范围数量
+    pub fn len(&self) -> usize {
+        self.footprints.len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.footprints.is_empty()
+    }
+
+    /// 按添加顺序遍历所有覆盖范围
+    pub fn iter(&self) -> impl Iterator<Item = &Footprint> {
+        self.footprints.iter()
+    }
+
+    /// 根据标识查找覆盖范围
+    pub fn get(&self, id: &str) -> Option<&Footprint> {
+        self.footprints.iter().find(|footprint| footprint.id == id)
+    }
+
+    /// 检索与经纬度范围相交的覆盖范围
+    ///
+    /// # 参数
+    /// * `bounds` - 经纬度范围(度),最大经度可以大于 180 以表示跨越 ±180° 经线的范围
+    pub fn search_bounds(&self, bounds: &Region<f64>) -> Vec<&Footprint> {
+        self.search(bounds, None)
+    }
+
+    /// 检索与经纬度范围相交、并且能以指定分辨率渲染的覆盖范围
+    ///
+    /// # 参数
+    /// * `bounds` - 经纬度范围(度),最大经度可以大于 180 以表示跨越 ±180° 经线的范围
+    /// * `resolution` - 请求像素的地面尺寸(米),None 时不按分辨率筛选,
+    ///   参见 [`Footprint::serves_resolution`]
+    pub fn search(&self, bounds: &Region<f64>, resolution: Option<f64>) -> Vec<&Footprint> {
+        let indices: BTreeSet<usize> = bounds
+            .split_wrapped(180.0)
+            .iter()
+            .flat_map(|part| {
+                self.tree
+                    .locate_in_envelope_intersecting(&envelope(part))
+                    .map(|entry| entry.index)
+            })
+            .collect();
+        indices
+            .into_iter()
+            .map(|index| &self.footprints[index])
+            .filter(|footprint| resolution.is_none_or(|r| footprint.serves_resolution(r)))
+            .collect()
+    }
+
+    /// 检索渲染 Web 墨卡托(EPSG:3857)XYZ 瓦片需要的覆盖范围
+    ///
+    /// 瓦片索引无效时返回空列表
+    ///
+    /// # 参数
+    /// * `z` - 缩放级别
+    /// * `x`, `y` - 瓦片索引,原点位于左上角
+    /// * `size` - 瓦片边长(像素),用于计算瓦片的地面分辨率
+    pub fn search_tile(&self, z: u32, x: u32, y: u32, size: u32) -> Vec<&Footprint> {
+        let (Some(bounds), Some(tile)) = (
+            wmts::tile_bounds_lat_lon_deg(x, y, z),
+            wmts::tile_bounds_web_mercator(x, y, z),
+        ) else {
+            return vec![];
+        };
+        let pixel_size = (tile.x.range() / size as f64, tile.y.range() / size as f64);
+        let centre_y = (tile.y.min + tile.y.max) / 2.0;
+        let resolution = projection::ground_pixel_size(3857, pixel_size, centre_y)
+            .ok()
+            .map(|(x, y)| x.max(y));
+        self.search(&bounds, resolution)
+    }
+
+    /// 检索渲染任意瓦片矩阵集中的瓦片需要的覆盖范围
+    ///
+    /// 瓦片索引无效时返回空列表
+    ///
+    /// # 参数
+    /// * `tms` - 瓦片矩阵集
+    /// * `z` - 缩放级别
+    /// * `x`, `y` - 瓦片列号和行号
+    ///
+    /// # 错误
+    /// 瓦片矩阵集的坐标系不受支持时返回错误
+    pub fn search_tms_tile(
+        &self,
+        tms: &TileMatrixSet,
+        z: u32,
+        x: u32,
+        y: u32,
+    ) -> Result<Vec<&Footprint>, FootprintError> {
+        let Some((matrix, tile)) = tms
+            .tile_matrix(z)
+            .and_then(|matrix| Some((matrix, matrix.tile_bounds(x, y)?)))
+        else {
+            return Ok(vec![]);
+        };
+        // 瓦片边界按单个像素的栅格投影到经纬度,沿四边加密采样
+        let bounds = GeoTransform::from_region(&tile, (1, 1))
+            .to_projection(tms.epsg, (1, 1))?
+            .bounds_lat_lon_deg()?;
+        // 地面分辨率使用投影的原生单位计算,地理坐标系为弧度
+        let native = if Proj::from_epsg_code(tms.epsg)
+            .map_err(ProjectionError::from)?
+            .is_latlong()
+        {
+            1_f64.to_radians()
+        } else {
+            1.0
+        };
+        let cell_size = matrix.cell_size * native;
+        let centre_y = (tile.y.min + tile.y.max) / 2.0 * native;
+        let (rx, ry) = projection::ground_pixel_size(tms.epsg, (cell_size, cell_size), centre_y)?;
+        Ok(self.search(&bounds, Some(rx.max(ry))))
+    }
+
+    /// 将索引序列化为 JSON
+    ///
+    /// # 错误
+    /// 序列化失败时返回错误
+    pub fn to_json(&self) -> Result<String, FootprintError> {
+        let raw = json::RawIndex {
+            version: FORMAT_VERSION,
+            footprints: self.footprints.iter().map(Into::into).collect(),
+        };
+        Ok(serde_json::to_string(&raw)?)
+    }
+
+    /// 从 JSON 加载索引
+    ///
+    /// # 错误
+    /// JSON 格式错误或版本不受支持时返回错误
+    pub fn from_json(json: &str) -> Result<Self, FootprintError> {
+        let raw: json::RawIndex = serde_json::from_str(json)?;
+        if raw.version != FORMAT_VERSION {
+            return Err(FootprintError::UnsupportedVersion(raw.version));
+        }
+        Ok(Self::from_footprints(
+            raw.footprints.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    /// 保存索引到文件
+    ///
+    /// 先写入同目录下的临时文件再重命名,中断时不会破坏已有的索引文件
+    ///
+    /// # 错误
+    /// 文件写入或序列化失败时返回错误
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FootprintError> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, self.to_json()?)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// 从文件加载索引
+    ///
+    /// # 错误
+    /// 文件读取失败、JSON 格式错误或版本不受支持时返回错误
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FootprintError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+impl FromIterator<Footprint> for FootprintIndex {
+    fn from_iter<I: IntoIterator<Item = Footprint>>(iter: I) -> Self {
+        Self::from_footprints(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Footprint> for FootprintIndex {
+    fn extend<I: IntoIterator<Item = Footprint>>(&mut self, iter: I) {
+        for footprint in iter {
+            self.insert(footprint);
+        }
+    }
+}
+
+mod json {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    /// 索引文件
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct RawIndex {
+        pub(super) version: u32,
+        pub(super) footprints: Vec<RawFootprint>,
+    }
+
+    /// 索引文件中的覆盖范围,边界为 [西, 南, 东, 北]
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct RawFootprint {
+        id: String,
+        epsg: u16,
+        bounds: [f64; 4],
+        dimensions: [u32; 2],
+        resolution: [f64; 2],
+        overview_resolution: [f64; 2],
+        band_count: usize,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        metadata: BTreeMap<String, String>,
+    }
+
+    impl From<&Footprint> for RawFootprint {
+        fn from(footprint: &Footprint) -> Self {
+            let (west, south, east, north) = footprint.bounds.as_tuple();
+            Self {
+                id: footprint.id.clone(),
+                epsg: footprint.epsg,
+                bounds: [west, south, east, north],
+                dimensions: footprint.dimensions.into(),
+                resolution: footprint.resolution.into(),
+                overview_resolution: footprint.overview_resolution.into(),
+                band_count: footprint.band_count,
+                metadata: footprint.metadata.clone(),
+            }
+        }
+    }
+
+    impl From<RawFootprint> for Footprint {
+        fn from(raw: RawFootprint) -> Self {
+            let [west, south, east, north] = raw.bounds;
+            Self {
+                id: raw.id,
+                epsg: raw.epsg,
+                bounds: Region::new(west, south, east, north),
+                dimensions: raw.dimensions.into(),
+                resolution: raw.resolution.into(),
+                overview_resolution: raw.overview_resolution.into(),
+                band_count: raw.band_count,
+                metadata: raw.metadata,
+            }
+        }
+    }
+}
@@ -2,27 +2,58 @@
 //!
 //! 本模块提供了通过 HTTP Range 请求实现的异步读取功能。
 //! 支持按需获取远程文件的指定字节范围。
+//!
+//! 所有请求都遵循读取器的 [`RequestPolicy`]:限制并发数、超时,
+//! 并对 5xx/429 等临时错误进行指数退避重试。
+//!
+//! 通过 [`HttpReaderBuilder`] 可以共享已配置的 `reqwest::Client`(代理、TLS 根证书等),
+//! 添加默认请求头(认证令牌、API 密钥、Cookie、User-Agent),
+//! 以及在每次请求前修改请求头的钩子。
+//!
+//! 每个响应都会被校验:范围请求必须返回 `206 Partial Content` 和匹配的 `Content-Range`。
+//! 第一次成功请求时记录对象的 ETag/Last-Modified,之后的请求携带 `If-Match`
+//! (或 `If-Unmodified-Since`),对象被替换时返回 [`ObjectChangedError`]。
+//!
+//! 不使用异步运行时的调用方可以使用 [`BlockingHttpReader`],
+//! 它实现了同步的 [`ReadRange`],并与异步读取器共享请求头、策略和响应校验。
 
 #![cfg(feature = "http")]
 
-use super::AsyncReadRange;
+use super::policy::RequestPolicy;
+use super::{AsyncReadRange, ObjectChangedError, ObjectVersion, ReadRange};
 use futures::future::BoxFuture;
 use futures::FutureExt;
-use reqwest::header::RANGE;
-use reqwest::{Client, IntoUrl, Url};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_RANGE, ETAG, IF_MATCH,
+    IF_UNMODIFIED_SINCE, LAST_MODIFIED, RANGE, USER_AGENT,
+};
+use reqwest::{Client, IntoUrl, StatusCode, Url};
 use std::fmt;
 use std::future::Future;
-use std::io::{Error, ErrorKind, Result};
+use std::io::{Error, ErrorKind, Read, Result};
 use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::task::{Context, Poll};
 use tokio::io::AsyncRead;
+use tokio::sync::Semaphore;
+use tracing::*;
 
 /// HTTP 范围读取器
 ///
 /// 通过 HTTP Range 请求实现远程文件的异步读取
 pub struct HttpReader {
+    /// HTTP 客户端,克隆开销很小,所有请求共享连接池
+    client: Client,
     /// 远程文件的 URL
     url: Url,
+    /// 请求头设置
+    headers: RequestHeaders,
+    /// 请求策略
+    policy: RequestPolicy,
+    /// 并发请求限制
+    limiter: Arc<Semaphore>,
+    /// 第一次成功请求时记录的对象版本,为 None 时不检查版本
+    version: Option<Arc<OnceLock<ObjectVersion>>>,
     /// 当前读取位置
     position: u64,
     /// 当前正在进行的读取请求
@@ -34,10 +65,56 @@ pub struct HttpReader {
 /// 包装了一个异步的字节数组结果
 type PendingRequest = Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Sync + Send>>;
 
+/// 请求头钩子
+///
+/// 在每次发送请求(包括重试)前调用,可以修改即将发送的请求头,
+/// 例如刷新会过期的访问令牌
+pub type HeaderHook = Arc<dyn Fn(&Url, &mut HeaderMap) + Send + Sync>;
+
+/// 请求头设置
+///
+/// 包含每个请求都会携带的默认请求头和可选的请求头钩子
+#[derive(Clone, Default)]
+pub struct RequestHeaders {
+    /// 默认请求头
+    pub default: HeaderMap,
+    /// 请求头钩子
+    pub hook: Option<HeaderHook>,
+}
+
+impl RequestHeaders {
+    /// 生成一次请求要发送的请求头
+    ///
+    /// 先复制默认请求头,再调用钩子
+    ///
+    /// # 参数
+    /// * `url` - 请求的 URL
+    pub fn for_request(&self, url: &Url) -> HeaderMap {
+        let mut headers = self.default.clone();
+        if let Some(hook) = &self.hook {
+            hook(url, &mut headers);
+        }
+        headers
+    }
+}
+
+impl fmt::Debug for RequestHeaders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 敏感请求头(如 Authorization)由 HeaderMap 自身负责隐藏
+        f.debug_struct("RequestHeaders")
+            .field("default", &self.default)
+            .field("hook", &self.hook.is_some())
+            .finish()
+    }
+}
+
 impl fmt::Debug for HttpReader {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("HttpReader")
             .field("url", &self.url)
+            .field("headers", &self.headers)
+            .field("policy", &self.policy)
+            .field("version", &self.version())
             .field("position", &self.position)
             .finish()
     }
@@ -46,19 +123,69 @@ impl fmt::Debug for HttpReader {
 impl HttpReader {
     /// 创建新的 HTTP 读取器
     ///
+    /// 使用新建的客户端、无额外请求头和默认的 [`RequestPolicy`]
+    ///
     /// # 参数
     /// * `url` - 远程文件的 URL
     ///
     /// # 错误
     /// 如果 URL 解析失败则返回错误
     pub fn new<U: IntoUrl>(url: U) -> Result<Self> {
-        Ok(Self {
-            url: url
-                .into_url()
-                .map_err(|e| Error::new(ErrorKind::AddrNotAvailable, format!("{e:?}")))?,
-            position: 0,
-            _read_request: None,
-        })
+        Self::builder(url).build()
+    }
+
+    /// 创建 HTTP 读取器构建器
+    ///
+    /// # 参数
+    /// * `url` - 远程文件的 URL
+    pub fn builder<U: IntoUrl>(url: U) -> HttpReaderBuilder {
+        HttpReaderBuilder::new(url)
+    }
+
+    /// 设置请求策略
+    ///
+    /// # 参数
+    /// * `policy` - 并发、超时和重试策略
+    pub fn with_policy(mut self, policy: RequestPolicy) -> Self {
+        self.limiter = Arc::new(Semaphore::new(policy.permits()));
+        self.policy = policy;
+        self
+    }
+
+    /// 获取当前的请求策略
+    pub fn policy(&self) -> &RequestPolicy {
+        &self.policy
+    }
+
+    /// 获取远程文件的 URL
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// 获取已记录的远程对象版本
+    ///
+    /// 在第一次成功请求之前,或者关闭了版本检查时返回 None
+    pub fn version(&self) -> Option<&ObjectVersion> {
+        self.version.as_ref().and_then(|version| version.get())
+    }
+
+    /// 创建一个按策略执行的范围请求
+    ///
+    /// 返回的 Future 不借用读取器,可以在任务间传递
+    ///
+    /// # 参数
+    /// * `start` - 起始字节位置
+    /// * `n` - 要读取的字节数
+    fn fetch(&self, start: u64, n: usize) -> impl Future<Output = Result<Vec<u8>>> + Send + Sync {
+        RangeFetch {
+            client: self.client.clone(),
+            headers: self.headers.clone(),
+            url: self.url.clone(),
+            policy: self.policy,
+            limiter: self.limiter.clone(),
+            version: self.version.clone(),
+        }
+        .fetch(start, n)
     }
 
     /// 获取或创建读取请求
@@ -71,28 +198,7 @@ impl HttpReader {
     pub fn get_or_create_read_request(&mut self, n: usize) -> PendingRequest {
         match self._read_request.take() {
             Some(req) => req,
-            None => {
-                let start = self.position;
-                // 注意: HTTP Range 是包含性的,所以需要减1
-                let end = start + n as u64 - 1;
-
-                // 创建新的 HTTP 请求
-                let fut = Client::new()
-                    .get(self.url.clone())
-                    .header(RANGE, format!("bytes={start}-{end}"))
-                    .send();
-
-                // 包装请求结果处理
-                Box::pin(fut.then(|result| async move {
-                    match result {
-                        Ok(response) => match response.bytes().await {
-                            Ok(bytes) => Ok(bytes.to_vec()),
-                            Err(e) => Err(Error::new(ErrorKind::InvalidData, format!("{e:?}"))),
-                        },
-                        Err(e) => Err(Error::new(ErrorKind::NotConnected, format!("{e:?}"))),
-                    }
-                }))
-            }
+            None => Box::pin(self.fetch(self.position, n)),
         }
     }
 }
@@ -108,35 +214,722 @@ impl AsyncReadRange for HttpReader {
         start: u64,
         buf: &'a mut [u8],
     ) -> BoxFuture<'a, Result<usize>> {
-        // 计算请求范围的结束位置
-        let end = start + buf.len() as u64 - 1;
-
-        // 构建带有Range头的HTTP请求
-        let request_builder = Client::new()
-            .get(self.url.clone())
-            .header(RANGE, format!("bytes={start}-{end}"));
+        let request = self.fetch(start, buf.len());
 
         Box::pin(async move {
-            // 发送HTTP请求
-            let request = request_builder.send();
-            let response = request
-                .await
-                .map_err(|e| Error::new(ErrorKind::NotConnected, format!("{e:?}")))?;
-
-            // 获取响应体字节数据
-            let bytes = response
-                .bytes()
-                .await
-                .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{e:?}")))?;
+            let bytes = request.await?;
 
             // 将响应数据复制到目标缓冲区
-            let n = bytes.len();
-            buf[..n].copy_from_slice(&bytes[..]);
+            // 服务器返回的数据可能多于请求的范围,多余部分丢弃
+            let n = bytes.len().min(buf.len());
+            buf[..n].copy_from_slice(&bytes[..n]);
 
             // 返回读取的字节数
-            Ok(bytes.len())
+            Ok(n)
+        })
+    }
+
+    fn max_in_flight(&self) -> Option<usize> {
+        Some(self.policy.permits())
+    }
+}
+
+/// HTTP 读取器构建器
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::HttpReader;
+///
+/// let reader = HttpReader::builder("https://example.com/data.tif")
+///     .with_bearer_auth("token")
+///     .with_user_agent("my-tile-server/1.0")
+///     .build()
+///     .unwrap();
+/// ```
+pub struct HttpReaderBuilder {
+    /// 远程文件的 URL,解析失败时保存错误直到构建
+    url: Result<Url>,
+    /// 外部提供的客户端
+    client: Option<Client>,
+    /// 外部提供的阻塞客户端
+    blocking_client: Option<reqwest::blocking::Client>,
+    /// 请求头设置
+    headers: RequestHeaders,
+    /// 请求策略
+    policy: RequestPolicy,
+    /// 是否记录并检查对象版本
+    pin_version: bool,
+    /// 构建过程中遇到的第一个错误
+    error: Option<Error>,
+}
+
+impl HttpReaderBuilder {
+    /// 创建新的构建器
+    ///
+    /// # 参数
+    /// * `url` - 远程文件的 URL
+    pub fn new<U: IntoUrl>(url: U) -> Self {
+        Self {
+            url: url
+                .into_url()
+                .map_err(|e| Error::new(ErrorKind::AddrNotAvailable, format!("{e:?}"))),
+            client: None,
+            blocking_client: None,
+            headers: RequestHeaders::default(),
+            policy: RequestPolicy::default(),
+            pin_version: true,
+            error: None,
+        }
+    }
+
+    /// 使用已配置的客户端
+    ///
+    /// 多个读取器可以共享同一个客户端的连接池、代理和 TLS 设置
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// 使用已配置的阻塞客户端
+    ///
+    /// 仅用于 [`build_blocking`](Self::build_blocking)
+    pub fn with_blocking_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.blocking_client = Some(client);
+        self
+    }
+
+    /// 添加一个默认请求头
+    ///
+    /// # 参数
+    /// * `name` - 请求头名称
+    /// * `value` - 请求头的值
+    pub fn with_header<K, V>(mut self, name: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: fmt::Debug,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: fmt::Debug,
+    {
+        match (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            (Ok(name), Ok(value)) => {
+                self.headers.default.append(name, value);
+            }
+            (Err(e), _) => self.set_error(format!("无效的请求头名称: {e:?}")),
+            (_, Err(e)) => self.set_error(format!("无效的请求头值: {e:?}")),
+        }
+        self
+    }
+
+    /// 添加多个默认请求头
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.default.extend(headers);
+        self
+    }
+
+    /// 添加 Bearer 认证请求头
+    ///
+    /// # 参数
+    /// * `token` - 访问令牌
+    pub fn with_bearer_auth<T: fmt::Display>(mut self, token: T) -> Self {
+        match HeaderValue::try_from(format!("Bearer {token}")) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                self.headers.default.insert(AUTHORIZATION, value);
+            }
+            Err(e) => self.set_error(format!("无效的访问令牌: {e:?}")),
+        }
+        self
+    }
+
+    /// 设置 User-Agent 请求头
+    pub fn with_user_agent<V>(self, user_agent: V) -> Self
+    where
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: fmt::Debug,
+    {
+        self.with_header(USER_AGENT, user_agent)
+    }
+
+    /// 设置每次请求前调用的请求头钩子
+    ///
+    /// 钩子收到的请求头已经包含默认请求头
+    pub fn with_request_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Url, &mut HeaderMap) + Send + Sync + 'static,
+    {
+        self.headers.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// 设置请求策略
+    pub fn with_policy(mut self, policy: RequestPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 设置是否记录并检查对象版本
+    ///
+    /// 默认开启。对于不提供 ETag/Last-Modified 或每次返回不同 ETag 的服务器可以关闭
+    pub fn with_version_pinning(mut self, pin_version: bool) -> Self {
+        self.pin_version = pin_version;
+        self
+    }
+
+    /// 构建 HTTP 读取器
+    ///
+    /// # 错误
+    /// 如果 URL 或请求头无效则返回错误
+    pub fn build(self) -> Result<HttpReader> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        Ok(HttpReader {
+            client: self.client.unwrap_or_default(),
+            url: self.url?,
+            headers: self.headers,
+            policy: self.policy,
+            limiter: Arc::new(Semaphore::new(self.policy.permits())),
+            version: self.pin_version.then(|| Arc::new(OnceLock::new())),
+            position: 0,
+            _read_request: None,
         })
     }
+
+    /// 构建阻塞 HTTP 读取器
+    ///
+    /// 使用与 [`build`](Self::build) 相同的请求头、策略和版本检查设置。
+    /// 注意:不能在异步运行时内部创建阻塞客户端
+    ///
+    /// # 错误
+    /// 如果 URL 或请求头无效则返回错误
+    pub fn build_blocking(self) -> Result<BlockingHttpReader> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        Ok(BlockingHttpReader {
+            client: self.blocking_client.unwrap_or_default(),
+            url: self.url?,
+            headers: self.headers,
+            policy: self.policy,
+            limiter: BlockingLimiter::new(self.policy.permits()),
+            version: self.pin_version.then(OnceLock::new),
+        })
+    }
+
+    /// 记录第一个构建错误
+    fn set_error(&mut self, message: String) {
+        if self.error.is_none() {
+            self.error = Some(Error::new(ErrorKind::InvalidInput, message));
+        }
+    }
+}
+
+impl fmt::Debug for HttpReaderBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpReaderBuilder")
+            .field("url", &self.url)
+            .field("client", &self.client.is_some())
+            .field("blocking_client", &self.blocking_client.is_some())
+            .field("headers", &self.headers)
+            .field("policy", &self.policy)
+            .field("pin_version", &self.pin_version)
+            .finish()
+    }
+}
+
+/// 一次范围读取所需的全部状态
+///
+/// 不借用读取器,因此生成的 Future 可以在任务间传递
+struct RangeFetch {
+    /// HTTP 客户端
+    client: Client,
+    /// 远程文件的 URL
+    url: Url,
+    /// 请求头设置,每次尝试前重新生成请求头
+    headers: RequestHeaders,
+    /// 请求策略
+    policy: RequestPolicy,
+    /// 并发请求限制
+    limiter: Arc<Semaphore>,
+    /// 记录的对象版本
+    version: Option<Arc<OnceLock<ObjectVersion>>>,
+}
+
+impl RangeFetch {
+    /// 按策略执行一次范围读取
+    ///
+    /// 在并发许可内发送请求,超时或遇到临时错误时按退避时间重试,
+    /// 重试次数用尽后返回最后一次的错误
+    ///
+    /// # 参数
+    /// * `start` - 起始字节位置
+    /// * `n` - 要读取的字节数
+    async fn fetch(self, start: u64, n: usize) -> Result<Vec<u8>> {
+        if n == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut attempt = 0;
+        loop {
+            let result = {
+                // 持有许可直到响应体读取完毕
+                let _permit = self
+                    .limiter
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::other(format!("{e:?}")))?;
+                let request = self.attempt(start, n);
+                match self.policy.timeout {
+                    Some(timeout) => match tokio::time::timeout(timeout, request).await {
+                        Ok(result) => result,
+                        Err(_) => Err(Attempt::TimedOut),
+                    },
+                    None => request.await,
+                }
+            };
+
+            match result {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) if e.is_retryable() && attempt < self.policy.max_retries => {
+                    let delay = self.policy.backoff(attempt);
+                    attempt += 1;
+                    warn!("HTTP 范围请求失败,{delay:?} 后第 {attempt} 次重试: {e:?}");
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e.into_io_error(&self.url)),
+            }
+        }
+    }
+
+    /// 发送一次范围请求并校验响应
+    ///
+    /// 每次尝试都调用请求头钩子,重试时可以使用刷新后的凭证
+    ///
+    /// # 参数
+    /// * `start` - 起始字节位置
+    /// * `n` - 要读取的字节数
+    async fn attempt(&self, start: u64, n: usize) -> std::result::Result<Vec<u8>, Attempt> {
+        // 注意: HTTP Range 是包含性的,所以需要减1
+        let end = start + n as u64 - 1;
+
+        let mut request = self
+            .client
+            .get(self.url.clone())
+            .headers(self.headers.for_request(&self.url))
+            .header(RANGE, format!("bytes={start}-{end}"));
+        // 要求服务器只在对象未改变时返回数据
+        if let Some((name, value)) = precondition(self.version.as_deref()) {
+            request = request.header(name, value);
+        }
+
+        let mut response = request.send().await.map_err(Attempt::Request)?;
+        check_response(
+            self.version.as_deref(),
+            response.status(),
+            response.headers(),
+            start,
+            end,
+        )?;
+
+        // 忽略 Range 的服务器会返回整个对象,只读取需要的字节后丢弃连接
+        let mut bytes = Vec::with_capacity(n);
+        while bytes.len() < n {
+            match response.chunk().await.map_err(Attempt::Request)? {
+                Some(chunk) => {
+                    let take = chunk.len().min(n - bytes.len());
+                    bytes.extend_from_slice(&chunk[..take]);
+                }
+                None => break,
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// 根据记录的对象版本生成条件请求头
+///
+/// 优先使用强 ETag 的 `If-Match`,否则使用 `If-Unmodified-Since`
+fn precondition(version: Option<&OnceLock<ObjectVersion>>) -> Option<(HeaderName, String)> {
+    let expected = version?.get()?;
+    if let Some(etag) = expected.strong_etag() {
+        Some((IF_MATCH, etag.to_string()))
+    } else {
+        expected
+            .last_modified
+            .clone()
+            .map(|last_modified| (IF_UNMODIFIED_SINCE, last_modified))
+    }
+}
+
+/// 校验范围请求的响应
+///
+/// - 412 或版本不一致时返回对象变更错误
+/// - 第一次成功的响应会记录对象版本
+/// - 范围请求必须返回 206 和匹配的 Content-Range,
+///   只有从头读取时才接受忽略 Range 的 200 响应
+///
+/// # 参数
+/// * `version` - 记录的对象版本,为 None 时不检查版本
+/// * `status` - 响应状态码
+/// * `headers` - 响应头
+/// * `start` - 请求的起始字节位置
+/// * `end` - 请求的结束字节位置(包含)
+fn check_response(
+    version: Option<&OnceLock<ObjectVersion>>,
+    status: StatusCode,
+    headers: &HeaderMap,
+    start: u64,
+    end: u64,
+) -> std::result::Result<(), Attempt> {
+    if status == StatusCode::PRECONDITION_FAILED {
+        return Err(Attempt::Changed(ObjectChangedError {
+            expected: version.and_then(|v| v.get()).cloned().unwrap_or_default(),
+            found: None,
+        }));
+    }
+    if !status.is_success() {
+        return Err(Attempt::Status(status));
+    }
+
+    // 检查对象版本
+    let found = ObjectVersion {
+        etag: header_string(headers, ETAG),
+        last_modified: header_string(headers, LAST_MODIFIED),
+    };
+    if let Some(version) = version {
+        match version.get() {
+            Some(expected) if expected.conflicts_with(&found) => {
+                return Err(Attempt::Changed(ObjectChangedError {
+                    expected: expected.clone(),
+                    found: Some(found),
+                }));
+            }
+            Some(_) => {}
+            None if found.is_empty() => {}
+            None => {
+                // 并发的第一批请求中只有一个版本会被记录,其余请求与记录的版本比较
+                let pinned = version.get_or_init(|| found.clone());
+                if pinned.conflicts_with(&found) {
+                    return Err(Attempt::Changed(ObjectChangedError {
+                        expected: pinned.clone(),
+                        found: Some(found),
+                    }));
+                }
+            }
+        }
+    }
+
+    // 检查响应是否对应请求的范围
+    match status {
+        StatusCode::PARTIAL_CONTENT => {
+            let content_range = header_string(headers, CONTENT_RANGE);
+            match content_range.as_deref().and_then(parse_content_range) {
+                Some((range_start, range_end)) if range_start == start && range_end <= end => {
+                    Ok(())
+                }
+                _ => Err(Attempt::Invalid(format!(
+                    "Content-Range {content_range:?} 与请求的范围 {start}-{end} 不符"
+                ))),
+            }
+        }
+        // 服务器忽略了 Range 并返回整个对象,只有从头读取时才能截取使用,
+        // 调用方只读取响应体开头需要的字节
+        StatusCode::OK if start == 0 => Ok(()),
+        _ => Err(Attempt::Invalid(format!(
+            "服务器未按 Range 返回部分内容: {status}"
+        ))),
+    }
+}
+
+/// 阻塞 HTTP 范围读取器
+///
+/// 通过阻塞的 HTTP Range 请求实现 [`ReadRange`],
+/// 适用于命令行工具和基于 rayon 的批处理任务
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::{BlockingHttpReader, CloudTiff};
+///
+/// let reader = BlockingHttpReader::new("https://example.com/data.tif").unwrap();
+/// let cog = CloudTiff::open_from_range_reader(&reader).unwrap();
+/// let preview = cog
+///     .renderer()
+///     .with_mp_limit(1.0)
+///     .with_range_reader(reader)
+///     .render()
+///     .unwrap();
+/// ```
+pub struct BlockingHttpReader {
+    /// 阻塞 HTTP 客户端
+    client: reqwest::blocking::Client,
+    /// 远程文件的 URL
+    url: Url,
+    /// 请求头设置
+    headers: RequestHeaders,
+    /// 请求策略
+    policy: RequestPolicy,
+    /// 并发请求限制
+    limiter: BlockingLimiter,
+    /// 第一次成功请求时记录的对象版本,为 None 时不检查版本
+    version: Option<OnceLock<ObjectVersion>>,
+}
+
+impl BlockingHttpReader {
+    /// 创建新的阻塞 HTTP 读取器
+    ///
+    /// 使用新建的客户端、无额外请求头和默认的 [`RequestPolicy`]
+    ///
+    /// # 参数
+    /// * `url` - 远程文件的 URL
+    ///
+    /// # 错误
+    /// 如果 URL 解析失败则返回错误
+    pub fn new<U: IntoUrl>(url: U) -> Result<Self> {
+        Self::builder(url).build_blocking()
+    }
+
+    /// 创建读取器构建器,使用 [`HttpReaderBuilder::build_blocking`] 完成构建
+    ///
+    /// # 参数
+    /// * `url` - 远程文件的 URL
+    pub fn builder<U: IntoUrl>(url: U) -> HttpReaderBuilder {
+        HttpReaderBuilder::new(url)
+    }
+
+    /// 获取当前的请求策略
+    pub fn policy(&self) -> &RequestPolicy {
+        &self.policy
+    }
+
+    /// 获取远程文件的 URL
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// 获取已记录的远程对象版本
+    pub fn version(&self) -> Option<&ObjectVersion> {
+        self.version.as_ref().and_then(|version| version.get())
+    }
+
+    /// 发送一次范围请求并校验响应
+    ///
+    /// # 参数
+    /// * `start` - 起始字节位置
+    /// * `n` - 要读取的字节数
+    fn attempt(&self, start: u64, n: usize) -> std::result::Result<Vec<u8>, Attempt> {
+        // 注意: HTTP Range 是包含性的,所以需要减1
+        let end = start + n as u64 - 1;
+
+        let mut request = self
+            .client
+            .get(self.url.clone())
+            .headers(self.headers.for_request(&self.url))
+            .header(RANGE, format!("bytes={start}-{end}"));
+        if let Some(timeout) = self.policy.timeout {
+            request = request.timeout(timeout);
+        }
+        // 要求服务器只在对象未改变时返回数据
+        if let Some((name, value)) = precondition(self.version.as_ref()) {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().map_err(Attempt::Request)?;
+        check_response(
+            self.version.as_ref(),
+            response.status(),
+            response.headers(),
+            start,
+            end,
+        )?;
+
+        // 忽略 Range 的服务器会返回整个对象,只读取需要的字节后丢弃连接
+        let mut bytes = Vec::with_capacity(n);
+        response
+            .take(n as u64)
+            .read_to_end(&mut bytes)
+            .map_err(Attempt::Body)?;
+        Ok(bytes)
+    }
+}
+
+impl ReadRange for BlockingHttpReader {
+    /// 读取指定范围的数据
+    ///
+    /// 在并发许可内发送请求,超时或遇到临时错误时按退避时间重试
+    ///
+    /// # 参数
+    /// * `start` - 起始字节位置
+    /// * `buf` - 目标缓冲区
+    fn read_range(&self, start: u64, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut attempt = 0;
+        loop {
+            let result = {
+                // 持有许可直到响应体读取完毕
+                let _permit = self.limiter.acquire();
+                self.attempt(start, buf.len())
+            };
+
+            match result {
+                Ok(bytes) => {
+                    buf[..bytes.len()].copy_from_slice(&bytes);
+                    return Ok(bytes.len());
+                }
+                Err(e) if e.is_retryable() && attempt < self.policy.max_retries => {
+                    let delay = self.policy.backoff(attempt);
+                    attempt += 1;
+                    warn!("HTTP 范围请求失败,{delay:?} 后第 {attempt} 次重试: {e:?}");
+                    std::thread::sleep(delay);
+                }
+                Err(e) => return Err(e.into_io_error(&self.url)),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BlockingHttpReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingHttpReader")
+            .field("url", &self.url)
+            .field("headers", &self.headers)
+            .field("policy", &self.policy)
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+/// 同步的计数信号量
+///
+/// 限制同一个阻塞读取器上同时进行的请求数
+struct BlockingLimiter {
+    /// 剩余的许可数
+    available: Mutex<usize>,
+    /// 许可被归还时发出通知
+    released: Condvar,
+}
+
+/// 许可守卫,离开作用域时归还许可
+struct BlockingPermit<'a>(&'a BlockingLimiter);
+
+impl BlockingLimiter {
+    /// 创建拥有指定许可数的信号量
+    fn new(permits: usize) -> Self {
+        Self {
+            available: Mutex::new(permits.max(1)),
+            released: Condvar::new(),
+        }
+    }
+
+    /// 等待并获取一个许可
+    fn acquire(&self) -> BlockingPermit<'_> {
+        let mut available = self
+            .available
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        *available -= 1;
+        BlockingPermit(self)
+    }
+}
+
+impl Drop for BlockingPermit<'_> {
+    fn drop(&mut self) {
+        let mut available = self
+            .0
+            .available
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *available += 1;
+        self.0.released.notify_one();
+    }
+}
+
+/// 读取字符串形式的响应头
+fn header_string(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+/// 解析 `Content-Range: bytes start-end/total` 响应头
+///
+/// # 返回
+/// 返回 (起始位置, 结束位置),两者都是包含性的
+fn parse_content_range(value: &str) -> Option<(u64, u64)> {
+    let range = value.trim().strip_prefix("bytes")?.trim_start();
+    let (range, _total) = range.split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+}
+
+/// 单次请求失败的原因
+#[derive(Debug)]
+enum Attempt {
+    /// 服务器返回了非成功状态码
+    Status(StatusCode),
+    /// 请求超时
+    TimedOut,
+    /// 连接或传输错误
+    Request(reqwest::Error),
+    /// 读取阻塞响应体失败
+    Body(Error),
+    /// 响应与请求的范围不符
+    Invalid(String),
+    /// 远程对象已被替换
+    Changed(ObjectChangedError),
+}
+
+impl Attempt {
+    /// 是否为可以重试的临时错误
+    fn is_retryable(&self) -> bool {
+        match self {
+            Attempt::Status(status) => RequestPolicy::is_retryable_status(status.as_u16()),
+            Attempt::TimedOut => true,
+            Attempt::Request(e) => e.is_timeout() || e.is_connect() || e.is_body(),
+            Attempt::Body(_) => true,
+            Attempt::Invalid(_) | Attempt::Changed(_) => false,
+        }
+    }
+
+    /// 转换为 IO 错误
+    fn into_io_error(self, url: &Url) -> Error {
+        match self {
+            Attempt::Status(status) => {
+                let kind = match status {
+                    StatusCode::NOT_FOUND => ErrorKind::NotFound,
+                    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
+                    StatusCode::RANGE_NOT_SATISFIABLE => ErrorKind::UnexpectedEof,
+                    _ => ErrorKind::Other,
+                };
+                Error::new(kind, format!("HTTP 请求 {url} 失败: {status}"))
+            }
+            Attempt::TimedOut => Error::new(ErrorKind::TimedOut, format!("HTTP 请求 {url} 超时")),
+            Attempt::Request(e) if e.is_timeout() => {
+                Error::new(ErrorKind::TimedOut, format!("{e:?}"))
+            }
+            Attempt::Request(e) if e.is_body() || e.is_decode() => {
+                Error::new(ErrorKind::InvalidData, format!("{e:?}"))
+            }
+            Attempt::Request(e) => Error::new(ErrorKind::NotConnected, format!("{e:?}")),
+            Attempt::Body(e) => e,
+            Attempt::Invalid(message) => Error::new(
+                ErrorKind::InvalidData,
+                format!("HTTP 请求 {url}: {message}"),
+            ),
+            Attempt::Changed(e) => e.into_io_error(),
+        }
+    }
 }
 
 impl AsyncRead for HttpReader {
@@ -163,10 +956,8 @@ impl AsyncRead for HttpReader {
             Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
             // 请求成功完成
             Poll::Ready(Ok(bytes)) => {
-                let n = bytes.len().max(buf.remaining());
-                let target = buf.initialize_unfilled_to(n);
-                target.copy_from_slice(&bytes[..]);
-                buf.advance(n);
+                let n = bytes.len().min(buf.remaining());
+                buf.put_slice(&bytes[..n]);
                 self.position += n as u64;
                 Poll::Ready(Ok(()))
             }
@@ -7,11 +7,13 @@
 //! 这些特性是 std::io::{Read + Seek} 和 tokio::io::{AsyncRead + AsyncSeek} 的超集。
 //! 主要区别在于 self 是不可变的,这使其成为 HTTP 字节范围请求等场景的强大抽象。
 
+use std::fmt;
 use std::io::{Error, ErrorKind, Result};
 use std::io::{Read, Seek};
 use std::sync::Mutex;
 
 pub mod http;
+pub mod policy;
 pub mod s3;
 
 /// 无状态的同步范围读取特性
@@ -73,6 +75,74 @@ pub trait ReadRange {
     }
 }
 
+/// 远程对象的版本标识
+///
+/// 远程读取器在第一次成功请求时记录对象的版本,
+/// 之后的范围请求都要求对象仍然是同一个版本
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectVersion {
+    /// 实体标签(ETag)
+    pub etag: Option<String>,
+    /// 最后修改时间(Last-Modified)
+    pub last_modified: Option<String>,
+}
+
+impl ObjectVersion {
+    /// 是否不包含任何版本信息
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// 获取可以用于 If-Match 的强 ETag
+    ///
+    /// 弱 ETag(以 `W/` 开头)在 If-Match 中总是比较失败,因此返回 None
+    pub fn strong_etag(&self) -> Option<&str> {
+        self.etag.as_deref().filter(|etag| !etag.starts_with("W/"))
+    }
+
+    /// 判断另一个版本是否与当前版本冲突
+    ///
+    /// 只比较双方都提供的字段,缺失的字段不视为冲突
+    pub fn conflicts_with(&self, other: &ObjectVersion) -> bool {
+        let differs = |a: &Option<String>, b: &Option<String>| match (a, b) {
+            (Some(a), Some(b)) => a != b,
+            _ => false,
+        };
+        differs(&self.etag, &other.etag) || differs(&self.last_modified, &other.last_modified)
+    }
+}
+
+/// 远程对象在读取过程中被替换的错误
+///
+/// 作为 `std::io::Error` 的内部错误返回,
+/// 转换为 `CloudTiffError` 时会变为 `CloudTiffError::RemoteObjectChanged`
+#[derive(Clone, Debug)]
+pub struct ObjectChangedError {
+    /// 打开时记录的版本
+    pub expected: ObjectVersion,
+    /// 服务器当前返回的版本,未知时为 None
+    pub found: Option<ObjectVersion>,
+}
+
+impl ObjectChangedError {
+    /// 包装为 IO 错误
+    pub fn into_io_error(self) -> Error {
+        Error::other(self)
+    }
+}
+
+impl fmt::Display for ObjectChangedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "远程对象已改变: 期望 {:?}, 实际 {:?}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ObjectChangedError {}
+
 /// 为实现了 Read + Seek 的类型实现 ReadRange
 impl<R: Read + Seek> ReadRange for Mutex<R> {
     fn read_range(&self, start: u64, buf: &mut [u8]) -> Result<usize> {
@@ -115,6 +185,14 @@ mod not_sync {
             buf: &'a mut [u8],
         ) -> BoxFuture<'a, Result<usize>>;
 
+        /// 建议的最大并发请求数
+        ///
+        /// 批量读取分块时以此限制同时发起的请求数量,
+        /// `None` 表示由调用方决定
+        fn max_in_flight(&self) -> Option<usize> {
+            None
+        }
+
         /// 异步精确读取指定长度的字节
         ///
         /// # 参数
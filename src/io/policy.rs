@@ -0,0 +1,119 @@
+//! 远程请求策略模块
+//!
+//! 本模块定义了远程范围读取器(HTTP、S3)共用的请求策略:
+//! - 最大并发请求数
+//! - 单次请求超时
+//! - 针对 5xx/429 等临时错误的指数退避重试
+//!
+//! 策略本身只是配置数据,同步和异步读取器各自负责执行。
+
+use std::time::Duration;
+
+/// 远程请求策略
+///
+/// # 字段说明
+///
+/// * `max_in_flight` - 同一读取器上允许同时进行的最大请求数
+/// * `timeout` - 单次请求(包括读取响应体)的超时时间,`None` 表示不限制
+/// * `max_retries` - 临时错误的最大重试次数,0 表示不重试
+/// * `initial_backoff` - 第一次重试前的等待时间
+/// * `max_backoff` - 退避等待时间的上限
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RequestPolicy {
+    /// 最大并发请求数
+    pub max_in_flight: usize,
+    /// 单次请求超时
+    pub timeout: Option<Duration>,
+    /// 最大重试次数
+    pub max_retries: u32,
+    /// 初始退避时间
+    pub initial_backoff: Duration,
+    /// 最大退避时间
+    pub max_backoff: Duration,
+}
+
+impl Default for RequestPolicy {
+    /// 默认策略:
+    /// - 最多 32 个并发请求
+    /// - 30 秒超时
+    /// - 最多重试 3 次,退避时间从 100ms 开始,最长 5s
+    fn default() -> Self {
+        Self {
+            max_in_flight: 32,
+            timeout: Some(Duration::from_secs(30)),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RequestPolicy {
+    /// 不限并发、不超时、不重试的策略
+    ///
+    /// 与旧版读取器的行为一致
+    pub fn unlimited() -> Self {
+        Self {
+            max_in_flight: usize::MAX >> 3, // tokio 信号量的许可数上限
+            timeout: None,
+            max_retries: 0,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    /// 设置最大并发请求数(至少为 1)
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// 实际使用的并发许可数
+    ///
+    /// 直接构造的策略可能把 `max_in_flight` 设为 0,此时按 1 处理,避免请求永远等待
+    #[cfg(any(feature = "http", feature = "s3"))]
+    pub(crate) fn permits(&self) -> usize {
+        self.max_in_flight.max(1)
+    }
+
+    /// 设置单次请求超时
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置最大重试次数
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// 设置退避时间范围
+    ///
+    /// # 参数
+    /// * `initial` - 第一次重试前的等待时间
+    /// * `max` - 等待时间上限
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// 计算第 `attempt` 次重试前的等待时间(从 0 开始计数)
+    ///
+    /// 等待时间按 2 的幂次增长,并限制在 `max_backoff` 以内
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1_u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    /// 判断 HTTP 状态码是否属于可重试的临时错误
+    ///
+    /// 5xx 服务端错误和 429 (Too Many Requests) 会被重试
+    pub fn is_retryable_status(status: u16) -> bool {
+        status == 429 || (500..600).contains(&status)
+    }
+}
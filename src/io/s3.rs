@@ -2,21 +2,58 @@
 //!
 //! 本模块提供了通过 AWS S3 GetObject API 实现的异步范围读取功能。
 //! 支持按需获取 S3 对象的指定字节范围。
+//!
+//! 与 HTTP 读取器一样,所有请求都遵循读取器的 [`RequestPolicy`]。
+//!
+//! 此外还支持:
+//! - 通过 HeadObject 获取对象大小和版本
+//! - 固定 `VersionId`(或 ETag),避免长时间渲染过程中混用不同版本的对象
+//! - 请求者付费(Requester Pays)存储桶
 
 #![cfg(feature = "s3")]
 
-use super::AsyncReadRange;
+use super::policy::RequestPolicy;
+use super::{AsyncReadRange, ObjectChangedError, ObjectVersion};
+use aws_sdk_s3::error::SdkError;
+use aws_sdk_s3::operation::head_object::builders::HeadObjectFluentBuilder;
+use aws_sdk_s3::primitives::DateTimeFormat;
+use aws_sdk_s3::types::RequestPayer;
 use aws_sdk_s3::{self, operation::get_object::builders::GetObjectFluentBuilder, Client};
 use futures::future::BoxFuture;
 use std::fmt;
+use std::future::Future;
 use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::*;
 
 /// S3 范围读取器
 ///
 /// 通过 AWS S3 GetObject API 实现对象的异步读取
 pub struct S3Reader {
+    /// S3 客户端,用于 HeadObject 请求
+    ///
+    /// 通过 `from_request_builder` 创建时为 None
+    client: Option<Client>,
     /// GetObject 请求构建器
     request: GetObjectFluentBuilder,
+    /// 固定的对象版本
+    pinned: Option<ObjectVersion>,
+    /// 请求策略
+    policy: RequestPolicy,
+    /// 并发请求限制
+    limiter: Arc<Semaphore>,
+}
+
+/// HeadObject 返回的对象信息
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct S3ObjectInfo {
+    /// 对象大小(字节)
+    pub size: u64,
+    /// 对象的版本 ID,未启用版本控制的存储桶为 None
+    pub version_id: Option<String>,
+    /// 对象的 ETag 和最后修改时间
+    pub version: ObjectVersion,
 }
 
 impl S3Reader {
@@ -28,15 +65,147 @@ impl S3Reader {
     /// * `key` - 对象键名
     pub fn new(client: Client, bucket: &str, key: &str) -> Self {
         let request = client.get_object().bucket(bucket).key(key);
-        Self { request }
+        Self {
+            client: Some(client),
+            ..Self::from_request_builder(request)
+        }
     }
 
     /// 从已有的请求构建器创建读取器
     ///
+    /// 这种方式创建的读取器没有客户端,不支持 HeadObject 相关功能
+    ///
     /// # 参数
     /// * `request` - GetObject 请求构建器
     pub fn from_request_builder(request: GetObjectFluentBuilder) -> Self {
-        Self { request }
+        let policy = RequestPolicy::default();
+        Self {
+            client: None,
+            request,
+            pinned: None,
+            policy,
+            limiter: Arc::new(Semaphore::new(policy.permits())),
+        }
+    }
+
+    /// 设置请求策略
+    ///
+    /// # 参数
+    /// * `policy` - 并发、超时和重试策略
+    pub fn with_policy(mut self, policy: RequestPolicy) -> Self {
+        self.limiter = Arc::new(Semaphore::new(policy.permits()));
+        self.policy = policy;
+        self
+    }
+
+    /// 读取对象的指定版本
+    ///
+    /// # 参数
+    /// * `version_id` - 对象的版本 ID
+    pub fn with_version_id(mut self, version_id: &str) -> Self {
+        self.request = self.request.version_id(version_id);
+        self
+    }
+
+    /// 设置是否以请求者付费的方式访问存储桶
+    ///
+    /// 访问要求请求者付费的公共数据集时需要开启
+    pub fn with_requester_pays(mut self, requester_pays: bool) -> Self {
+        let payer = requester_pays.then_some(RequestPayer::Requester);
+        self.request = self.request.set_request_payer(payer);
+        self
+    }
+
+    /// 获取当前的请求策略
+    pub fn policy(&self) -> &RequestPolicy {
+        &self.policy
+    }
+
+    /// 获取读取的对象版本 ID
+    pub fn version_id(&self) -> Option<&str> {
+        self.request.get_version_id().as_deref()
+    }
+
+    /// 获取固定的对象版本
+    pub fn pinned_version(&self) -> Option<&ObjectVersion> {
+        self.pinned.as_ref()
+    }
+
+    /// 通过 HeadObject 获取对象信息
+    ///
+    /// 请求使用与 GetObject 相同的存储桶、键名、版本 ID 和付费方式
+    ///
+    /// # 错误
+    /// 读取器没有客户端或请求失败时返回错误
+    pub async fn head_async(&self) -> Result<S3ObjectInfo> {
+        let request = self.head_request()?;
+        let output = retry(&self.policy, &self.limiter, move || {
+            let request = request.clone();
+            async move { request.send().await.map_err(sdk_error) }
+        })
+        .await
+        .map_err(|e| e.into_io_error(self.pinned.as_ref()))?;
+
+        Ok(S3ObjectInfo {
+            size: output.content_length().unwrap_or_default().max(0) as u64,
+            version_id: output.version_id().map(|v| v.to_string()),
+            version: ObjectVersion {
+                etag: output.e_tag().map(|v| v.to_string()),
+                last_modified: output
+                    .last_modified()
+                    .and_then(|v| v.fmt(DateTimeFormat::HttpDate).ok()),
+            },
+        })
+    }
+
+    /// 获取对象大小(字节)
+    pub async fn object_size_async(&self) -> Result<u64> {
+        Ok(self.head_async().await?.size)
+    }
+
+    /// 固定当前的对象版本
+    ///
+    /// 如果存储桶启用了版本控制,之后的读取都使用当前的 `VersionId`;
+    /// 否则之后的读取携带 `If-Match`,对象被替换时返回 [`ObjectChangedError`]
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// # async fn example(client: aws_sdk_s3::Client) -> std::io::Result<()> {
+    /// use cloudtiff::S3Reader;
+    ///
+    /// let reader = S3Reader::new(client, "bucket", "key.tif")
+    ///     .with_requester_pays(true)
+    ///     .pin_version_async()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pin_version_async(mut self) -> Result<Self> {
+        let info = self.head_async().await?;
+        if let Some(version_id) = &info.version_id {
+            self.request = self.request.version_id(version_id);
+        } else if let Some(etag) = &info.version.etag {
+            self.request = self.request.if_match(etag);
+        }
+        self.pinned = Some(info.version);
+        Ok(self)
+    }
+
+    /// 创建与 GetObject 请求对应的 HeadObject 请求
+    fn head_request(&self) -> Result<HeadObjectFluentBuilder> {
+        let Some(client) = &self.client else {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "S3Reader 没有客户端,无法发送 HeadObject 请求",
+            ));
+        };
+        Ok(client
+            .head_object()
+            .set_bucket(self.request.get_bucket().clone())
+            .set_key(self.request.get_key().clone())
+            .set_version_id(self.request.get_version_id().clone())
+            .set_request_payer(self.request.get_request_payer().clone()))
     }
 }
 
@@ -46,6 +215,10 @@ impl fmt::Debug for S3Reader {
         f.debug_struct("S3Reader")
             .field("bucket", &self.request.get_bucket().as_ref())
             .field("key", &self.request.get_key().as_ref())
+            .field("version_id", &self.version_id())
+            .field("request_payer", &self.request.get_request_payer().as_ref())
+            .field("pinned", &self.pinned)
+            .field("policy", &self.policy)
             .finish()
     }
 }
@@ -64,29 +237,160 @@ impl AsyncReadRange for S3Reader {
         let request_builder = self.request.clone().range(format!("bytes={start}-{end}"));
 
         Box::pin(async move {
-            // 发送 GetObject 请求
-            let request = request_builder.send();
-            let mut response = request
-                .await
-                .map_err(|e| Error::new(ErrorKind::NotConnected, format!("{e:?}")))?;
-
-            // 从响应流中读取数据到缓冲区
-            let mut pos = 0;
-            while let Some(bytes) = response.body.try_next().await.map_err(|err| {
-                Error::new(
-                    ErrorKind::Interrupted,
-                    format!("从 S3 下载流读取失败: {err:?}"),
-                )
-            })? {
-                // 计算本次要复制的字节数
-                let bytes_len = bytes.len();
-                let bytes_top = bytes_len.min(n - pos);
-                let buf_top = n.min(pos + bytes_len);
-                // 将数据复制到目标缓冲区
-                buf[pos..buf_top].copy_from_slice(&bytes[..bytes_top]);
-                pos += bytes_len;
-            }
-            Ok(pos)
+            let bytes = retry(&self.policy, &self.limiter, move || {
+                get_object(request_builder.clone())
+            })
+            .await
+            .map_err(|e| e.into_io_error(self.pinned.as_ref()))?;
+
+            // 将数据复制到目标缓冲区
+            let n = bytes.len().min(n);
+            buf[..n].copy_from_slice(&bytes[..n]);
+            Ok(n)
         })
     }
+
+    fn max_in_flight(&self) -> Option<usize> {
+        Some(self.policy.permits())
+    }
+}
+
+/// 按策略执行请求
+///
+/// 在并发许可内执行请求,超时或遇到临时错误时按退避时间重试,
+/// 重试次数用尽后返回最后一次的错误
+///
+/// # 参数
+/// * `policy` - 请求策略
+/// * `limiter` - 并发请求限制
+/// * `request` - 每次调用发送一次请求
+async fn retry<T, F, Fut>(
+    policy: &RequestPolicy,
+    limiter: &Semaphore,
+    request: F,
+) -> std::result::Result<T, Attempt>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = std::result::Result<T, Attempt>>,
+{
+    let mut attempt = 0;
+    loop {
+        let result = {
+            // 持有许可直到响应体读取完毕
+            let _permit = limiter.acquire().await.map_err(|e| Attempt::Request {
+                retryable: false,
+                status: None,
+                message: format!("{e:?}"),
+            })?;
+            match policy.timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, request()).await {
+                    Ok(result) => result,
+                    Err(_) => Err(Attempt::TimedOut),
+                },
+                None => request().await,
+            }
+        };
+
+        match result {
+            Ok(value) => return Ok(value),
+            Err(e) if e.is_retryable() && attempt < policy.max_retries => {
+                let delay = policy.backoff(attempt);
+                attempt += 1;
+                warn!("S3 请求失败,{delay:?} 后第 {attempt} 次重试: {e:?}");
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 发送一次 GetObject 请求并读取整个响应体
+///
+/// # 参数
+/// * `request_builder` - 已设置 Range 的请求构建器
+async fn get_object(
+    request_builder: GetObjectFluentBuilder,
+) -> std::result::Result<Vec<u8>, Attempt> {
+    // 发送 GetObject 请求
+    let mut response = request_builder.send().await.map_err(sdk_error)?;
+
+    // 从响应流中读取数据
+    let mut buf = Vec::new();
+    while let Some(bytes) = response
+        .body
+        .try_next()
+        .await
+        .map_err(|err| Attempt::Stream(format!("从 S3 下载流读取失败: {err:?}")))?
+    {
+        buf.extend_from_slice(&bytes);
+    }
+    Ok(buf)
+}
+
+/// 将 SDK 错误转换为单次请求失败
+fn sdk_error<E: fmt::Debug>(e: SdkError<E>) -> Attempt {
+    let status = e.raw_response().map(|r| r.status().as_u16());
+    let retryable = match &e {
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) => true,
+        _ => status.is_some_and(RequestPolicy::is_retryable_status),
+    };
+    Attempt::Request {
+        retryable,
+        status,
+        message: format!("{e:?}"),
+    }
+}
+
+/// 单次请求失败的原因
+#[derive(Debug)]
+enum Attempt {
+    /// 请求失败
+    Request {
+        retryable: bool,
+        status: Option<u16>,
+        message: String,
+    },
+    /// 下载流中断
+    Stream(String),
+    /// 请求超时
+    TimedOut,
+}
+
+impl Attempt {
+    /// 是否为可以重试的临时错误
+    fn is_retryable(&self) -> bool {
+        match self {
+            Attempt::Request { retryable, .. } => *retryable,
+            Attempt::Stream(_) | Attempt::TimedOut => true,
+        }
+    }
+
+    /// 转换为 IO 错误
+    ///
+    /// # 参数
+    /// * `pinned` - 固定的对象版本,412 响应会被转换为 [`ObjectChangedError`]
+    fn into_io_error(self, pinned: Option<&ObjectVersion>) -> Error {
+        match self {
+            Attempt::Request {
+                status: Some(412), ..
+            } if pinned.is_some() => ObjectChangedError {
+                expected: pinned.cloned().unwrap_or_default(),
+                found: None,
+            }
+            .into_io_error(),
+            Attempt::Request {
+                status, message, ..
+            } => {
+                let kind = match status {
+                    Some(404) => ErrorKind::NotFound,
+                    Some(401 | 403) => ErrorKind::PermissionDenied,
+                    Some(416) => ErrorKind::UnexpectedEof,
+                    _ => ErrorKind::NotConnected,
+                };
+                Error::new(kind, message)
+            }
+            Attempt::Stream(message) => Error::new(ErrorKind::Interrupted, message),
+            Attempt::TimedOut => Error::new(ErrorKind::TimedOut, "S3 请求超时"),
+        }
+    }
 }
@@ -30,6 +30,10 @@
 // 导出主要模块
 pub mod cog; // COG文件格式处理
 pub mod encode; // 编码相关功能
+#[cfg(feature = "image")]
+pub mod export; // 瓦片包导出
+#[cfg(feature = "footprint")]
+pub mod footprint; // 影像覆盖范围索引
 pub mod geotags; // 地理标签处理
 pub mod io; // IO操作
 pub mod projection; // 投影转换
@@ -41,16 +45,24 @@ pub mod tiff; // TIFF格式处理
 pub use cog::{disect, CloudTiff, CloudTiffError};
 pub use encode::{EncodeError, Encoder, SupportedCompression};
 pub use proj4rs::Proj;
+pub use projection::geo_transform::GeoTransform;
 pub use projection::primatives::{Point2D, Region, UnitFloat};
 pub use projection::Projection;
 pub use raster::{Raster, ResizeFilter};
+pub use render::capabilities::TileService;
+pub use render::cutline::Cutline;
+pub use render::mosaic::{Mosaic, MosaicMode};
+pub use render::resample::Resampling;
 pub use render::tiles;
+pub use render::tms::TileMatrixSet;
+pub use render::warp::GeoRaster;
 
 // IO相关导出
 #[cfg(feature = "http")]
-pub use io::http::HttpReader;
+pub use io::http::{BlockingHttpReader, HttpReader, HttpReaderBuilder};
+pub use io::policy::RequestPolicy;
 #[cfg(feature = "s3")]
-pub use io::s3::S3Reader;
+pub use io::s3::{S3ObjectInfo, S3Reader};
 #[cfg(feature = "async")]
 pub use io::AsyncReadRange;
 pub use io::ReadRange;
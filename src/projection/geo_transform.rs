@@ -0,0 +1,181 @@
+//! 仿射地理变换模块
+//!
+//! 本模块定义了像素网格与坐标系坐标之间的六参数仿射变换,参数约定与 GDAL 相同:
+//!
+//! ```text
+//! x = origin.x + i * pixel_size.x + j * rotation.0
+//! y = origin.y + i * rotation.1   + j * pixel_size.y
+//! ```
+//!
+//! 其中 `(i, j)` 为像素角点坐标(像素 `(0, 0)` 的左上角为 `(0.0, 0.0)`),
+//! 北向上的栅格 `pixel_size.y` 为负。地理坐标系的坐标使用度。
+
+use super::{Projection, ProjectionError};
+use crate::Region;
+use proj4rs::Proj;
+
+/// 仿射地理变换
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoTransform {
+    /// 左上角像素角点的坐标 (x, y)
+    pub origin: (f64, f64),
+    /// 像素尺寸 (x, y),北向上的栅格 y 为负
+    pub pixel_size: (f64, f64),
+    /// 旋转项 (行旋转, 列旋转),对应 GDAL 的第 2 和第 4 个参数
+    pub rotation: (f64, f64),
+}
+
+impl GeoTransform {
+    /// 创建北向上、无旋转的变换
+    ///
+    /// # 参数
+    /// * `origin` - 左上角像素角点的坐标 (x, y)
+    /// * `pixel_size` - 像素尺寸 (x, y),北向上的栅格 y 为负
+    pub fn new(origin: (f64, f64), pixel_size: (f64, f64)) -> Self {
+        Self {
+            origin,
+            pixel_size,
+            rotation: (0.0, 0.0),
+        }
+    }
+
+    /// 设置旋转项
+    ///
+    /// # 参数
+    /// * `row_rotation` - 行方向每像素的 x 增量(GDAL 第 2 个参数)
+    /// * `column_rotation` - 列方向每像素的 y 增量(GDAL 第 4 个参数)
+    pub fn with_rotation(mut self, row_rotation: f64, column_rotation: f64) -> Self {
+        self.rotation = (row_rotation, column_rotation);
+        self
+    }
+
+    /// 从 GDAL 的六参数数组创建变换
+    pub fn from_gdal(coefficients: [f64; 6]) -> Self {
+        let [x, dx, rx, y, ry, dy] = coefficients;
+        Self {
+            origin: (x, y),
+            pixel_size: (dx, dy),
+            rotation: (rx, ry),
+        }
+    }
+
+    /// 转换为 GDAL 的六参数数组
+    pub fn to_gdal(&self) -> [f64; 6] {
+        [
+            self.origin.0,
+            self.pixel_size.0,
+            self.rotation.0,
+            self.origin.1,
+            self.rotation.1,
+            self.pixel_size.1,
+        ]
+    }
+
+    /// 从区域和像素尺寸创建北向上的变换
+    ///
+    /// # 参数
+    /// * `region` - 栅格覆盖的区域
+    /// * `dimensions` - 栅格尺寸 (宽度, 高度)
+    pub fn from_region(region: &Region<f64>, dimensions: (u32, u32)) -> Self {
+        Self::new(
+            (region.x.min, region.y.max),
+            (
+                region.x.range() / dimensions.0 as f64,
+                -region.y.range() / dimensions.1 as f64,
+            ),
+        )
+    }
+
+    /// 是否包含旋转项
+    pub fn is_rotated(&self) -> bool {
+        self.rotation != (0.0, 0.0)
+    }
+
+    /// 计算像素角点坐标对应的坐标系坐标
+    ///
+    /// # 参数
+    /// * `i`, `j` - 像素角点坐标(列, 行)
+    pub fn apply(&self, i: f64, j: f64) -> (f64, f64) {
+        (
+            self.origin.0 + i * self.pixel_size.0 + j * self.rotation.0,
+            self.origin.1 + i * self.rotation.1 + j * self.pixel_size.1,
+        )
+    }
+
+    /// 计算坐标系坐标对应的像素角点坐标
+    ///
+    /// # 返回
+    /// 像素坐标 (列, 行),变换不可逆时返回 None
+    pub fn invert(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let (a, b) = (self.pixel_size.0, self.rotation.0);
+        let (c, d) = (self.rotation.1, self.pixel_size.1);
+        let det = a * d - b * c;
+        if !det.is_normal() {
+            return None;
+        }
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+        Some(((d * dx - b * dy) / det, (a * dy - c * dx) / det))
+    }
+
+    /// 计算栅格四个角点的外包区域
+    ///
+    /// # 参数
+    /// * `dimensions` - 栅格尺寸 (宽度, 高度)
+    pub fn bounds(&self, dimensions: (u32, u32)) -> Region<f64> {
+        let (w, h) = (dimensions.0 as f64, dimensions.1 as f64);
+        let corners = [(0.0, 0.0), (w, 0.0), (w, h), (0.0, h)].map(|(i, j)| self.apply(i, j));
+        let xs = corners.map(|c| c.0);
+        let ys = corners.map(|c| c.1);
+        Region::new(
+            xs.into_iter().fold(f64::MAX, f64::min),
+            ys.into_iter().fold(f64::MAX, f64::min),
+            xs.into_iter().fold(f64::MIN, f64::max),
+            ys.into_iter().fold(f64::MIN, f64::max),
+        )
+    }
+
+    /// 将所有参数乘以系数,用于单位换算(如度转弧度)
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self {
+            origin: (self.origin.0 * factor, self.origin.1 * factor),
+            pixel_size: (self.pixel_size.0 * factor, self.pixel_size.1 * factor),
+            rotation: (self.rotation.0 * factor, self.rotation.1 * factor),
+        }
+    }
+
+    /// 创建栅格的投影对象
+    ///
+    /// 地理坐标系的参数从度转换为投影使用的弧度
+    ///
+    /// # 参数
+    /// * `epsg` - 坐标系 EPSG 代码
+    /// * `dimensions` - 栅格尺寸 (宽度, 高度)
+    ///
+    /// # 错误
+    /// EPSG 代码不受支持、像素尺寸无效或包含旋转项(投影对象不支持旋转)时返回错误
+    pub fn to_projection(
+        &self,
+        epsg: u16,
+        dimensions: (u32, u32),
+    ) -> Result<Projection, ProjectionError> {
+        if self.is_rotated() {
+            return Err(ProjectionError::UnsupportedModelTransformation);
+        }
+        let proj = Proj::from_epsg_code(epsg)?;
+        let transform = if proj.is_latlong() {
+            self.scaled(1_f64.to_radians())
+        } else {
+            *self
+        };
+        let (dx, dy) = transform.pixel_size;
+        if !dx.is_normal() || !dy.is_normal() {
+            return Err(ProjectionError::InvalidScale((dx, dy, 0.0)));
+        }
+        Ok(Projection {
+            epsg,
+            proj,
+            origin: (transform.origin.0, transform.origin.1, 0.0),
+            scale: (dx * dimensions.0 as f64, -dy * dimensions.1 as f64, 0.0),
+        })
+    }
+}
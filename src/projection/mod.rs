@@ -17,9 +17,20 @@ use primatives::{Point2D, Region};
 use proj4rs::errors::Error as Proj4Error;
 use proj4rs::proj::Proj;
 use proj4rs::transform::transform;
+use std::f64::consts::{FRAC_PI_2, PI};
 
+pub mod geo_transform;
 pub mod primatives;
 
+/// 地球平均半径(米),用于将经纬度和 Web 墨卡托的像素尺寸换算为地面距离
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Web墨卡托投影 x 和 y 方向的最大坐标(米)
+const WEB_MERCATOR_EXTENT: f64 = crate::render::wmts::WEB_MERCATOR_EXTENT;
+
+/// 计算边界时每条边的采样点数
+const BOUNDS_EDGE_SAMPLES: usize = 32;
+
 /// 投影错误类型
 #[derive(Debug)]
 pub enum ProjectionError {
@@ -291,6 +302,8 @@ impl Projection {
     }
 
     /// 获取经纬度边界(度)
+    ///
+    /// 跨越 ±180° 经线时最大经度大于 180,参见 [`Projection::bounds`]
     pub fn bounds_lat_lon_deg(&self) -> Result<Region<f64>, ProjectionError> {
         // 获取 EPSG:4326 (WGS84) 坐标系下的边界（弧度）
         let radians = self.bounds(4326);
@@ -305,35 +318,88 @@ impl Projection {
     }
 
     /// 获取指定 EPSG 坐标系下的边界
+    ///
+    /// 沿影像四条边加密采样并投影到目标坐标系。目标为地理坐标系或 Web 墨卡托时:
+    /// - 跨越 ±180° 经线的影像返回环绕的区域,x 轴最大值超过周期边界(如经度大于 π),
+    ///   可以使用 [`Region::split_wrapped`] 拆分
+    /// - 包含极点的影像(如极地立体投影)的边界扩展到极点,经度覆盖整个周期
+    ///
+    /// 所有采样点都无法转换时返回空区域(最小值大于最大值)
     pub fn bounds(&self, epsg: u16) -> Region<f64> {
-        // 采样8个点来确定边界
-        // 这些点分别位于图像的四个角落和四条边的中点
-        // 通过遍历这些点并将它们投影到目标坐标系中
-        // 我们可以得到一个近似的边界区域
-        vec![
-            [0.0, 0.0], // 左上角
-            [0.5, 0.0], // 上边中点
-            [1.0, 0.0], // 右上角
-            [1.0, 0.5], // 右边中点
-            [1.0, 1.0], // 右下角
-            [0.5, 1.0], // 下边中点
-            [0.0, 1.0], // 左下角
-            [0.0, 0.5], // 左边中点
-        ]
-        .into_iter()
-        .fold(
-            Region::new(f64::MAX, f64::MAX, f64::MIN, f64::MIN),
-            |region, [u, v]| {
-                // 尝试将每个点转换到目标坐标系
-                if let Ok((x, y, _)) = self.transform_into(u, v, 0.0, epsg) {
-                    // 如果转换成功，则扩展边界区域
-                    region.extend(&Point2D { x, y })
-                } else {
-                    // 如果转换失败，保持原有边界不变
-                    region
+        let empty = Region::new(f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        let Ok(to) = Proj::from_epsg_code(epsg) else {
+            return empty;
+        };
+        let half_period = wrap_half_period_in(epsg, to.is_latlong());
+
+        // 沿四条边按顺时针顺序采样,组成闭合的边界环
+        let n = BOUNDS_EDGE_SAMPLES;
+        let ring: Vec<Point2D<f64>> = (0..4 * n)
+            .filter_map(|i| {
+                let t = (i % n) as f64 / n as f64;
+                let (u, v) = match i / n {
+                    0 => (t, 0.0),       // 上边
+                    1 => (1.0, t),       // 右边
+                    2 => (1.0 - t, 1.0), // 下边
+                    _ => (0.0, 1.0 - t), // 左边
+                };
+                let (x, y, _) = self.transform_into_proj(&to, u, v, 0.0).ok()?;
+                (x.is_finite() && y.is_finite()).then_some(Point2D { x, y })
+            })
+            .collect();
+        let mut region = match half_period {
+            Some(half_period) => unwrap_ring(&ring, half_period).unwrap_or(empty),
+            None => ring
+                .iter()
+                .fold(empty, |region, point| region.extend(point)),
+        };
+
+        // 地理坐标系的影像中极点是一条边,不需要单独处理
+        if self.proj.is_latlong() {
+            return region;
+        }
+        // 极点位于影像内时,边界扩展到极点
+        for lat in [FRAC_PI_2, -FRAC_PI_2] {
+            let Ok((u, v, _)) = self.transform_from(0.0, lat, 0.0, 4326) else {
+                continue;
+            };
+            if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+                continue;
+            }
+            // 对跖极点在方位投影中没有有效坐标,通过逆变换确认
+            match self.transform_into(u, v, 0.0, 4326) {
+                Ok((_, pole_lat, _)) if (pole_lat - lat).abs() < 1e-6 => {}
+                _ => continue,
+            }
+            match half_period {
+                Some(half_period) => {
+                    // Web 墨卡托无法表示极点,使用其 y 轴范围
+                    let pole_y = if to.is_latlong() {
+                        lat
+                    } else {
+                        half_period.copysign(lat)
+                    };
+                    region = Region::new(
+                        -half_period,
+                        region.y.min.min(pole_y),
+                        half_period,
+                        region.y.max.max(pole_y),
+                    );
+                }
+                None => {
+                    let mut pole = (0.0, lat, 0.0);
+                    let transformed = Proj::from_epsg_code(4326)
+                        .and_then(|from| transform(&from, &to, &mut pole));
+                    if transformed.is_ok() && pole.0.is_finite() && pole.1.is_finite() {
+                        region = region.extend(&Point2D {
+                            x: pole.0,
+                            y: pole.1,
+                        });
+                    }
                 }
-            },
-        )
+            }
+        }
+        region
     }
 
     /// 获取指定投影下的边界
@@ -345,4 +411,257 @@ impl Projection {
         // 创建并返回包含边界的 Region 对象
         Ok(Region::new(left, bottom, right, top))
     }
+
+    /// 计算像素的地面尺寸(米)
+    ///
+    /// 地理坐标系按纬度换算为米,其他投影直接使用投影单位
+    ///
+    /// # 参数
+    /// * `pixel_size` - 像素在本投影下的尺寸 (x, y)
+    /// * `v` - 像素所在位置的归一化纵坐标(0~1),用于纬度修正
+    pub fn ground_pixel_size(&self, pixel_size: (f64, f64), v: f64) -> (f64, f64) {
+        let y = self.origin.1 - v * self.scale.1;
+        ground_pixel_size_in(self.epsg, self.proj.is_latlong(), pixel_size, y)
+    }
+}
+
+/// 计算指定坐标系下像素的地面尺寸(米)
+///
+/// 地理坐标系(弧度)按纬度换算为米,Web 墨卡托(EPSG:3857)按纬度修正比例,
+/// 其他投影直接使用投影单位
+///
+/// # 参数
+/// * `epsg` - 坐标系 EPSG 代码
+/// * `pixel_size` - 像素在该坐标系下的尺寸 (x, y)
+/// * `y` - 像素所在位置的纵坐标
+///
+/// # 错误
+/// 如果 EPSG 代码不受支持则返回错误
+pub fn ground_pixel_size(
+    epsg: u16,
+    pixel_size: (f64, f64),
+    y: f64,
+) -> Result<(f64, f64), ProjectionError> {
+    let is_latlong = Proj::from_epsg_code(epsg)?.is_latlong();
+    Ok(ground_pixel_size_in(epsg, is_latlong, pixel_size, y))
+}
+
+/// 获取坐标系 x 轴的半个周期
+///
+/// 地理坐标系(弧度)的经度范围为 [-π, π],Web 墨卡托(EPSG:3857)为
+/// [-20037508.34, 20037508.34],超出范围的坐标与平移一个周期后的坐标相同。
+/// 其他投影不循环,返回 None
+///
+/// # 参数
+/// * `epsg` - 坐标系 EPSG 代码
+///
+/// # 错误
+/// 如果 EPSG 代码不受支持则返回错误
+pub fn wrap_half_period(epsg: u16) -> Result<Option<f64>, ProjectionError> {
+    let is_latlong = Proj::from_epsg_code(epsg)?.is_latlong();
+    Ok(wrap_half_period_in(epsg, is_latlong))
+}
+
+/// 根据坐标系类型获取 x 轴的半个周期
+fn wrap_half_period_in(epsg: u16, is_latlong: bool) -> Option<f64> {
+    if is_latlong {
+        Some(PI)
+    } else if epsg == 3857 {
+        Some(WEB_MERCATOR_EXTENT)
+    } else {
+        None
+    }
+}
+
+/// 计算循环坐标系中闭合边界环的范围
+///
+/// 相邻两点的 x 坐标相差超过半个周期时视为跨越周期边界,将后续点平移一个周期使环连续。
+/// 绕行一周后未回到起点的环包围了极点,x 轴覆盖整个周期;
+/// 否则将结果平移到最小值位于 [-半周期, 半周期) 内,最大值可能超过半周期
+///
+/// # 参数
+/// * `ring` - 按顺序排列的边界点
+/// * `half_period` - x 轴的半个周期
+///
+/// # 返回
+/// 边界环为空时返回 None
+fn unwrap_ring(ring: &[Point2D<f64>], half_period: f64) -> Option<Region<f64>> {
+    let period = 2.0 * half_period;
+    let first = ring.first()?;
+    let mut region = Region::new(first.x, first.y, first.x, first.y);
+    let mut previous = first.x;
+    let mut offset = 0.0;
+    // 最后回到起点以检查环是否闭合
+    for point in ring[1..].iter().chain([first]) {
+        let dx = point.x - previous;
+        if dx > half_period {
+            offset -= period;
+        } else if dx < -half_period {
+            offset += period;
+        }
+        previous = point.x;
+        region = region.extend(&Point2D {
+            x: point.x + offset,
+            y: point.y,
+        });
+    }
+
+    if offset != 0.0 || region.x.range() >= period {
+        return Some(Region::new(
+            -half_period,
+            region.y.min,
+            half_period,
+            region.y.max,
+        ));
+    }
+    let shift = ((region.x.min + half_period) / period).floor() * period;
+    Some(Region::new(
+        region.x.min - shift,
+        region.y.min,
+        region.x.max - shift,
+        region.y.max,
+    ))
+}
+
+/// 根据坐标系类型计算像素的地面尺寸
+fn ground_pixel_size_in(epsg: u16, is_latlong: bool, pixel_size: (f64, f64), y: f64) -> (f64, f64) {
+    let (dx, dy) = (pixel_size.0.abs(), pixel_size.1.abs());
+    if is_latlong {
+        // 弧度换算为米,经度方向按纬度收缩
+        (dx * EARTH_RADIUS * y.cos(), dy * EARTH_RADIUS)
+    } else if epsg == 3857 {
+        // Web 墨卡托在纬度 φ 处的比例为 1/cos(φ)
+        let scale = (y / 6_378_137.0).sinh().atan().cos();
+        (dx * scale, dy * scale)
+    } else {
+        (dx, dy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按 (x, y) 坐标创建边界环
+    fn ring(points: &[(f64, f64)]) -> Vec<Point2D<f64>> {
+        points.iter().map(|&(x, y)| Point2D { x, y }).collect()
+    }
+
+    /// 以左上角和尺寸创建投影
+    fn projection(epsg: u16, origin: (f64, f64), size: (f64, f64)) -> Projection {
+        Projection {
+            epsg,
+            proj: Proj::from_epsg_code(epsg).unwrap(),
+            origin: (origin.0, origin.1, 0.0),
+            scale: (size.0, size.1, 0.0),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "实际值 {actual},期望值 {expected}"
+        );
+    }
+
+    #[test]
+    fn unwrap_ring_without_crossing() {
+        let ring = ring(&[(10.0, 5.0), (20.0, 5.0), (20.0, -5.0), (10.0, -5.0)]);
+        let region = unwrap_ring(&ring, 180.0).unwrap();
+        assert_eq!(region, Region::new(10.0, -5.0, 20.0, 5.0));
+    }
+
+    #[test]
+    fn unwrap_ring_crossing_antimeridian() {
+        let ring = ring(&[
+            (170.0, 10.0),
+            (-170.0, 10.0),
+            (-170.0, -10.0),
+            (170.0, -10.0),
+        ]);
+        let region = unwrap_ring(&ring, 180.0).unwrap();
+        assert_eq!(region, Region::new(170.0, -10.0, 190.0, 10.0));
+        assert_eq!(
+            region.split_wrapped(180.0),
+            vec![
+                Region::new(170.0, -10.0, 180.0, 10.0),
+                Region::new(-180.0, -10.0, -170.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn unwrap_ring_shifted_by_a_period() {
+        // 整个环位于 [-半周期, 半周期) 之外时平移回来
+        let ring = ring(&[(190.0, 1.0), (200.0, 1.0), (200.0, 0.0), (190.0, 0.0)]);
+        let region = unwrap_ring(&ring, 180.0).unwrap();
+        assert_eq!(region, Region::new(-170.0, 0.0, -160.0, 1.0));
+    }
+
+    #[test]
+    fn unwrap_ring_around_pole() {
+        // 绕行一周的环包围极点,x 轴覆盖整个周期
+        let ring = ring(&[
+            (-150.0, 60.0),
+            (-90.0, 61.0),
+            (-30.0, 62.0),
+            (30.0, 63.0),
+            (90.0, 64.0),
+            (150.0, 65.0),
+        ]);
+        let region = unwrap_ring(&ring, 180.0).unwrap();
+        assert_eq!(region, Region::new(-180.0, 60.0, 180.0, 65.0));
+    }
+
+    #[test]
+    fn unwrap_empty_ring() {
+        assert_eq!(unwrap_ring(&[], 180.0), None);
+    }
+
+    #[test]
+    fn bounds_of_geographic_raster_crossing_antimeridian() {
+        // 经度 170° ~ 190°(即 -170°),纬度 -10° ~ 10°
+        let projection = projection(
+            4326,
+            (170_f64.to_radians(), 10_f64.to_radians()),
+            (20_f64.to_radians(), 20_f64.to_radians()),
+        );
+        let bounds = projection.bounds_lat_lon_deg().unwrap();
+        assert_close(bounds.x.min, 170.0);
+        assert_close(bounds.x.max, 190.0);
+        assert_close(bounds.y.min, -10.0);
+        assert_close(bounds.y.max, 10.0);
+
+        // 写入 TileJSON 等格式时东边界折回,西边界大于东边界
+        let (west, _, east, _) = bounds.wrapped_bounds(180.0);
+        assert_close(west, 170.0);
+        assert_close(east, -170.0);
+        assert_close(bounds.wrapped_center(180.0).x.abs(), 180.0);
+    }
+
+    #[test]
+    fn bounds_of_polar_stereographic_raster_containing_pole() {
+        // 南极立体投影中以极点为中心的 2000km 见方的影像
+        let projection = projection(3031, (-1e6, 1e6), (2e6, 2e6));
+        let bounds = projection.bounds(4326);
+        assert_close(bounds.x.min, -PI);
+        assert_close(bounds.x.max, PI);
+        assert_close(bounds.y.min, -FRAC_PI_2);
+        assert!(bounds.y.max < -70_f64.to_radians());
+
+        // Web 墨卡托无法表示极点,y 轴扩展到其范围的边界
+        let bounds = projection.bounds(3857);
+        assert_close(bounds.x.min, -WEB_MERCATOR_EXTENT);
+        assert_close(bounds.x.max, WEB_MERCATOR_EXTENT);
+        assert_close(bounds.y.min, -WEB_MERCATOR_EXTENT);
+    }
+
+    #[test]
+    fn bounds_of_polar_stereographic_raster_beside_pole() {
+        // 不包含极点的影像不扩展到整个周期
+        let projection = projection(3031, (1e6, 2e6), (1e6, 1e6));
+        let bounds = projection.bounds(4326);
+        assert!(bounds.x.range() < PI);
+        assert!(bounds.y.min > -FRAC_PI_2 + 1e-3);
+    }
 }
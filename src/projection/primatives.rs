@@ -181,6 +181,69 @@ impl Region<f64> {
         )
     }
 
+    /// 判断两个区域是否相交(包括边界接触)
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.x.min <= other.x.max
+            && other.x.min <= self.x.max
+            && self.y.min <= other.y.max
+            && other.y.min <= self.y.max
+    }
+
+    /// 拆分在 x 轴上环绕的区域
+    ///
+    /// x 轴最大值超过 `half_period` 时(如跨越 ±180° 经线的经纬度区域),
+    /// 将超出部分平移一个周期,拆分为两个不环绕的区域
+    ///
+    /// # 参数
+    /// * `half_period` - x 轴的半个周期,坐标范围为 [-half_period, half_period]
+    pub fn split_wrapped(&self, half_period: f64) -> Vec<Self> {
+        if self.x.max <= half_period {
+            return vec![*self];
+        }
+        vec![
+            Self::new(self.x.min, self.y.min, half_period, self.y.max),
+            Self::new(
+                -half_period,
+                self.y.min,
+                self.x.max - 2.0 * half_period,
+                self.y.max,
+            ),
+        ]
+    }
+
+    /// 环绕区域的 (西, 南, 东, 北) 边界
+    ///
+    /// x 轴最大值超过 `half_period` 时将东边界平移一个周期,此时西边界大于东边界,
+    /// 这是 TileJSON、MBTiles 等格式表示跨越 ±180° 经线区域的惯例
+    ///
+    /// # 参数
+    /// * `half_period` - x 轴的半个周期,坐标范围为 [-half_period, half_period]
+    pub fn wrapped_bounds(&self, half_period: f64) -> (f64, f64, f64, f64) {
+        let east = if self.x.max > half_period {
+            self.x.max - 2.0 * half_period
+        } else {
+            self.x.max
+        };
+        (self.x.min, self.y.min, east, self.y.max)
+    }
+
+    /// 环绕区域的中心点,x 坐标折回 [-half_period, half_period] 范围内
+    ///
+    /// # 参数
+    /// * `half_period` - x 轴的半个周期
+    pub fn wrapped_center(&self, half_period: f64) -> Point2D<f64> {
+        let mut x = (self.x.min + self.x.max) / 2.0;
+        if x > half_period {
+            x -= 2.0 * half_period;
+        } else if x < -half_period {
+            x += 2.0 * half_period;
+        }
+        Point2D {
+            x,
+            y: (self.y.min + self.y.max) / 2.0,
+        }
+    }
+
     /// 扩展区域以包含指定点
     pub fn extend(self, point: &Point2D<f64>) -> Self {
         Self::new(
@@ -0,0 +1,234 @@
+//! 波段操作模块
+//!
+//! 本模块提供了对栅格波段的操作:
+//! - 选择和重新排列波段,例如从多光谱影像中生成假彩色合成
+//! - 将多个尺寸相同的栅格按波段堆叠为一个栅格
+//!
+//! 所有操作都要求样本按字节对齐。
+
+use super::{ExtraSamples, PhotometricInterpretation, Raster, RasterError, SampleFormat};
+
+impl Raster {
+    /// 选择并重新排列波段
+    ///
+    /// 波段可以按任意顺序选择,也可以重复选择同一个波段。
+    /// 三个及以上波段的结果解释为 RGB,否则解释为灰度,其余波段作为额外样本
+    ///
+    /// # 参数
+    /// * `bands` - 源波段索引(从 0 开始),按输出顺序排列
+    ///
+    /// # 错误
+    /// 如果波段索引无效、未选择任何波段或样本不按字节对齐则返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// # fn example(raster: &cloudtiff::Raster) {
+    /// // 近红外、红、绿假彩色合成
+    /// let false_colour = raster.select_bands(&[3, 2, 1]).unwrap();
+    /// # }
+    /// ```
+    pub fn select_bands(&self, bands: &[usize]) -> Result<Self, RasterError> {
+        if bands.is_empty() {
+            return Err(RasterError::NotSupported("未选择任何波段".into()));
+        }
+        let offsets = self.band_byte_offsets()?;
+        let pixel_size = self.bits_per_pixel as usize / 8;
+
+        // 每个输出波段在源像素中的字节范围
+        let mut ranges = Vec::with_capacity(bands.len());
+        for band in bands {
+            let start = *offsets.get(*band).ok_or_else(|| {
+                RasterError::NotSupported(format!(
+                    "波段索引 {band} 超出范围 (共 {} 个波段)",
+                    self.band_count()
+                ))
+            })?;
+            ranges.push(start..start + self.bits_per_sample[*band] as usize / 8);
+        }
+
+        // 逐像素复制所选波段
+        let pixel_count = self.dimensions.0 as usize * self.dimensions.1 as usize;
+        let out_pixel_size: usize = ranges.iter().map(|r| r.len()).sum();
+        let mut buffer = Vec::with_capacity(pixel_count * out_pixel_size);
+        for pixel in self.buffer.chunks_exact(pixel_size) {
+            for range in &ranges {
+                buffer.extend_from_slice(&pixel[range.clone()]);
+            }
+        }
+
+        let bits_per_sample = bands.iter().map(|b| self.bits_per_sample[*b]).collect();
+        let sample_format = bands
+            .iter()
+            .map(|b| {
+                self.sample_format
+                    .get(*b)
+                    .or(self.sample_format.first())
+                    .copied()
+                    .unwrap_or(SampleFormat::Unsigned)
+            })
+            .collect();
+        let (interpretation, extra_samples) = self.band_interpretation(bands);
+
+        Self::new(
+            self.dimensions,
+            buffer,
+            bits_per_sample,
+            interpretation,
+            sample_format,
+            extra_samples,
+            self.endian,
+        )
+    }
+
+    /// 将多个尺寸相同的栅格按波段堆叠
+    ///
+    /// 输出栅格依次包含每个输入栅格的所有波段,字节序与第一个栅格相同
+    ///
+    /// # 参数
+    /// * `rasters` - 要堆叠的栅格
+    ///
+    /// # 错误
+    /// 如果没有输入、尺寸不一致或样本不按字节对齐则返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// # fn example(red: &cloudtiff::Raster, nir: &cloudtiff::Raster) {
+    /// // b1 为红光, b2 为近红外
+    /// let stacked = cloudtiff::Raster::stack(&[red, nir]).unwrap();
+    /// # }
+    /// ```
+    pub fn stack(rasters: &[&Raster]) -> Result<Self, RasterError> {
+        let first = rasters
+            .first()
+            .ok_or_else(|| RasterError::NotSupported("没有要堆叠的栅格".into()))?;
+        let dimensions = first.dimensions;
+        let endian = first.endian;
+
+        // 统一字节序
+        let mut converted = Vec::with_capacity(rasters.len());
+        for raster in rasters {
+            if raster.dimensions != dimensions {
+                return Err(RasterError::DimensionMismatch((
+                    dimensions,
+                    raster.dimensions,
+                )));
+            }
+            if raster.bits_per_pixel % 8 != 0 {
+                return Err(RasterError::NotSupported(format!(
+                    "像素不是按字节对齐的: {} 位",
+                    raster.bits_per_pixel
+                )));
+            }
+            let mut raster = (*raster).clone();
+            raster.convert_endian(endian);
+            converted.push(raster);
+        }
+
+        // 逐像素拼接所有栅格的波段
+        let pixel_sizes: Vec<usize> = converted
+            .iter()
+            .map(|r| r.bits_per_pixel as usize / 8)
+            .collect();
+        let pixel_count = dimensions.0 as usize * dimensions.1 as usize;
+        let mut buffer = Vec::with_capacity(pixel_count * pixel_sizes.iter().sum::<usize>());
+        for i in 0..pixel_count {
+            for (raster, size) in converted.iter().zip(&pixel_sizes) {
+                buffer.extend_from_slice(&raster.buffer[i * size..(i + 1) * size]);
+            }
+        }
+
+        let mut bits_per_sample = vec![];
+        let mut sample_format = vec![];
+        for raster in &converted {
+            for band in 0..raster.band_count() {
+                bits_per_sample.push(raster.bits_per_sample[band]);
+                sample_format.push(
+                    raster
+                        .sample_format
+                        .get(band)
+                        .or(raster.sample_format.first())
+                        .copied()
+                        .unwrap_or(SampleFormat::Unsigned),
+                );
+            }
+        }
+        let band_count = bits_per_sample.len();
+        let (interpretation, extra_samples) = default_interpretation(band_count);
+
+        Self::new(
+            dimensions,
+            buffer,
+            bits_per_sample,
+            interpretation,
+            sample_format,
+            extra_samples,
+            endian,
+        )
+    }
+
+    /// 计算每个波段在像素中的字节偏移
+    ///
+    /// # 错误
+    /// 如果样本不按字节对齐则返回错误
+    fn band_byte_offsets(&self) -> Result<Vec<usize>, RasterError> {
+        if self.bits_per_sample.iter().any(|bits| bits % 8 != 0) {
+            return Err(RasterError::NotSupported(format!(
+                "样本不是按字节对齐的: {:?}",
+                self.bits_per_sample
+            )));
+        }
+        Ok(self
+            .bits_per_sample
+            .iter()
+            .scan(0, |offset, bits| {
+                let start = *offset;
+                *offset += *bits as usize / 8;
+                Some(start)
+            })
+            .collect())
+    }
+
+    /// 计算选择波段后的光度解释方式和额外样本
+    ///
+    /// 选择全部波段且顺序不变时保持原样,否则按波段数重新确定,
+    /// 来自源额外样本的波段保留其额外样本类型
+    fn band_interpretation(
+        &self,
+        bands: &[usize],
+    ) -> (PhotometricInterpretation, Vec<ExtraSamples>) {
+        let identity =
+            bands.len() == self.band_count() && bands.iter().enumerate().all(|(i, b)| i == *b);
+        if identity {
+            return (self.interpretation, self.extra_samples.clone());
+        }
+
+        let (interpretation, mut extra_samples) = default_interpretation(bands.len());
+        let source_base = self.band_count().saturating_sub(self.extra_samples.len());
+        let base = bands.len() - extra_samples.len();
+        for (extra, band) in extra_samples.iter_mut().zip(&bands[base..]) {
+            if *band >= source_base {
+                *extra = self.extra_samples[*band - source_base];
+            }
+        }
+        (interpretation, extra_samples)
+    }
+}
+
+/// 根据波段数确定默认的光度解释方式和额外样本
+///
+/// 三个及以上波段为 RGB,否则为灰度,其余波段为未指定用途的额外样本
+fn default_interpretation(band_count: usize) -> (PhotometricInterpretation, Vec<ExtraSamples>) {
+    if band_count >= 3 {
+        (
+            PhotometricInterpretation::RGB,
+            vec![ExtraSamples::Unspecified; band_count - 3],
+        )
+    } else {
+        (
+            PhotometricInterpretation::BlackIsZero,
+            vec![ExtraSamples::Unspecified; band_count.saturating_sub(1)],
+        )
+    }
+}
@@ -0,0 +1,259 @@
+//! 颜色映射模块
+//!
+//! 本模块提供了将单波段栅格(如 DEM、温度、NDVI)转换为 RGBA 图像的功能:
+//! - 内置颜色渐变 (viridis、magma、terrain、greys、RdYlGn)
+//! - 自定义颜色节点并线性插值
+//! - 分段和精确匹配的分类颜色表
+//! - 无数据值透明
+
+use super::sample::alpha_to_u8;
+use super::{ExtraSamples, PhotometricInterpretation, Raster, RasterError, SampleFormat};
+use crate::tiff::Endian;
+
+/// RGBA 颜色
+pub type Rgba = [u8; 4];
+
+/// 透明色
+const TRANSPARENT: Rgba = [0, 0, 0, 0];
+
+/// 内置颜色渐变
+///
+/// 节点定义在 0~1 之间,使用时按 [`Colormap::named`] 的取值范围缩放
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorRamp {
+    /// 感知均匀的蓝-绿-黄渐变
+    Viridis,
+    /// 感知均匀的黑-紫-橙-浅黄渐变
+    Magma,
+    /// 地形渐变:深蓝(水)-绿-黄-棕-白(雪)
+    Terrain,
+    /// 黑-白灰度渐变
+    Greys,
+    /// 红-黄-绿发散渐变,适用于 NDVI
+    RdYlGn,
+}
+
+impl ColorRamp {
+    /// 渐变在 0~1 上的颜色节点
+    fn stops(&self) -> &'static [(f64, Rgba)] {
+        match self {
+            ColorRamp::Viridis => &[
+                (0.0, [68, 1, 84, 255]),
+                (0.25, [59, 82, 139, 255]),
+                (0.5, [33, 145, 140, 255]),
+                (0.75, [94, 201, 98, 255]),
+                (1.0, [253, 231, 37, 255]),
+            ],
+            ColorRamp::Magma => &[
+                (0.0, [0, 0, 4, 255]),
+                (0.25, [81, 18, 124, 255]),
+                (0.5, [183, 55, 121, 255]),
+                (0.75, [252, 137, 97, 255]),
+                (1.0, [252, 253, 191, 255]),
+            ],
+            ColorRamp::Terrain => &[
+                (0.0, [51, 51, 153, 255]),
+                (0.15, [0, 153, 255, 255]),
+                (0.25, [0, 204, 102, 255]),
+                (0.5, [255, 255, 153, 255]),
+                (0.75, [128, 92, 84, 255]),
+                (1.0, [255, 255, 255, 255]),
+            ],
+            ColorRamp::Greys => &[(0.0, [0, 0, 0, 255]), (1.0, [255, 255, 255, 255])],
+            ColorRamp::RdYlGn => &[
+                (0.0, [165, 0, 38, 255]),
+                (0.25, [244, 109, 67, 255]),
+                (0.5, [255, 255, 191, 255]),
+                (0.75, [102, 189, 99, 255]),
+                (1.0, [0, 104, 55, 255]),
+            ],
+        }
+    }
+}
+
+/// 颜色节点之间的取色方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColormapMode {
+    /// 在相邻节点之间线性插值,超出范围时使用端点颜色
+    Interpolate,
+    /// 分段:取值大于等于某个节点且小于下一个节点时使用该节点的颜色,低于第一个节点时透明
+    Discrete,
+    /// 精确匹配:只有与节点值相等的像素着色,其余透明
+    Exact,
+}
+
+/// 颜色映射
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::raster::{ColorRamp, Colormap};
+///
+/// # fn example(dem: &cloudtiff::Raster) {
+/// let colormap = Colormap::named(ColorRamp::Terrain, 0.0, 3000.0).with_nodata(-9999.0);
+/// let rgba = dem.apply_colormap(&colormap).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Colormap {
+    /// 按取值升序排列的颜色节点
+    stops: Vec<(f64, Rgba)>,
+    /// 取色方式
+    mode: ColormapMode,
+    /// 无数据值,对应像素输出为透明
+    nodata: Option<f64>,
+}
+
+impl Colormap {
+    /// 使用自定义颜色节点创建插值颜色映射
+    ///
+    /// # 参数
+    /// * `stops` - (取值, RGBA 颜色) 列表,会按取值排序
+    pub fn from_stops(stops: Vec<(f64, Rgba)>) -> Self {
+        Self::with_mode(stops, ColormapMode::Interpolate)
+    }
+
+    /// 使用分类颜色表创建颜色映射
+    ///
+    /// # 参数
+    /// * `classes` - (取值或区间下界, RGBA 颜色) 列表
+    /// * `mode` - [`ColormapMode::Discrete`] 按区间着色,[`ColormapMode::Exact`] 按值精确匹配
+    pub fn classes(classes: Vec<(f64, Rgba)>, mode: ColormapMode) -> Self {
+        Self::with_mode(classes, mode)
+    }
+
+    /// 使用内置渐变创建颜色映射
+    ///
+    /// # 参数
+    /// * `ramp` - 内置渐变
+    /// * `min` - 渐变起点对应的值
+    /// * `max` - 渐变终点对应的值
+    pub fn named(ramp: ColorRamp, min: f64, max: f64) -> Self {
+        let stops = ramp
+            .stops()
+            .iter()
+            .map(|(t, color)| (min + t * (max - min), *color))
+            .collect();
+        Self::from_stops(stops)
+    }
+
+    /// 按指定取色方式创建颜色映射
+    fn with_mode(mut stops: Vec<(f64, Rgba)>, mode: ColormapMode) -> Self {
+        stops.retain(|(value, _)| !value.is_nan());
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            stops,
+            mode,
+            nodata: None,
+        }
+    }
+
+    /// 设置无数据值,对应像素输出为透明
+    pub fn with_nodata(mut self, nodata: f64) -> Self {
+        self.nodata = Some(nodata);
+        self
+    }
+
+    /// 获取单个值对应的颜色
+    ///
+    /// NaN 和无数据值返回透明色
+    pub fn color(&self, value: f64) -> Rgba {
+        if value.is_nan() || self.nodata == Some(value) || self.stops.is_empty() {
+            return TRANSPARENT;
+        }
+        match self.mode {
+            ColormapMode::Exact => self
+                .stops
+                .iter()
+                .find(|(v, _)| *v == value)
+                .map_or(TRANSPARENT, |(_, color)| *color),
+            ColormapMode::Discrete => self
+                .stops
+                .iter()
+                .rev()
+                .find(|(v, _)| value >= *v)
+                .map_or(TRANSPARENT, |(_, color)| *color),
+            ColormapMode::Interpolate => {
+                // 找到第一个大于该值的节点
+                let i = self.stops.partition_point(|(v, _)| *v <= value);
+                if i == 0 {
+                    return self.stops[0].1;
+                }
+                if i == self.stops.len() {
+                    return self.stops[i - 1].1;
+                }
+                let (v0, c0) = self.stops[i - 1];
+                let (v1, c1) = self.stops[i];
+                let t = (value - v0) / (v1 - v0);
+                let mut color = [0; 4];
+                for (k, channel) in color.iter_mut().enumerate() {
+                    let c = c0[k] as f64 + t * (c1[k] as f64 - c0[k] as f64);
+                    *channel = c.round().clamp(0.0, 255.0) as u8;
+                }
+                color
+            }
+        }
+    }
+}
+
+impl Raster {
+    /// 使用颜色映射将第一个波段转换为 RGBA 栅格
+    ///
+    /// 如果栅格带有 alpha 额外样本,输出的透明度会与其相乘
+    ///
+    /// # 参数
+    /// * `colormap` - 颜色映射
+    ///
+    /// # 返回
+    /// 每波段 8 位的 RGBA 栅格(非预乘 alpha)
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub fn apply_colormap(&self, colormap: &Colormap) -> Result<Raster, RasterError> {
+        self.apply_colormap_masked(colormap, None)
+    }
+
+    /// 使用颜色映射转换为 RGBA 栅格,掩膜外的像素为透明
+    ///
+    /// 与 [`apply_colormap`](Self::apply_colormap) 相同,但 `mask` 为 false 的像素输出透明色。
+    /// 掩膜在对比度拉伸之前计算时,拉伸后无法再按无数据值识别的像素同样为透明
+    ///
+    /// # 参数
+    /// * `colormap` - 颜色映射
+    /// * `mask` - 按行优先排列的有效像素掩膜
+    pub(crate) fn apply_colormap_masked(
+        &self,
+        colormap: &Colormap,
+        mask: Option<&[bool]>,
+    ) -> Result<Raster, RasterError> {
+        let pixel_count = self.dimensions.0 as usize * self.dimensions.1 as usize;
+        let mut buffer = Vec::with_capacity(pixel_count * 4);
+        for (i, value) in self.band::<f64>(0)?.enumerate() {
+            let color = match mask {
+                Some(mask) if !mask[i] => TRANSPARENT,
+                _ => colormap.color(value),
+            };
+            buffer.extend_from_slice(&color);
+        }
+
+        // 保留源数据的透明度
+        if let Some(band) = self.alpha_band() {
+            let sample_type = self.band_type(band);
+            for (i, value) in self.band::<f64>(band)?.enumerate() {
+                let alpha = alpha_to_u8(value, sample_type);
+                let a = &mut buffer[i * 4 + 3];
+                *a = ((*a as u16 * alpha as u16 + 127) / 255) as u8;
+            }
+        }
+
+        Raster::new(
+            self.dimensions,
+            buffer,
+            vec![8; 4],
+            PhotometricInterpretation::RGB,
+            vec![SampleFormat::Unsigned; 4],
+            vec![ExtraSamples::UnassociatedAlpha],
+            Endian::native(),
+        )
+    }
+}
@@ -0,0 +1,495 @@
+//! 波段运算模块
+//!
+//! 本模块实现了一个简单的逐像素表达式求值器,用于计算 NDVI 等波段指数:
+//!
+//! ```text
+//! (b4 - b3) / (b4 + b3)
+//! ```
+//!
+//! 支持的语法:
+//! - 波段引用 `b1`、`b2`...(从 1 开始编号,大小写均可)
+//! - 数字常量,如 `2`、`0.5`、`1e-3`
+//! - 运算符 `+`、`-`、`*`、`/`、`^`(乘方)和一元负号
+//! - 括号
+//!
+//! 计算使用 f64 进行,结果保存为 Float32 栅格。除以零得到 NaN 或无穷大。
+
+use super::{PhotometricInterpretation, Raster, RasterError, SampleFormat};
+use crate::tiff::Endian;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// 二元运算符
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// 表达式语法树节点
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    /// 常量
+    Const(f64),
+    /// 波段引用(从 0 开始的索引)
+    Band(usize),
+    /// 一元负号
+    Neg(Box<Node>),
+    /// 二元运算
+    Binary(Op, Box<Node>, Box<Node>),
+}
+
+impl Node {
+    /// 计算节点的值
+    fn evaluate(&self, bands: &[f64]) -> f64 {
+        match self {
+            Node::Const(v) => *v,
+            Node::Band(i) => bands[*i],
+            Node::Neg(node) => -node.evaluate(bands),
+            Node::Binary(op, a, b) => {
+                let (a, b) = (a.evaluate(bands), b.evaluate(bands));
+                match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                    Op::Pow => a.powf(b),
+                }
+            }
+        }
+    }
+
+    /// 表达式中引用的最大波段索引
+    fn max_band(&self) -> Option<usize> {
+        match self {
+            Node::Const(_) => None,
+            Node::Band(i) => Some(*i),
+            Node::Neg(node) => node.max_band(),
+            Node::Binary(_, a, b) => a.max_band().max(b.max_band()),
+        }
+    }
+}
+
+/// 词法单元
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Band(usize),
+    Op(char),
+    Open,
+    Close,
+}
+
+/// 已解析的波段表达式
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::raster::BandExpression;
+///
+/// # fn example(raster: &cloudtiff::Raster) {
+/// let ndvi: BandExpression = "(b4 - b3) / (b4 + b3)".parse().unwrap();
+/// let result = raster.evaluate(&ndvi).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BandExpression {
+    /// 原始表达式
+    source: String,
+    /// 语法树
+    root: Node,
+}
+
+impl BandExpression {
+    /// 解析表达式
+    ///
+    /// # 参数
+    /// * `expression` - 表达式字符串
+    ///
+    /// # 错误
+    /// 语法错误时返回 [`RasterError::InvalidExpression`]
+    pub fn parse(expression: &str) -> Result<Self, RasterError> {
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let root = parser.expression()?;
+        if let Some(token) = parser.peek() {
+            return Err(RasterError::InvalidExpression(format!(
+                "多余的符号 {token:?}: {expression}"
+            )));
+        }
+        Ok(Self {
+            source: expression.to_string(),
+            root,
+        })
+    }
+
+    /// 表达式需要的波段数
+    pub fn band_count(&self) -> usize {
+        self.root.max_band().map_or(0, |i| i + 1)
+    }
+
+    /// 使用给定的波段值计算表达式
+    ///
+    /// # 参数
+    /// * `bands` - 各波段的值,长度至少为 [`band_count`](Self::band_count)
+    pub fn evaluate(&self, bands: &[f64]) -> f64 {
+        self.root.evaluate(bands)
+    }
+}
+
+impl FromStr for BandExpression {
+    type Err = RasterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for BandExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// 将表达式拆分为词法单元
+fn tokenize(expression: &str) -> Result<Vec<Token>, RasterError> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '+' | '-' | '*' | '/' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            'b' | 'B' => {
+                // 波段引用,从 1 开始编号
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && chars[end].is_ascii_digit() {
+                    end += 1;
+                }
+                let number: String = chars[start..end].iter().collect();
+                match number.parse::<usize>() {
+                    Ok(n) if n > 0 => tokens.push(Token::Band(n - 1)),
+                    _ => {
+                        return Err(RasterError::InvalidExpression(format!(
+                            "无效的波段引用 b{number}: {expression}"
+                        )))
+                    }
+                }
+                i = end;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                let mut end = i;
+                while end < chars.len() {
+                    let c = chars[end];
+                    let exponent_sign =
+                        (c == '+' || c == '-') && matches!(chars[end - 1], 'e' | 'E');
+                    if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                        end += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let number: String = chars[start..end].iter().collect();
+                let value = number.parse::<f64>().map_err(|_| {
+                    RasterError::InvalidExpression(format!("无效的数字 {number}: {expression}"))
+                })?;
+                tokens.push(Token::Number(value));
+                i = end;
+            }
+            _ => {
+                return Err(RasterError::InvalidExpression(format!(
+                    "无效的字符 '{c}': {expression}"
+                )))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// 递归下降语法分析器
+///
+/// ```text
+/// expression = term (('+' | '-') term)*
+/// term       = unary (('*' | '/') unary)*
+/// unary      = '-' unary | power
+/// power      = primary ('^' unary)?
+/// primary    = number | band | '(' expression ')'
+/// ```
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// 查看下一个词法单元
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.position).copied()
+    }
+
+    /// 取出下一个词法单元
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn expression(&mut self) -> Result<Node, RasterError> {
+        let mut node = self.term()?;
+        while let Some(Token::Op(c @ ('+' | '-'))) = self.peek() {
+            self.next();
+            let op = if c == '+' { Op::Add } else { Op::Sub };
+            node = Node::Binary(op, Box::new(node), Box::new(self.term()?));
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node, RasterError> {
+        let mut node = self.unary()?;
+        while let Some(Token::Op(c @ ('*' | '/'))) = self.peek() {
+            self.next();
+            let op = if c == '*' { Op::Mul } else { Op::Div };
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, RasterError> {
+        if let Some(Token::Op('-')) = self.peek() {
+            self.next();
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Node, RasterError> {
+        let node = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.next();
+            return Ok(Node::Binary(
+                Op::Pow,
+                Box::new(node),
+                Box::new(self.unary()?),
+            ));
+        }
+        Ok(node)
+    }
+
+    fn primary(&mut self) -> Result<Node, RasterError> {
+        match self.next() {
+            Some(Token::Number(v)) => Ok(Node::Const(v)),
+            Some(Token::Band(i)) => Ok(Node::Band(i)),
+            Some(Token::Open) => {
+                let node = self.expression()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(node),
+                    _ => Err(RasterError::InvalidExpression("缺少右括号".into())),
+                }
+            }
+            Some(token) => Err(RasterError::InvalidExpression(format!(
+                "意外的符号 {token:?}"
+            ))),
+            None => Err(RasterError::InvalidExpression("表达式意外结束".into())),
+        }
+    }
+}
+
+impl Raster {
+    /// 逐像素计算波段表达式
+    ///
+    /// # 参数
+    /// * `expression` - 已解析的波段表达式
+    ///
+    /// # 返回
+    /// 单波段 Float32 栅格(本机字节序)
+    ///
+    /// # 错误
+    /// 表达式引用的波段不存在或波段类型不受支持时返回错误
+    pub fn evaluate(&self, expression: &BandExpression) -> Result<Raster, RasterError> {
+        let band_count = expression.band_count();
+        if band_count > self.band_count() {
+            return Err(RasterError::InvalidExpression(format!(
+                "表达式 {expression} 需要 {band_count} 个波段,栅格只有 {} 个",
+                self.band_count()
+            )));
+        }
+
+        let mut bands = (0..band_count)
+            .map(|band| self.band::<f64>(band))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pixel_count = self.dimensions.0 as usize * self.dimensions.1 as usize;
+        let mut values = vec![0.0; band_count];
+        let mut output: Vec<f32> = Vec::with_capacity(pixel_count);
+        for _ in 0..pixel_count {
+            for (value, band) in values.iter_mut().zip(bands.iter_mut()) {
+                *value = band.next().unwrap_or(f64::NAN);
+            }
+            output.push(expression.evaluate(&values) as f32);
+        }
+
+        Raster::new(
+            self.dimensions,
+            bytemuck::cast_slice(&output).to_vec(),
+            vec![32],
+            PhotometricInterpretation::BlackIsZero,
+            vec![SampleFormat::Float],
+            vec![],
+            Endian::native(),
+        )
+    }
+
+    /// 解析并逐像素计算波段表达式
+    ///
+    /// # 参数
+    /// * `expression` - 表达式字符串,例如 `(b4-b3)/(b4+b3)`
+    ///
+    /// # 错误
+    /// 表达式语法错误或引用的波段不存在时返回错误
+    pub fn band_math(&self, expression: &str) -> Result<Raster, RasterError> {
+        self.evaluate(&BandExpression::parse(expression)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::ExtraSamples;
+
+    /// 解析并计算表达式
+    fn eval(expression: &str, bands: &[f64]) -> f64 {
+        BandExpression::parse(expression).unwrap().evaluate(bands)
+    }
+
+    /// 解析失败时返回错误信息
+    fn parse_error(expression: &str) -> String {
+        match BandExpression::parse(expression) {
+            Err(RasterError::InvalidExpression(message)) => message,
+            other => panic!("{expression} 应解析失败: {other:?}"),
+        }
+    }
+
+    /// 2x1 像素、两个 8 位波段的栅格
+    fn two_band_raster() -> Raster {
+        Raster::new(
+            (2, 1),
+            vec![10, 30, 20, 40],
+            vec![8, 8],
+            PhotometricInterpretation::BlackIsZero,
+            vec![SampleFormat::Unsigned; 2],
+            vec![ExtraSamples::Unspecified],
+            Endian::native(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn power_binds_tighter_than_multiplication() {
+        assert_eq!(eval("2 * 3 ^ 2", &[]), 18.0);
+        assert_eq!(eval("3 ^ 2 * 2", &[]), 18.0);
+        assert_eq!(eval("2 ^ 3 + 1", &[]), 9.0);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(eval("2 ^ 3 ^ 2", &[]), 512.0);
+        assert_eq!(eval("(2 ^ 3) ^ 2", &[]), 64.0);
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-2 ^ 2", &[]), -4.0);
+        assert_eq!(eval("(-2) ^ 2", &[]), 4.0);
+        assert_eq!(eval("2 ^ -1", &[]), 0.5);
+        assert_eq!(eval("--3", &[]), 3.0);
+        assert_eq!(eval("-b1 * 2", &[1.5]), -3.0);
+        assert_eq!(eval("4 - -1", &[]), 5.0);
+    }
+
+    #[test]
+    fn subtraction_and_division_are_left_associative() {
+        assert_eq!(eval("10 - 4 - 3", &[]), 3.0);
+        assert_eq!(eval("64 / 4 / 2", &[]), 8.0);
+    }
+
+    #[test]
+    fn numeric_literals() {
+        assert_eq!(eval("1e3", &[]), 1000.0);
+        assert_eq!(eval("2.5E-2", &[]), 0.025);
+        assert_eq!(eval("1e+2", &[]), 100.0);
+        assert_eq!(eval(".5", &[]), 0.5);
+        // 指数后的符号属于数字,其他位置的符号是运算符
+        assert_eq!(eval("1e-3-1", &[]), 1e-3 - 1.0);
+        assert_eq!(eval("2-1", &[]), 1.0);
+    }
+
+    #[test]
+    fn band_references() {
+        let expression = BandExpression::parse("(B4 - b3) / (b4 + b3)").unwrap();
+        assert_eq!(expression.band_count(), 4);
+        assert_eq!(expression.evaluate(&[0.0, 0.0, 1.0, 3.0]), 0.5);
+        assert_eq!(BandExpression::parse("1 + 2").unwrap().band_count(), 0);
+        assert_eq!(expression.to_string(), "(B4 - b3) / (b4 + b3)");
+    }
+
+    #[test]
+    fn malformed_expressions() {
+        assert!(parse_error("b0 + 1").contains("无效的波段引用 b0"));
+        assert!(parse_error("b + 1").contains("无效的波段引用 b"));
+        assert!(parse_error("1.2.3").contains("无效的数字 1.2.3"));
+        assert!(parse_error("1e").contains("无效的数字 1e"));
+        assert!(parse_error("b1 % 2").contains("无效的字符 '%'"));
+        assert!(parse_error("(b1 + 1").contains("缺少右括号"));
+        assert!(parse_error("b1 + 1)").contains("多余的符号 Close"));
+        assert!(parse_error("b1 +").contains("表达式意外结束"));
+        assert!(parse_error("").contains("表达式意外结束"));
+        assert!(parse_error("* 2").contains("意外的符号 Op('*')"));
+        assert!(parse_error("1 2").contains("多余的符号 Number(2.0)"));
+    }
+
+    #[test]
+    fn band_math_evaluates_each_pixel() {
+        let result = two_band_raster()
+            .band_math("(b2 - b1) / (b2 + b1)")
+            .unwrap();
+        assert_eq!(result.dimensions, (2, 1));
+        assert_eq!(result.bits_per_sample, vec![32]);
+        assert_eq!(result.sample_format, vec![SampleFormat::Float]);
+        let values: Vec<f32> = result.band::<f32>(0).unwrap().collect();
+        assert_eq!(values, vec![0.5, 1.0 / 3.0]);
+    }
+
+    #[test]
+    fn band_math_rejects_out_of_range_bands() {
+        match two_band_raster().band_math("b3 - b1") {
+            Err(RasterError::InvalidExpression(message)) => {
+                assert!(message.contains("需要 3 个波段,栅格只有 2 个"), "{message}")
+            }
+            other => panic!("应返回错误: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn division_by_zero_is_not_an_error() {
+        assert!(eval("0 / 0", &[]).is_nan());
+        assert_eq!(eval("1 / 0", &[]), f64::INFINITY);
+    }
+}
@@ -31,9 +31,14 @@ impl Raster {
             [8, 8, 8] => Rgba([p[0], p[1], p[2], 255]), // 24位 RGB 图，添加不透明的 alpha 通道
             [8, 8, 8, 8] => Rgba([p[0], p[1], p[2], p[3]]), // 32位 RGBA 图
             [16] => {
-                // 16位灰度图，需要进行解码和缩放
-                let v: i16 = self.endian.decode([p[0], p[1]]).ok()?;
-                let v8 = (v / 10).clamp(0, 255) as u8;
+                // 16位灰度图，按样本格式解码后取高8位
+                let v8 = match self.sample_format.first() {
+                    Some(SampleFormat::Signed) => {
+                        let v: i16 = self.get_sample(x, y, 0)?;
+                        ((v as i32 + 0x8000) >> 8) as u8
+                    }
+                    _ => (self.get_sample::<u16>(x, y, 0)? >> 8) as u8,
+                };
                 Rgba([v8, v8, v8, 255])
             }
             _ => return None, // 不支持的位深度
@@ -117,11 +122,7 @@ impl Raster {
     pub fn from_image(img: &DynamicImage) -> Result<Self, RasterError> {
         let dimensions = (img.width(), img.height());
         let buffer = img.as_bytes().to_vec();
-        let endian = if cfg!(target_endian = "big") {
-            Endian::Big
-        } else {
-            Endian::Little
-        };
+        let endian = Endian::native();
 
         // 根据不同的 DynamicImage 类型设置对应的参数
         let (interpretation, bits_per_sample, sample_format, extra_samples) = match img {
@@ -5,14 +5,25 @@
 use crate::tiff::Endian;
 use std::fmt::Display;
 
+mod bands;
+mod colormap;
+mod expr;
 mod image;
 mod ops;
 mod photometrics;
+mod sample;
+mod stretch;
+mod terrain;
 
+pub use colormap::{ColorRamp, Colormap, ColormapMode, Rgba};
+pub use expr::BandExpression;
 pub use ops::ResizeFilter;
 pub use photometrics::{
     ExtraSamples, PhotometricInterpretation, PlanarConfiguration, SampleFormat,
 };
+pub use sample::{BandIter, Sample, SampleType};
+pub use stretch::{Stretch, StretchRange};
+pub use terrain::{Terrain, TerrainKind};
 
 // TODO: 处理奇特的位序问题。已经遇到过两种不同的情况。
 
@@ -24,6 +35,11 @@ pub enum RasterError {
     BufferSize((usize, (u32, u32), Vec<u16>, u32)),
     /// 不支持的操作错误
     NotSupported(String),
+    /// 栅格尺寸不一致错误
+    /// 包含 (期望尺寸, 实际尺寸)
+    DimensionMismatch(((u32, u32), (u32, u32))),
+    /// 波段表达式无效
+    InvalidExpression(String),
 }
 
 /// 表示一个栅格图像
@@ -184,8 +184,8 @@ impl Raster {
         let mut buffer = vec![0; ((width * height) as usize) * bytes_per_pixel];
 
         // 复制指定区域的像素数据
-        for j in region.y.min..region.y.max.min(self.dimensions.1 - 1) {
-            for i in region.x.min..region.x.max.min(self.dimensions.0 - 1) {
+        for j in region.y.min..region.y.max.min(self.dimensions.1) {
+            for i in region.x.min..region.x.max.min(self.dimensions.0) {
                 // 计算源图像中像素的起始位置
                 let src = (j * self.dimensions.0 + i) as usize * bytes_per_pixel;
 
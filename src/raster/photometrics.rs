@@ -111,3 +111,28 @@ pub enum ExtraSamples {
     #[num_enum(default)]
     Unknown = 0xFFFF,
 }
+
+impl ExtraSamples {
+    /// 是否为 Alpha 通道
+    pub fn is_alpha(&self) -> bool {
+        matches!(
+            self,
+            ExtraSamples::AssociatedAlpha | ExtraSamples::UnassociatedAlpha
+        )
+    }
+
+    /// 查找 Alpha 波段索引
+    ///
+    /// 额外样本对应最后几个波段
+    ///
+    /// # 参数
+    /// * `band_count` - 波段数
+    /// * `extra_samples` - 额外样本信息
+    pub fn alpha_band(band_count: usize, extra_samples: &[ExtraSamples]) -> Option<usize> {
+        let first_extra = band_count.checked_sub(extra_samples.len())?;
+        extra_samples
+            .iter()
+            .position(ExtraSamples::is_alpha)
+            .map(|position| first_extra + position)
+    }
+}
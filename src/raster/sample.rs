@@ -0,0 +1,513 @@
+//! 栅格样本类型模块
+//!
+//! 本模块提供了按样本类型访问栅格数据的功能:
+//! - [`Sample`] 特性描述了支持的原始数据类型(u8 ~ f64)
+//! - [`SampleType`] 根据 `sample_format` 和 `bits_per_sample` 确定样本类型
+//! - 类型化的切片视图、单个样本读写和波段迭代器
+//!
+//! 从 COG 中提取的瓦片在解压时已经转换为本机字节序,
+//! 因此通常可以直接通过 [`Raster::as_slice`] 获取类型化视图(缓冲区对齐时不复制)。
+
+use super::{ExtraSamples, Raster, RasterError, SampleFormat};
+use crate::tiff::Endian;
+use bytemuck::Pod;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+/// 栅格支持的样本数据类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SampleType {
+    /// 8位无符号整数
+    U8,
+    /// 16位无符号整数
+    U16,
+    /// 32位无符号整数
+    U32,
+    /// 64位无符号整数
+    U64,
+    /// 8位有符号整数
+    I8,
+    /// 16位有符号整数
+    I16,
+    /// 32位有符号整数
+    I32,
+    /// 64位有符号整数
+    I64,
+    /// 32位浮点数
+    F32,
+    /// 64位浮点数
+    F64,
+}
+
+impl SampleType {
+    /// 根据样本格式和位深度确定样本类型
+    ///
+    /// 未定义或未知的样本格式按无符号整数处理
+    ///
+    /// # 返回
+    /// 不支持的组合(如 12 位整数或复数)返回 None
+    pub fn from_format(format: SampleFormat, bits: u16) -> Option<Self> {
+        Some(match (format, bits) {
+            (SampleFormat::Float, 32) => Self::F32,
+            (SampleFormat::Float, 64) => Self::F64,
+            (SampleFormat::Signed, 8) => Self::I8,
+            (SampleFormat::Signed, 16) => Self::I16,
+            (SampleFormat::Signed, 32) => Self::I32,
+            (SampleFormat::Signed, 64) => Self::I64,
+            (SampleFormat::Unsigned | SampleFormat::Undefined | SampleFormat::Unknown, 8) => {
+                Self::U8
+            }
+            (SampleFormat::Unsigned | SampleFormat::Undefined | SampleFormat::Unknown, 16) => {
+                Self::U16
+            }
+            (SampleFormat::Unsigned | SampleFormat::Undefined | SampleFormat::Unknown, 32) => {
+                Self::U32
+            }
+            (SampleFormat::Unsigned | SampleFormat::Undefined | SampleFormat::Unknown, 64) => {
+                Self::U64
+            }
+            _ => return None,
+        })
+    }
+
+    /// 样本格式
+    pub fn format(&self) -> SampleFormat {
+        match self {
+            Self::U8 | Self::U16 | Self::U32 | Self::U64 => SampleFormat::Unsigned,
+            Self::I8 | Self::I16 | Self::I32 | Self::I64 => SampleFormat::Signed,
+            Self::F32 | Self::F64 => SampleFormat::Float,
+        }
+    }
+
+    /// 位深度
+    pub fn bits(&self) -> u16 {
+        match self {
+            Self::U8 | Self::I8 => 8,
+            Self::U16 | Self::I16 => 16,
+            Self::U32 | Self::I32 | Self::F32 => 32,
+            Self::U64 | Self::I64 | Self::F64 => 64,
+        }
+    }
+
+    /// 字节数
+    pub fn size(&self) -> usize {
+        self.bits() as usize / 8
+    }
+
+    /// 将指定字节序的样本字节解码为 f64
+    ///
+    /// # 参数
+    /// * `bytes` - 样本字节,长度必须等于 [`size`](Self::size)
+    /// * `endian` - 字节序
+    pub fn decode_f64(&self, bytes: &[u8], endian: Endian) -> f64 {
+        match self {
+            Self::U8 => u8::decode(bytes, endian).to_f64(),
+            Self::U16 => u16::decode(bytes, endian).to_f64(),
+            Self::U32 => u32::decode(bytes, endian).to_f64(),
+            Self::U64 => u64::decode(bytes, endian).to_f64(),
+            Self::I8 => i8::decode(bytes, endian).to_f64(),
+            Self::I16 => i16::decode(bytes, endian).to_f64(),
+            Self::I32 => i32::decode(bytes, endian).to_f64(),
+            Self::I64 => i64::decode(bytes, endian).to_f64(),
+            Self::F32 => f32::decode(bytes, endian).to_f64(),
+            Self::F64 => f64::decode(bytes, endian),
+        }
+    }
+
+    /// 将 f64 值编码为指定字节序的样本字节
+    ///
+    /// 整数类型会四舍五入并限制在类型范围内
+    ///
+    /// # 参数
+    /// * `value` - 样本值
+    /// * `endian` - 字节序
+    /// * `bytes` - 目标字节,长度必须等于 [`size`](Self::size)
+    pub fn encode_f64(&self, value: f64, endian: Endian, bytes: &mut [u8]) {
+        match self {
+            Self::U8 => u8::from_f64(value).encode(endian, bytes),
+            Self::U16 => u16::from_f64(value).encode(endian, bytes),
+            Self::U32 => u32::from_f64(value).encode(endian, bytes),
+            Self::U64 => u64::from_f64(value).encode(endian, bytes),
+            Self::I8 => i8::from_f64(value).encode(endian, bytes),
+            Self::I16 => i16::from_f64(value).encode(endian, bytes),
+            Self::I32 => i32::from_f64(value).encode(endian, bytes),
+            Self::I64 => i64::from_f64(value).encode(endian, bytes),
+            Self::F32 => f32::from_f64(value).encode(endian, bytes),
+            Self::F64 => value.encode(endian, bytes),
+        }
+    }
+}
+
+/// 将 Alpha 样本转换为 8 位
+///
+/// 整数按位深度的最大值缩放,浮点数按 0~1 缩放
+pub(crate) fn alpha_to_u8(value: f64, sample_type: Option<SampleType>) -> u8 {
+    let max = match sample_type {
+        Some(SampleType::F32 | SampleType::F64) => 1.0,
+        Some(sample_type) => 2f64.powi(sample_type.bits() as i32) - 1.0,
+        None => 255.0,
+    };
+    (value / max * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// 栅格样本的原始数据类型
+///
+/// 为 `u8`、`u16`、`u32`、`u64`、`i8`、`i16`、`i32`、`i64`、`f32` 和 `f64` 实现
+pub trait Sample: Pod + PartialOrd + Default + Send + Sync {
+    /// 对应的样本类型
+    const TYPE: SampleType;
+
+    /// 从指定字节序的字节解码
+    fn decode(bytes: &[u8], endian: Endian) -> Self;
+
+    /// 按指定字节序编码到字节
+    fn encode(self, endian: Endian, bytes: &mut [u8]);
+
+    /// 转换为 f64
+    fn to_f64(self) -> f64;
+
+    /// 从 f64 转换,整数类型会四舍五入并饱和到类型范围(NaN 转换为 0)
+    fn from_f64(value: f64) -> Self;
+}
+
+/// 为整数和浮点类型实现 Sample
+macro_rules! impl_sample {
+    ($t:ty, $variant:ident, $round:expr) => {
+        impl Sample for $t {
+            const TYPE: SampleType = SampleType::$variant;
+
+            fn decode(bytes: &[u8], endian: Endian) -> Self {
+                let mut array = [0u8; std::mem::size_of::<$t>()];
+                array.copy_from_slice(bytes);
+                match endian {
+                    Endian::Big => <$t>::from_be_bytes(array),
+                    Endian::Little => <$t>::from_le_bytes(array),
+                }
+            }
+
+            fn encode(self, endian: Endian, bytes: &mut [u8]) {
+                let array = match endian {
+                    Endian::Big => self.to_be_bytes(),
+                    Endian::Little => self.to_le_bytes(),
+                };
+                bytes.copy_from_slice(&array);
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                let round: fn(f64) -> f64 = $round;
+                // `as` 转换会饱和到类型范围
+                round(value) as $t
+            }
+        }
+    };
+}
+
+impl_sample!(u8, U8, f64::round);
+impl_sample!(u16, U16, f64::round);
+impl_sample!(u32, U32, f64::round);
+impl_sample!(u64, U64, f64::round);
+impl_sample!(i8, I8, f64::round);
+impl_sample!(i16, I16, f64::round);
+impl_sample!(i32, I32, f64::round);
+impl_sample!(i64, I64, f64::round);
+impl_sample!(f32, F32, |v| v);
+impl_sample!(f64, F64, |v| v);
+
+impl Raster {
+    /// 获取波段数
+    pub fn band_count(&self) -> usize {
+        self.bits_per_sample.len()
+    }
+
+    /// 获取 Alpha 波段索引,没有 Alpha 波段时返回 None
+    pub fn alpha_band(&self) -> Option<usize> {
+        ExtraSamples::alpha_band(self.band_count(), &self.extra_samples)
+    }
+
+    /// 获取指定波段的样本类型
+    ///
+    /// # 参数
+    /// * `band` - 波段索引
+    ///
+    /// # 返回
+    /// 波段不存在或类型不受支持时返回 None
+    pub fn band_type(&self, band: usize) -> Option<SampleType> {
+        let bits = *self.bits_per_sample.get(band)?;
+        let format = self
+            .sample_format
+            .get(band)
+            .or(self.sample_format.first())
+            .copied()
+            .unwrap_or(SampleFormat::Unsigned);
+        SampleType::from_format(format, bits)
+    }
+
+    /// 获取所有波段共同的样本类型
+    ///
+    /// # 错误
+    /// 如果波段类型不一致或不受支持则返回错误
+    pub fn sample_type(&self) -> Result<SampleType, RasterError> {
+        let first = self.band_type(0).ok_or_else(|| {
+            RasterError::NotSupported(format!(
+                "不支持的样本类型: {:?} {:?}",
+                self.bits_per_sample, self.sample_format
+            ))
+        })?;
+        if (1..self.band_count()).all(|band| self.band_type(band) == Some(first)) {
+            Ok(first)
+        } else {
+            Err(RasterError::NotSupported(format!(
+                "波段样本类型不一致: {:?} {:?}",
+                self.bits_per_sample, self.sample_format
+            )))
+        }
+    }
+
+    /// 获取类型化的样本切片视图
+    ///
+    /// 切片按像素交错排列所有波段的样本,例如 RGB 图像为 `[r, g, b, r, g, b, ...]`。
+    /// 字节缓冲区的分配不保证按 `T` 对齐,对齐时直接借用缓冲区,否则复制为新的 `Vec<T>`
+    ///
+    /// # 错误
+    /// 如果样本类型与 `T` 不一致或字节序不是本机字节序则返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// # fn example(raster: &cloudtiff::Raster) {
+    /// let samples = raster.as_slice::<u16>().unwrap();
+    /// let first: u16 = samples[0];
+    /// # }
+    /// ```
+    pub fn as_slice<T: Sample>(&self) -> Result<Cow<'_, [T]>, RasterError> {
+        self.check_typed_view::<T>()?;
+        if !self.buffer.len().is_multiple_of(T::TYPE.size()) {
+            return Err(RasterError::NotSupported(format!(
+                "缓冲区长度 {} 不是样本大小的整数倍",
+                self.buffer.len()
+            )));
+        }
+        Ok(match bytemuck::try_cast_slice(&self.buffer) {
+            Ok(samples) => Cow::Borrowed(samples),
+            Err(_) => Cow::Owned(bytemuck::pod_collect_to_vec(&self.buffer)),
+        })
+    }
+
+    /// 获取可变的类型化样本切片视图
+    ///
+    /// # 错误
+    /// 与 [`as_slice`](Self::as_slice) 相同;此外可变视图不能复制,
+    /// 字节缓冲区的地址未按 `T` 对齐时也返回错误
+    pub fn as_mut_slice<T: Sample>(&mut self) -> Result<&mut [T], RasterError> {
+        self.check_typed_view::<T>()?;
+        bytemuck::try_cast_slice_mut(&mut self.buffer)
+            .map_err(|e| RasterError::NotSupported(format!("无法转换缓冲区: {e:?}")))
+    }
+
+    /// 检查缓冲区能否作为 `T` 类型的切片访问
+    fn check_typed_view<T: Sample>(&self) -> Result<(), RasterError> {
+        let sample_type = self.sample_type()?;
+        if sample_type != T::TYPE {
+            return Err(RasterError::NotSupported(format!(
+                "样本类型为 {sample_type:?},不能作为 {:?} 访问",
+                T::TYPE
+            )));
+        }
+        if T::TYPE.size() > 1 && self.endian != Endian::native() {
+            return Err(RasterError::NotSupported(format!(
+                "缓冲区字节序为 {:?},不是本机字节序",
+                self.endian
+            )));
+        }
+        Ok(())
+    }
+
+    /// 计算样本在缓冲区中的字节位置
+    ///
+    /// # 返回
+    /// 返回 (起始字节, 样本类型),坐标或波段无效、样本不按字节对齐时返回 None
+    fn sample_offset(&self, x: u32, y: u32, band: usize) -> Option<(usize, SampleType)> {
+        if x >= self.dimensions.0 || y >= self.dimensions.1 {
+            return None;
+        }
+        let sample_type = self.band_type(band)?;
+        let band_offset_bits: u32 = self.bits_per_sample[..band]
+            .iter()
+            .map(|bits| *bits as u32)
+            .sum();
+        let offset_bits = x as u64 * self.bits_per_pixel as u64 + band_offset_bits as u64;
+        if !offset_bits.is_multiple_of(8) {
+            return None;
+        }
+        let start = y as usize * self.row_size() as usize + (offset_bits / 8) as usize;
+        Some((start, sample_type))
+    }
+
+    /// 读取单个样本并转换为 `T`
+    ///
+    /// 根据波段的 `sample_format` 和 `bits_per_sample` 解码,
+    /// 类型不同时通过 f64 转换(整数会四舍五入并饱和)
+    ///
+    /// # 参数
+    /// * `x` - 像素的 x 坐标
+    /// * `y` - 像素的 y 坐标
+    /// * `band` - 波段索引
+    ///
+    /// # 返回
+    /// 坐标或波段无效、样本类型不受支持时返回 None
+    pub fn get_sample<T: Sample>(&self, x: u32, y: u32, band: usize) -> Option<T> {
+        let (start, sample_type) = self.sample_offset(x, y, band)?;
+        let bytes = self.buffer.get(start..start + sample_type.size())?;
+        Some(if sample_type == T::TYPE {
+            T::decode(bytes, self.endian)
+        } else {
+            T::from_f64(sample_type.decode_f64(bytes, self.endian))
+        })
+    }
+
+    /// 写入单个样本
+    ///
+    /// 值会转换为波段的样本类型(整数会四舍五入并饱和)
+    ///
+    /// # 参数
+    /// * `x` - 像素的 x 坐标
+    /// * `y` - 像素的 y 坐标
+    /// * `band` - 波段索引
+    /// * `value` - 样本值
+    ///
+    /// # 错误
+    /// 坐标或波段无效、样本类型不受支持时返回错误
+    pub fn put_sample<T: Sample>(
+        &mut self,
+        x: u32,
+        y: u32,
+        band: usize,
+        value: T,
+    ) -> Result<(), RasterError> {
+        let (start, sample_type) = self.sample_offset(x, y, band).ok_or_else(|| {
+            RasterError::NotSupported(format!("无效的样本位置: ({x}, {y}) 波段 {band}"))
+        })?;
+        let endian = self.endian;
+        let bytes = &mut self.buffer[start..start + sample_type.size()];
+        if sample_type == T::TYPE {
+            value.encode(endian, bytes);
+        } else {
+            sample_type.encode_f64(value.to_f64(), endian, bytes);
+        }
+        Ok(())
+    }
+
+    /// 获取指定波段的样本迭代器
+    ///
+    /// 按行优先顺序遍历所有像素,样本转换规则与 [`get_sample`](Self::get_sample) 相同
+    ///
+    /// # 参数
+    /// * `band` - 波段索引
+    ///
+    /// # 错误
+    /// 波段无效、样本类型不受支持或像素不按字节对齐时返回错误
+    pub fn band<T: Sample>(&self, band: usize) -> Result<BandIter<'_, T>, RasterError> {
+        let sample_type = self.band_type(band).ok_or_else(|| {
+            RasterError::NotSupported(format!(
+                "不支持的波段 {band}: {:?} {:?}",
+                self.bits_per_sample, self.sample_format
+            ))
+        })?;
+        if !self.bits_per_pixel.is_multiple_of(8) {
+            return Err(RasterError::NotSupported(format!(
+                "像素不是按字节对齐的: {} 位",
+                self.bits_per_pixel
+            )));
+        }
+        let offset = self.bits_per_sample[..band]
+            .iter()
+            .map(|bits| *bits as usize)
+            .sum::<usize>()
+            / 8;
+        Ok(BandIter {
+            raster: self,
+            sample_type,
+            offset,
+            stride: self.bits_per_pixel as usize / 8,
+            index: 0,
+            len: self.dimensions.0 as usize * self.dimensions.1 as usize,
+            _marker: PhantomData,
+        })
+    }
+
+    /// 将所有多字节样本转换为指定字节序
+    ///
+    /// # 参数
+    /// * `endian` - 目标字节序
+    pub fn convert_endian(&mut self, endian: Endian) {
+        if self.endian == endian {
+            return;
+        }
+        // 只有所有样本都按字节对齐时才能逐样本交换
+        let aligned = self.bits_per_sample.iter().all(|bits| bits % 8 == 0);
+        if aligned && self.bits_per_sample.iter().any(|bits| *bits > 8) {
+            let sizes: Vec<usize> = self
+                .bits_per_sample
+                .iter()
+                .map(|bits| *bits as usize / 8)
+                .collect();
+            let pixel_size: usize = sizes.iter().sum();
+            for pixel in self.buffer.chunks_exact_mut(pixel_size) {
+                let mut start = 0;
+                for size in &sizes {
+                    pixel[start..start + size].reverse();
+                    start += size;
+                }
+            }
+        }
+        self.endian = endian;
+    }
+}
+
+/// 波段样本迭代器
+///
+/// 由 [`Raster::band`] 创建
+pub struct BandIter<'a, T> {
+    /// 源栅格
+    raster: &'a Raster,
+    /// 波段的样本类型
+    sample_type: SampleType,
+    /// 波段在像素中的字节偏移
+    offset: usize,
+    /// 每个像素的字节数
+    stride: usize,
+    /// 下一个像素的索引
+    index: usize,
+    /// 像素总数
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Sample> Iterator for BandIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index >= self.len {
+            return None;
+        }
+        let start = self.index * self.stride + self.offset;
+        self.index += 1;
+        let bytes = &self.raster.buffer[start..start + self.sample_type.size()];
+        Some(if self.sample_type == T::TYPE {
+            T::decode(bytes, self.raster.endian)
+        } else {
+            T::from_f64(self.sample_type.decode_f64(bytes, self.raster.endian))
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Sample> ExactSizeIterator for BandIter<'_, T> {}
@@ -0,0 +1,321 @@
+//! 对比度拉伸模块
+//!
+//! 本模块提供了将 16 位整数或浮点栅格线性拉伸为 8 位栅格的功能,用于显示和导出 PNG:
+//! - 最小值/最大值线性拉伸
+//! - 百分比截断拉伸(如 2%~98%)
+//! - gamma 校正
+//! - 每个波段独立拉伸或所有波段使用同一范围
+//!
+//! 拉伸参数 [`Stretch`] 与数据分离,可以先从低分辨率的概览图计算,
+//! 再应用到全分辨率瓦片上,使相邻的网络瓦片颜色保持一致。
+//!
+//! 无数据值、NaN 和透明的像素不参与拉伸范围的计算,拉伸后这些像素的数据波段为 0;
+//! Alpha 波段不做拉伸,只转换为 8 位。
+
+use super::sample::alpha_to_u8;
+use super::{Raster, RasterError, SampleFormat};
+use crate::cog::BandStatistics;
+use crate::tiff::Endian;
+
+/// 拉伸范围的计算方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StretchRange {
+    /// 使用数据的最小值和最大值
+    MinMax,
+    /// 使用百分位数截断,参数为 (下百分位, 上百分位),取值 0~100
+    Percentile(f64, f64),
+}
+
+/// 对比度拉伸参数
+///
+/// # 字段说明
+///
+/// * `ranges` - 每个波段的 (最小值, 最大值);只有一个范围时应用于所有波段
+/// * `gamma` - gamma 值,输出为 `t^(1/gamma)`,大于 1 时变亮
+/// * `nodata` - 无数据值,这些像素不参与拉伸,输出为 0
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::raster::{Stretch, StretchRange};
+///
+/// # fn example(overview: &cloudtiff::Raster, tile: &cloudtiff::Raster) {
+/// // 从概览图计算 2%~98% 截断范围,再应用到全分辨率瓦片
+/// let stretch = Stretch::from_raster(overview, StretchRange::Percentile(2.0, 98.0), true, None)
+///     .unwrap()
+///     .with_gamma(1.2);
+/// let display = tile.stretch_to_u8(&stretch).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stretch {
+    /// 每个波段的拉伸范围
+    pub ranges: Vec<(f64, f64)>,
+    /// gamma 值
+    pub gamma: f64,
+    /// 无数据值
+    pub nodata: Option<f64>,
+}
+
+impl Stretch {
+    /// 使用给定的每波段范围创建拉伸参数
+    ///
+    /// # 参数
+    /// * `ranges` - 每个波段的 (最小值, 最大值)
+    pub fn new(ranges: Vec<(f64, f64)>) -> Self {
+        Self {
+            ranges,
+            gamma: 1.0,
+            nodata: None,
+        }
+    }
+
+    /// 创建所有波段共用一个范围的线性拉伸
+    ///
+    /// # 参数
+    /// * `min` - 映射为 0 的值
+    /// * `max` - 映射为 255 的值
+    pub fn linear(min: f64, max: f64) -> Self {
+        Self::new(vec![(min, max)])
+    }
+
+    /// 设置 gamma 值
+    pub fn with_gamma(mut self, gamma: f64) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// 设置无数据值
+    ///
+    /// 除 Alpha 外所有波段都为无数据值(或 NaN)的像素拉伸后数据波段为 0
+    pub fn with_nodata(mut self, nodata: f64) -> Self {
+        self.nodata = Some(nodata);
+        self
+    }
+
+    /// 从栅格数据计算拉伸范围
+    ///
+    /// 通常使用概览图或低分辨率渲染结果计算。无数据值、NaN 和透明的像素会被忽略,
+    /// Alpha 波段不参与计算
+    ///
+    /// # 参数
+    /// * `raster` - 用于统计的栅格
+    /// * `range` - 范围的计算方式
+    /// * `per_band` - 为 true 时每个波段独立计算,否则所有波段使用同一范围
+    /// * `nodata` - 无数据值,会保存在返回的拉伸参数中
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub fn from_raster(
+        raster: &Raster,
+        range: StretchRange,
+        per_band: bool,
+        nodata: Option<f64>,
+    ) -> Result<Self, RasterError> {
+        let alpha = raster.alpha_band();
+        let valid = raster.valid_pixels(nodata)?;
+        let mut bands = (0..raster.band_count())
+            .filter(|band| Some(*band) != alpha)
+            .map(|band| {
+                raster.band::<f64>(band).map(|values| {
+                    values
+                        .zip(&valid)
+                        .filter(|(v, valid)| **valid && !v.is_nan() && nodata != Some(*v))
+                        .map(|(v, _)| v)
+                        .collect::<Vec<f64>>()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !per_band {
+            bands = vec![bands.concat()];
+        }
+        let mut ranges: Vec<_> = bands
+            .iter_mut()
+            .map(|values| value_range(values, range))
+            .collect();
+        // Alpha 波段的位置保留一个占位范围,使范围与波段索引对应
+        if let Some(alpha) = alpha.filter(|alpha| per_band && *alpha <= ranges.len()) {
+            ranges.insert(alpha, (0.0, 255.0));
+        }
+        Ok(Self {
+            nodata,
+            ..Self::new(ranges)
+        })
+    }
+
+    /// 从 COG 的波段统计结果计算每个波段的拉伸范围
+    ///
+    /// 百分位数由直方图估算,适合使用 [`CloudTiff::statistics`](crate::CloudTiff::statistics) 的结果
+    ///
+    /// # 参数
+    /// * `statistics` - 每个波段的统计结果
+    /// * `range` - 范围的计算方式
+    pub fn from_statistics(statistics: &[BandStatistics], range: StretchRange) -> Self {
+        let ranges = statistics
+            .iter()
+            .map(|band| match range {
+                StretchRange::MinMax => (band.min, band.max),
+                StretchRange::Percentile(low, high) => (
+                    band.histogram.percentile(low),
+                    band.histogram.percentile(high),
+                ),
+            })
+            .collect();
+        Self::new(ranges)
+    }
+
+    /// 获取指定波段的拉伸范围
+    ///
+    /// # 参数
+    /// * `band` - 波段索引
+    pub fn range(&self, band: usize) -> (f64, f64) {
+        self.ranges
+            .get(band)
+            .or(self.ranges.first())
+            .copied()
+            .unwrap_or((0.0, 255.0))
+    }
+
+    /// 将单个值映射到 0~255
+    ///
+    /// # 参数
+    /// * `value` - 源值
+    /// * `band` - 波段索引
+    pub fn apply(&self, value: f64, band: usize) -> u8 {
+        let (min, max) = self.range(band);
+        let t = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else if value >= max {
+            1.0
+        } else {
+            0.0
+        };
+        let t = if self.gamma > 0.0 && self.gamma != 1.0 {
+            t.powf(1.0 / self.gamma)
+        } else {
+            t
+        };
+        // NaN 映射为 0
+        (t * 255.0).round() as u8
+    }
+}
+
+/// 计算排序后数据的取值范围
+///
+/// # 参数
+/// * `values` - 样本值,会被就地排序
+/// * `range` - 范围的计算方式
+fn value_range(values: &mut [f64], range: StretchRange) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    match range {
+        StretchRange::MinMax => values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(*v), max.max(*v))
+            }),
+        StretchRange::Percentile(low, high) => {
+            values.sort_unstable_by(|a, b| a.total_cmp(b));
+            let at = |p: f64| {
+                let rank = (p.clamp(0.0, 100.0) / 100.0) * (values.len() - 1) as f64;
+                values[rank.round() as usize]
+            };
+            (at(low), at(high))
+        }
+    }
+}
+
+impl Raster {
+    /// 判断每个像素是否有效
+    ///
+    /// 透明像素和除 Alpha 外所有波段都为无数据值或 NaN 的像素无效
+    ///
+    /// # 参数
+    /// * `nodata` - 无数据值
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub(crate) fn valid_pixels(&self, nodata: Option<f64>) -> Result<Vec<bool>, RasterError> {
+        let pixel_count = self.dimensions.0 as usize * self.dimensions.1 as usize;
+        let alpha = self.alpha_band();
+        let mut valid = vec![false; pixel_count];
+        for band in (0..self.band_count()).filter(|band| Some(*band) != alpha) {
+            for (valid, value) in valid.iter_mut().zip(self.band::<f64>(band)?) {
+                *valid |= !value.is_nan() && nodata != Some(value);
+            }
+        }
+        if let Some(alpha) = alpha {
+            for (valid, value) in valid.iter_mut().zip(self.band::<f64>(alpha)?) {
+                *valid &= value != 0.0;
+            }
+        }
+        Ok(valid)
+    }
+
+    /// 按拉伸参数将栅格转换为每波段 8 位的无符号栅格
+    ///
+    /// 光度解释方式和额外样本保持不变。无效像素(见 [`Stretch::nodata`])的数据波段为 0,
+    /// Alpha 波段不做拉伸,只转换为 8 位
+    ///
+    /// # 参数
+    /// * `stretch` - 拉伸参数
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub fn stretch_to_u8(&self, stretch: &Stretch) -> Result<Raster, RasterError> {
+        self.stretch_to_u8_masked(stretch, None)
+    }
+
+    /// 按拉伸参数转换为每波段 8 位,并清除掩膜外的像素
+    ///
+    /// 与 [`stretch_to_u8`](Self::stretch_to_u8) 相同,但 `mask` 为 false 的像素也视为无效,
+    /// 且这些像素的 Alpha 波段为 0(透明)
+    ///
+    /// # 参数
+    /// * `stretch` - 拉伸参数
+    /// * `mask` - 按行优先排列的有效像素掩膜
+    pub(crate) fn stretch_to_u8_masked(
+        &self,
+        stretch: &Stretch,
+        mask: Option<&[bool]>,
+    ) -> Result<Raster, RasterError> {
+        let band_count = self.band_count();
+        let pixel_count = self.dimensions.0 as usize * self.dimensions.1 as usize;
+        let alpha = self.alpha_band();
+        let mut valid = self.valid_pixels(stretch.nodata)?;
+        if let Some(mask) = mask {
+            for (valid, inside) in valid.iter_mut().zip(mask) {
+                *valid &= *inside;
+            }
+        }
+        let mut buffer = vec![0; pixel_count * band_count];
+        for band in 0..band_count {
+            let values = self.band::<f64>(band)?.zip(&valid).enumerate();
+            if Some(band) == alpha {
+                let sample_type = self.band_type(band);
+                for (i, (value, _)) in values {
+                    let masked = mask.is_some_and(|mask| !mask[i]);
+                    if !masked {
+                        buffer[i * band_count + band] = alpha_to_u8(value, sample_type);
+                    }
+                }
+                continue;
+            }
+            for (i, (value, _)) in values.filter(|(_, (_, valid))| **valid) {
+                buffer[i * band_count + band] = stretch.apply(value, band);
+            }
+        }
+
+        Raster::new(
+            self.dimensions,
+            buffer,
+            vec![8; band_count],
+            self.interpretation,
+            vec![SampleFormat::Unsigned; band_count],
+            self.extra_samples.clone(),
+            Endian::native(),
+        )
+    }
+}
@@ -0,0 +1,266 @@
+//! 地形分析模块
+//!
+//! 本模块提供了基于数字高程模型(DEM)的地形分析功能:
+//! - 山体阴影(可配置光源方位角、高度角和高程缩放系数)
+//! - 坡度(度)
+//! - 坡向(度,正北为 0,顺时针)
+//!
+//! 梯度使用 Horn 方法在 3x3 窗口上计算。栅格边缘的像素使用最近的有效像素补齐,
+//! 因此渲染瓦片时应在四周多渲染一个像素(参见 `RenderBuilder::with_terrain`),避免瓦片接缝。
+
+use super::{PhotometricInterpretation, Raster, RasterError, SampleFormat};
+use crate::tiff::Endian;
+
+/// 地形分析的类型
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TerrainKind {
+    /// 山体阴影,输出 8 位灰度,0 表示无数据,有效值为 1~255
+    Hillshade {
+        /// 光源方位角(度),正北为 0,顺时针
+        azimuth: f64,
+        /// 光源高度角(度),地平线为 0
+        altitude: f64,
+    },
+    /// 坡度,输出 Float32,单位为度,无数据为 NaN
+    Slope,
+    /// 坡向,输出 Float32,单位为度,正北为 0,顺时针,平地和无数据为 NaN
+    Aspect,
+}
+
+/// 地形分析参数
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::raster::Terrain;
+///
+/// # fn example(dem: &cloudtiff::Raster) {
+/// let terrain = Terrain::hillshade().with_z_factor(2.0).with_nodata(-9999.0);
+/// let shaded = dem.terrain(&terrain, (30.0, 30.0)).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Terrain {
+    /// 分析类型
+    pub kind: TerrainKind,
+    /// 高程缩放系数,用于高程单位与水平单位不一致或夸大地形
+    pub z_factor: f64,
+    /// 无数据值,NaN 始终被视为无数据
+    pub nodata: Option<f64>,
+}
+
+impl Terrain {
+    /// 创建地形分析参数
+    ///
+    /// # 参数
+    /// * `kind` - 分析类型
+    pub fn new(kind: TerrainKind) -> Self {
+        Self {
+            kind,
+            z_factor: 1.0,
+            nodata: None,
+        }
+    }
+
+    /// 光源位于西北方向(方位角 315°,高度角 45°)的山体阴影
+    pub fn hillshade() -> Self {
+        Self::new(TerrainKind::Hillshade {
+            azimuth: 315.0,
+            altitude: 45.0,
+        })
+    }
+
+    /// 指定光源方向的山体阴影
+    ///
+    /// # 参数
+    /// * `azimuth` - 光源方位角(度),正北为 0,顺时针
+    /// * `altitude` - 光源高度角(度)
+    pub fn hillshade_from(azimuth: f64, altitude: f64) -> Self {
+        Self::new(TerrainKind::Hillshade { azimuth, altitude })
+    }
+
+    /// 坡度
+    pub fn slope() -> Self {
+        Self::new(TerrainKind::Slope)
+    }
+
+    /// 坡向
+    pub fn aspect() -> Self {
+        Self::new(TerrainKind::Aspect)
+    }
+
+    /// 设置高程缩放系数
+    pub fn with_z_factor(mut self, z_factor: f64) -> Self {
+        self.z_factor = z_factor;
+        self
+    }
+
+    /// 设置无数据值
+    pub fn with_nodata(mut self, nodata: f64) -> Self {
+        self.nodata = Some(nodata);
+        self
+    }
+
+    /// 判断高程值是否有效
+    fn is_valid(&self, value: f64) -> bool {
+        !value.is_nan() && self.nodata != Some(value)
+    }
+}
+
+impl Raster {
+    /// 对第一个波段进行地形分析
+    ///
+    /// # 参数
+    /// * `terrain` - 地形分析参数
+    /// * `pixel_size` - 像素的地面尺寸 (x, y),与高程使用相同单位(通常为米)
+    ///
+    /// # 返回
+    /// 山体阴影返回 8 位灰度栅格,坡度和坡向返回 Float32 栅格
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub fn terrain(
+        &self,
+        terrain: &Terrain,
+        pixel_size: (f64, f64),
+    ) -> Result<Raster, RasterError> {
+        let (width, height) = (self.dimensions.0 as usize, self.dimensions.1 as usize);
+        let elevation: Vec<f64> = self.band::<f64>(0)?.collect();
+        let (dx, dy) = (pixel_size.0.abs(), pixel_size.1.abs());
+
+        // 每个像素的梯度 (东向, 南向),无数据时为 None
+        let gradient = |i: usize, j: usize| -> Option<(f64, f64)> {
+            let centre = elevation[j * width + i];
+            if !terrain.is_valid(centre) {
+                return None;
+            }
+            // 取 3x3 窗口的值,超出栅格或无数据时使用中心值
+            let z = |di: isize, dj: isize| {
+                let x = i as isize + di;
+                let y = j as isize + dj;
+                if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+                    return centre;
+                }
+                let value = elevation[y as usize * width + x as usize];
+                if terrain.is_valid(value) {
+                    value
+                } else {
+                    centre
+                }
+            };
+            let (a, b, c) = (z(-1, -1), z(0, -1), z(1, -1));
+            let (d, f) = (z(-1, 0), z(1, 0));
+            let (g, h, k) = (z(-1, 1), z(0, 1), z(1, 1));
+            let dzdx = ((c + 2.0 * f + k) - (a + 2.0 * d + g)) / (8.0 * dx);
+            let dzdy = ((g + 2.0 * h + k) - (a + 2.0 * b + c)) / (8.0 * dy);
+            Some((dzdx * terrain.z_factor, dzdy * terrain.z_factor))
+        };
+
+        match terrain.kind {
+            TerrainKind::Hillshade { azimuth, altitude } => {
+                // 光源方向向量 (东, 北, 上)
+                let (azimuth, altitude) = (azimuth.to_radians(), altitude.to_radians());
+                let light = (
+                    azimuth.sin() * altitude.cos(),
+                    azimuth.cos() * altitude.cos(),
+                    altitude.sin(),
+                );
+                let mut buffer = Vec::with_capacity(width * height);
+                for j in 0..height {
+                    for i in 0..width {
+                        let value = gradient(i, j).map_or(0, |(dzdx, dzdy)| {
+                            // 表面法向量 (东, 北, 上),北向梯度为南向梯度取反
+                            let normal = (-dzdx, dzdy, 1.0);
+                            let length = (normal.0 * normal.0 + normal.1 * normal.1 + 1.0).sqrt();
+                            let shade =
+                                (normal.0 * light.0 + normal.1 * light.1 + light.2) / length;
+                            (1.0 + 254.0 * shade.max(0.0)).round() as u8
+                        });
+                        buffer.push(value);
+                    }
+                }
+                Raster::new(
+                    self.dimensions,
+                    buffer,
+                    vec![8],
+                    PhotometricInterpretation::BlackIsZero,
+                    vec![SampleFormat::Unsigned],
+                    vec![],
+                    Endian::native(),
+                )
+            }
+            TerrainKind::Slope | TerrainKind::Aspect => {
+                let mut output: Vec<f32> = Vec::with_capacity(width * height);
+                for j in 0..height {
+                    for i in 0..width {
+                        let value = match (terrain.kind, gradient(i, j)) {
+                            (_, None) => f64::NAN,
+                            (TerrainKind::Slope, Some((dzdx, dzdy))) => {
+                                (dzdx * dzdx + dzdy * dzdy).sqrt().atan().to_degrees()
+                            }
+                            (_, Some((dzdx, dzdy))) if dzdx == 0.0 && dzdy == 0.0 => f64::NAN,
+                            (_, Some((dzdx, dzdy))) => {
+                                // 坡向为下坡方向 (东 = -dzdx, 北 = dzdy) 的方位角
+                                (-dzdx).atan2(dzdy).to_degrees().rem_euclid(360.0)
+                            }
+                        };
+                        output.push(value as f32);
+                    }
+                }
+                Raster::new(
+                    self.dimensions,
+                    bytemuck::cast_slice(&output).to_vec(),
+                    vec![32],
+                    PhotometricInterpretation::BlackIsZero,
+                    vec![SampleFormat::Float],
+                    vec![],
+                    Endian::native(),
+                )
+            }
+        }
+    }
+
+    /// 计算山体阴影
+    ///
+    /// # 参数
+    /// * `pixel_size` - 像素的地面尺寸 (x, y)
+    /// * `azimuth` - 光源方位角(度),正北为 0,顺时针
+    /// * `altitude` - 光源高度角(度)
+    /// * `z_factor` - 高程缩放系数
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub fn hillshade(
+        &self,
+        pixel_size: (f64, f64),
+        azimuth: f64,
+        altitude: f64,
+        z_factor: f64,
+    ) -> Result<Raster, RasterError> {
+        let terrain = Terrain::hillshade_from(azimuth, altitude).with_z_factor(z_factor);
+        self.terrain(&terrain, pixel_size)
+    }
+
+    /// 计算坡度(度)
+    ///
+    /// # 参数
+    /// * `pixel_size` - 像素的地面尺寸 (x, y)
+    /// * `z_factor` - 高程缩放系数
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub fn slope(&self, pixel_size: (f64, f64), z_factor: f64) -> Result<Raster, RasterError> {
+        self.terrain(&Terrain::slope().with_z_factor(z_factor), pixel_size)
+    }
+
+    /// 计算坡向(度,正北为 0,顺时针)
+    ///
+    /// # 参数
+    /// * `pixel_size` - 像素的地面尺寸 (x, y)
+    ///
+    /// # 错误
+    /// 如果样本类型不受支持则返回错误
+    pub fn aspect(&self, pixel_size: (f64, f64)) -> Result<Raster, RasterError> {
+        self.terrain(&Terrain::aspect(), pixel_size)
+    }
+}
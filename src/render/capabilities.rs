@@ -0,0 +1,406 @@
+//! 瓦片服务描述文档模块
+//!
+//! 本模块提供了为 COG 生成瓦片服务发现文档的功能,供基于本库的瓦片服务器响应客户端的发现请求:
+//! - WMTS 1.0 GetCapabilities XML(支持任意左上角原点的瓦片矩阵集)
+//! - TileJSON 3.0(需要 `json` 特性)
+//!
+//! 文档中的边界取自 [`CloudTiff::bounds_lat_lon_deg`],缩放级别范围与瓦片树的计算方式一致。
+//! 跨越 ±180° 经线的影像写入的东边界折回 [-180, 180],西边界大于东边界。
+//! URL 模板使用 XYZ 风格的 `{z}`、`{x}`、`{y}` 占位符,生成 WMTS 文档时会转换为
+//! `{TileMatrix}`、`{TileCol}`、`{TileRow}`。
+
+use super::tms::{CornerOfOrigin, TileMatrixSet};
+use crate::cog::{CloudTiff, CloudTiffError, CloudTiffResult};
+use crate::projection::ProjectionError;
+use crate::Region;
+use proj4rs::Proj;
+use std::f64::consts::TAU;
+use std::fmt::Write;
+
+/// WMTS 标准规定的像素尺寸(米)
+const STANDARDIZED_PIXEL_SIZE: f64 = 0.28e-3;
+
+/// WGS84 椭球长半轴(米),用于将度换算为米
+const WGS84_SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+
+/// 瓦片服务的描述信息
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::render::capabilities::TileService;
+///
+/// let service = TileService::new("dem", "https://tiles.example.com/dem/{z}/{x}/{y}.png")
+///     .with_title("数字高程模型")
+///     .with_attribution("© Example");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileService {
+    /// 图层标识
+    pub name: String,
+    /// 瓦片 URL 模板,使用 `{z}`、`{x}`、`{y}` 占位符
+    pub url_template: String,
+    /// 图层标题,默认使用图层标识
+    pub title: Option<String>,
+    /// 图层描述,默认描述影像的尺寸和波段数
+    pub description: Option<String>,
+    /// 版权信息
+    pub attribution: Option<String>,
+    /// 瓦片的 MIME 类型,默认为 `image/png`
+    pub format: String,
+    /// XYZ 瓦片的边长(像素),用于计算 TileJSON 的缩放级别范围,默认为 256
+    pub tile_size: u32,
+}
+
+impl TileService {
+    /// 创建瓦片服务描述
+    ///
+    /// # 参数
+    /// * `name` - 图层标识
+    /// * `url_template` - 瓦片 URL 模板,使用 `{z}`、`{x}`、`{y}` 占位符
+    pub fn new(name: impl Into<String>, url_template: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url_template: url_template.into(),
+            title: None,
+            description: None,
+            attribution: None,
+            format: "image/png".to_string(),
+            tile_size: 256,
+        }
+    }
+
+    /// 设置图层标题
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// 设置图层描述
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// 设置版权信息
+    pub fn with_attribution(mut self, attribution: impl Into<String>) -> Self {
+        self.attribution = Some(attribution.into());
+        self
+    }
+
+    /// 设置瓦片的 MIME 类型,例如 `image/jpeg`、`image/webp`
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
+        self.format = format.into();
+        self
+    }
+
+    /// 设置 XYZ 瓦片的边长(像素)
+    pub fn with_tile_size(mut self, tile_size: u32) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+
+    /// 图层标题,未设置时使用图层标识
+    fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+
+    /// 图层描述,未设置时描述影像的尺寸和波段数
+    fn description(&self, cog: &CloudTiff) -> String {
+        self.description.clone().unwrap_or_else(|| {
+            let (width, height) = cog.full_dimensions();
+            let bands = cog.band_count();
+            format!("{width}x{height} pixels, {bands} band(s)")
+        })
+    }
+
+    /// 转换为 WMTS 风格的 URL 模板
+    fn wmts_url_template(&self) -> String {
+        self.url_template
+            .replace("{z}", "{TileMatrix}")
+            .replace("{x}", "{TileCol}")
+            .replace("{y}", "{TileRow}")
+    }
+}
+
+impl CloudTiff {
+    /// 生成 WMTS 1.0 GetCapabilities 文档
+    ///
+    /// 文档包含一个图层和所用的瓦片矩阵集,图层的瓦片范围限制在影像覆盖的瓦片内
+    ///
+    /// # 参数
+    /// * `service` - 瓦片服务描述
+    /// * `tms` - 瓦片矩阵集,例如 [`TileMatrixSet::web_mercator_quad`]
+    ///
+    /// # 返回
+    /// GetCapabilities XML 文本
+    ///
+    /// # 错误
+    /// 坐标系无法识别,或瓦片矩阵的原点不在左上角(WMTS 1.0 不支持)时返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use cloudtiff::render::capabilities::TileService;
+    /// use cloudtiff::TileMatrixSet;
+    ///
+    /// # fn example(cog: &cloudtiff::CloudTiff) {
+    /// let service = TileService::new("dem", "https://tiles.example.com/dem/{z}/{x}/{y}.png");
+    /// let xml = cog
+    ///     .wmts_capabilities(&service, &TileMatrixSet::web_mercator_quad())
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn wmts_capabilities(
+        &self,
+        service: &TileService,
+        tms: &TileMatrixSet,
+    ) -> CloudTiffResult<String> {
+        if let Some(matrix) = tms
+            .tile_matrices
+            .iter()
+            .find(|m| m.corner_of_origin != CornerOfOrigin::TopLeft)
+        {
+            return Err(CloudTiffError::NotSupported(format!(
+                "WMTS 1.0 要求瓦片矩阵原点位于左上角: {}",
+                matrix.id
+            )));
+        }
+
+        let (west, south, east, north) = self.bounds_lat_lon_deg()?.wrapped_bounds(180.0);
+        let (bounds, (min_z, max_z)) = self.bounds_tms(tms)?;
+        let meters_per_unit = meters_per_unit(tms)?;
+        let format = escape_xml(&service.format);
+
+        // 使用 write! 写入 String 不会失败
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(concat!(
+            "<Capabilities xmlns=\"http://www.opengis.net/wmts/1.0\"",
+            " xmlns:ows=\"http://www.opengis.net/ows/1.1\"",
+            " xmlns:xlink=\"http://www.w3.org/1999/xlink\"",
+            " xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"",
+            " xsi:schemaLocation=\"http://www.opengis.net/wmts/1.0",
+            " http://schemas.opengis.net/wmts/1.0/wmtsGetCapabilities_response.xsd\"",
+            " version=\"1.0.0\">\n",
+        ));
+
+        // 服务信息
+        xml.push_str("  <ows:ServiceIdentification>\n");
+        let _ = writeln!(
+            xml,
+            "    <ows:Title>{}</ows:Title>",
+            escape_xml(service.title())
+        );
+        xml.push_str("    <ows:ServiceType>OGC WMTS</ows:ServiceType>\n");
+        xml.push_str("    <ows:ServiceTypeVersion>1.0.0</ows:ServiceTypeVersion>\n");
+        xml.push_str("  </ows:ServiceIdentification>\n");
+
+        // 图层
+        xml.push_str("  <Contents>\n    <Layer>\n");
+        let _ = writeln!(
+            xml,
+            "      <ows:Title>{}</ows:Title>",
+            escape_xml(service.title())
+        );
+        let _ = writeln!(
+            xml,
+            "      <ows:Abstract>{}</ows:Abstract>",
+            escape_xml(&service.description(self))
+        );
+        xml.push_str("      <ows:WGS84BoundingBox>\n");
+        let _ = writeln!(
+            xml,
+            "        <ows:LowerCorner>{west} {south}</ows:LowerCorner>"
+        );
+        let _ = writeln!(
+            xml,
+            "        <ows:UpperCorner>{east} {north}</ows:UpperCorner>"
+        );
+        xml.push_str("      </ows:WGS84BoundingBox>\n");
+        let _ = writeln!(
+            xml,
+            "      <ows:Identifier>{}</ows:Identifier>",
+            escape_xml(&service.name)
+        );
+        xml.push_str("      <Style isDefault=\"true\">\n");
+        xml.push_str("        <ows:Identifier>default</ows:Identifier>\n");
+        xml.push_str("      </Style>\n");
+        let _ = writeln!(xml, "      <Format>{format}</Format>");
+        xml.push_str("      <TileMatrixSetLink>\n");
+        let _ = writeln!(
+            xml,
+            "        <TileMatrixSet>{}</TileMatrixSet>",
+            escape_xml(&tms.id)
+        );
+        xml.push_str("        <TileMatrixSetLimits>\n");
+        for z in min_z..=max_z {
+            // 跨越 ±180° 经线的影像使用东西两部分的并集
+            let range = tms.tile_ranges(z, &bounds).into_iter().reduce(|a, b| {
+                Region::new(
+                    a.x.min.min(b.x.min),
+                    a.y.min.min(b.y.min),
+                    a.x.max.max(b.x.max),
+                    a.y.max.max(b.y.max),
+                )
+            });
+            let (Some(matrix), Some(range)) = (tms.tile_matrix(z), range) else {
+                continue;
+            };
+            xml.push_str("          <TileMatrixLimits>\n");
+            let _ = writeln!(
+                xml,
+                "            <TileMatrix>{}</TileMatrix>",
+                escape_xml(&matrix.id)
+            );
+            let _ = writeln!(xml, "            <MinTileRow>{}</MinTileRow>", range.y.min);
+            let _ = writeln!(
+                xml,
+                "            <MaxTileRow>{}</MaxTileRow>",
+                range.y.max - 1
+            );
+            let _ = writeln!(xml, "            <MinTileCol>{}</MinTileCol>", range.x.min);
+            let _ = writeln!(
+                xml,
+                "            <MaxTileCol>{}</MaxTileCol>",
+                range.x.max - 1
+            );
+            xml.push_str("          </TileMatrixLimits>\n");
+        }
+        xml.push_str("        </TileMatrixSetLimits>\n");
+        xml.push_str("      </TileMatrixSetLink>\n");
+        let _ = writeln!(
+            xml,
+            "      <ResourceURL format=\"{format}\" resourceType=\"tile\" template=\"{}\"/>",
+            escape_xml(&service.wmts_url_template())
+        );
+        xml.push_str("    </Layer>\n");
+
+        // 瓦片矩阵集,只列出到图层最大缩放级别的矩阵
+        xml.push_str("    <TileMatrixSet>\n");
+        let _ = writeln!(
+            xml,
+            "      <ows:Identifier>{}</ows:Identifier>",
+            escape_xml(&tms.id)
+        );
+        let _ = writeln!(
+            xml,
+            "      <ows:SupportedCRS>{}</ows:SupportedCRS>",
+            crs_urn(tms.epsg)
+        );
+        for matrix in tms.tile_matrices.iter().take(max_z as usize + 1) {
+            let scale_denominator = matrix.cell_size * meters_per_unit / STANDARDIZED_PIXEL_SIZE;
+            xml.push_str("      <TileMatrix>\n");
+            let _ = writeln!(
+                xml,
+                "        <ows:Identifier>{}</ows:Identifier>",
+                escape_xml(&matrix.id)
+            );
+            let _ = writeln!(
+                xml,
+                "        <ScaleDenominator>{scale_denominator}</ScaleDenominator>"
+            );
+            let _ = writeln!(
+                xml,
+                "        <TopLeftCorner>{} {}</TopLeftCorner>",
+                matrix.origin.0, matrix.origin.1
+            );
+            let _ = writeln!(xml, "        <TileWidth>{}</TileWidth>", matrix.tile_width);
+            let _ = writeln!(
+                xml,
+                "        <TileHeight>{}</TileHeight>",
+                matrix.tile_height
+            );
+            let _ = writeln!(
+                xml,
+                "        <MatrixWidth>{}</MatrixWidth>",
+                matrix.matrix_width
+            );
+            let _ = writeln!(
+                xml,
+                "        <MatrixHeight>{}</MatrixHeight>",
+                matrix.matrix_height
+            );
+            xml.push_str("      </TileMatrix>\n");
+        }
+        xml.push_str("    </TileMatrixSet>\n");
+        xml.push_str("  </Contents>\n</Capabilities>\n");
+        Ok(xml)
+    }
+
+    /// 生成 TileJSON 3.0 文档
+    ///
+    /// 瓦片为 Web 墨卡托下的 XYZ 瓦片,缩放级别范围由 [`super::wmts::bounds_wmts`] 按
+    /// 服务的瓦片尺寸计算。除标准字段外还包含 `bands` 字段记录波段数
+    ///
+    /// # 参数
+    /// * `service` - 瓦片服务描述
+    ///
+    /// # 返回
+    /// TileJSON 文本
+    ///
+    /// # 错误
+    /// 影像边界无法转换为经纬度时返回错误
+    #[cfg(feature = "json")]
+    pub fn tile_json(&self, service: &TileService) -> CloudTiffResult<String> {
+        let bounds = self.bounds_lat_lon_deg()?;
+        let tile_dim = (service.tile_size, service.tile_size);
+        let (_, (min_z, max_z)) =
+            super::wmts::bounds_wmts(bounds, self.full_dimensions(), tile_dim);
+        let (west, south, east, north) = bounds.wrapped_bounds(180.0);
+        let center = bounds.wrapped_center(180.0);
+
+        let mut json = serde_json::json!({
+            "tilejson": "3.0.0",
+            "name": service.title(),
+            "description": service.description(self),
+            "tiles": [service.url_template],
+            "scheme": "xyz",
+            "minzoom": min_z,
+            "maxzoom": max_z,
+            "bounds": [west, south, east, north],
+            "center": [center.x, center.y, min_z],
+            "bands": self.band_count(),
+        });
+        if let Some(attribution) = &service.attribution {
+            json["attribution"] = attribution.as_str().into();
+        }
+        Ok(format!("{json:#}"))
+    }
+}
+
+/// 瓦片矩阵集坐标系单位对应的米数
+fn meters_per_unit(tms: &TileMatrixSet) -> Result<f64, ProjectionError> {
+    let proj = Proj::from_epsg_code(tms.epsg)?;
+    Ok(if proj.is_latlong() {
+        TAU * WGS84_SEMI_MAJOR_AXIS / 360.0
+    } else {
+        proj.to_meter()
+    })
+}
+
+/// 坐标系的 URN
+///
+/// EPSG:4326 使用经度在前的 OGC CRS84,与瓦片矩阵原点的 (经度, 纬度) 顺序一致
+fn crs_urn(epsg: u16) -> String {
+    match epsg {
+        4326 => "urn:ogc:def:crs:OGC:1.3:CRS84".to_string(),
+        epsg => format!("urn:ogc:def:crs:EPSG::{epsg}"),
+    }
+}
+
+/// 转义 XML 特殊字符
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
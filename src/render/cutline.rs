@@ -0,0 +1,703 @@
+//! 裁切多边形模块
+//!
+//! 本模块提供按矢量多边形(如行政区划边界)裁切渲染结果的功能:
+//! - 多边形可以从 WKT(`POLYGON`/`MULTIPOLYGON`,支持 EWKT 的 `SRID=` 前缀)
+//!   或 GeoJSON(需要 `json` 特性)解析,可以使用任意坐标系
+//! - 渲染时将多边形投影到输出像素网格,按像素中心是否位于多边形内(奇偶规则,支持洞和多个多边形)生成掩膜
+//! - 多边形外的输出像素不采样,只被这些像素使用的瓦片不会被读取
+//!
+//! 多边形的边在投影后会自适应加密,使弯曲的边界在输出像素网格中的误差小于四分之一像素。
+//!
+//! # 示例
+//!
+//! ```no_run
+//! use cloudtiff::render::cutline::Cutline;
+//! use std::fs::File;
+//!
+//! # fn example(cog: &cloudtiff::CloudTiff) {
+//! let cutline = Cutline::from_wkt(
+//!     "POLYGON ((-129 54.96, -128.92 54.96, -128.92 54.99, -129 54.96))",
+//!     4326,
+//! )
+//! .unwrap();
+//! let tile = cog
+//!     .renderer()
+//!     .with_reader(File::open("image.tif").unwrap())
+//!     .with_cutline(cutline)
+//!     .render_tile(14, 2822, 5172, 256)
+//!     .unwrap();
+//! # }
+//! ```
+
+use crate::projection::geo_transform::GeoTransform;
+use crate::projection::{Projection, ProjectionError};
+use crate::raster::{Raster, RasterError, SampleFormat};
+use crate::{Region, UnitFloat};
+use proj4rs::transform::transform;
+use proj4rs::Proj;
+use std::fmt;
+use tracing::warn;
+
+/// 多边形的边投影后允许的最大偏差(输出像素)
+const DENSIFY_TOLERANCE: f64 = 0.25;
+
+/// 多边形的边最多二分的次数
+const DENSIFY_MAX_DEPTH: u32 = 8;
+
+/// 多边形,第一个环为外环,其余为洞,顶点为 (x, y)
+pub type Polygon = Vec<Vec<(f64, f64)>>;
+
+/// 裁切多边形错误
+#[derive(Debug)]
+pub enum CutlineError {
+    /// WKT 格式错误,包含错误描述
+    InvalidWkt(String),
+    /// GeoJSON 格式错误,包含错误描述
+    InvalidGeoJson(String),
+    /// JSON 解析错误
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+    /// 坐标系不受支持
+    Projection(ProjectionError),
+}
+
+impl fmt::Display for CutlineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for CutlineError {}
+
+impl From<ProjectionError> for CutlineError {
+    fn from(e: ProjectionError) -> Self {
+        CutlineError::Projection(e)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for CutlineError {
+    fn from(e: serde_json::Error) -> Self {
+        CutlineError::Json(e)
+    }
+}
+
+/// 裁切多边形
+#[derive(Clone, Debug)]
+pub struct Cutline {
+    /// 坐标系 EPSG 代码
+    epsg: u16,
+    /// 坐标系的投影对象
+    proj: Proj,
+    /// 多边形,地理坐标系使用度
+    polygons: Vec<Polygon>,
+}
+
+impl Cutline {
+    /// 创建裁切多边形
+    ///
+    /// # 参数
+    /// * `epsg` - 多边形坐标的坐标系 EPSG 代码
+    /// * `polygons` - 多边形,地理坐标系使用度,顶点顺序为 (经度, 纬度)
+    ///
+    /// # 错误
+    /// 坐标系不受支持时返回错误
+    pub fn new(epsg: u16, polygons: Vec<Polygon>) -> Result<Self, CutlineError> {
+        let proj = Proj::from_epsg_code(epsg).map_err(ProjectionError::from)?;
+        Ok(Self {
+            epsg,
+            proj,
+            polygons,
+        })
+    }
+
+    /// 从 WKT 解析裁切多边形
+    ///
+    /// 支持 `POLYGON` 和 `MULTIPOLYGON`,Z/M 坐标会被忽略。
+    /// 带有 EWKT 的 `SRID=<代码>;` 前缀时使用前缀中的坐标系
+    ///
+    /// # 参数
+    /// * `wkt` - WKT 文本
+    /// * `epsg` - 多边形坐标的坐标系 EPSG 代码
+    ///
+    /// # 错误
+    /// WKT 格式错误、几何类型不是多边形或坐标系不受支持时返回错误
+    pub fn from_wkt(wkt: &str, epsg: u16) -> Result<Self, CutlineError> {
+        let (epsg, wkt) = match wkt.trim().split_once(';') {
+            Some((srid, rest)) if srid.trim().to_uppercase().starts_with("SRID=") => {
+                let code = srid.trim()[5..].trim();
+                let epsg = code
+                    .parse()
+                    .map_err(|_| CutlineError::InvalidWkt(format!("无效的 SRID: {code}")))?;
+                (epsg, rest)
+            }
+            _ => (epsg, wkt),
+        };
+        Self::new(epsg, wkt::parse(wkt)?)
+    }
+
+    /// 从 GeoJSON 解析裁切多边形
+    ///
+    /// 支持 `Polygon` 和 `MultiPolygon` 几何,以及包含它们的 `Feature`、
+    /// `FeatureCollection` 和 `GeometryCollection`,其他几何类型会被忽略。
+    /// 标准 GeoJSON 使用 EPSG:4326
+    ///
+    /// # 参数
+    /// * `geojson` - GeoJSON 文本
+    /// * `epsg` - 多边形坐标的坐标系 EPSG 代码
+    ///
+    /// # 错误
+    /// JSON 格式错误、不包含多边形或坐标系不受支持时返回错误
+    #[cfg(feature = "json")]
+    pub fn from_geojson(geojson: &str, epsg: u16) -> Result<Self, CutlineError> {
+        let value: serde_json::Value = serde_json::from_str(geojson)?;
+        let mut polygons = vec![];
+        geojson::collect_polygons(&value, &mut polygons)?;
+        if polygons.is_empty() {
+            return Err(CutlineError::InvalidGeoJson("不包含多边形".into()));
+        }
+        Self::new(epsg, polygons)
+    }
+
+    /// 多边形坐标的坐标系 EPSG 代码
+    pub fn epsg(&self) -> u16 {
+        self.epsg
+    }
+
+    /// 多边形,地理坐标系使用度
+    pub fn polygons(&self) -> &[Polygon] {
+        &self.polygons
+    }
+
+    /// 多边形在其坐标系下的外包区域,地理坐标系使用度
+    pub fn bounds(&self) -> Region<f64> {
+        let points = self.polygons.iter().flatten().flatten();
+        points.fold(
+            Region::new(f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |bounds, &(x, y)| {
+                Region::new(
+                    bounds.x.min.min(x),
+                    bounds.y.min.min(y),
+                    bounds.x.max.max(x),
+                    bounds.y.max.max(y),
+                )
+            },
+        )
+    }
+
+    /// 计算输出像素网格的掩膜
+    ///
+    /// # 参数
+    /// * `epsg` - 输出坐标系 EPSG 代码
+    /// * `grid` - 输出像素网格的仿射变换,使用投影的原生单位(地理坐标系为弧度)
+    /// * `dimensions` - 输出尺寸
+    ///
+    /// # 返回
+    /// 按行优先排列,像素中心位于多边形内时为 true
+    ///
+    /// # 错误
+    /// 输出坐标系不受支持时返回错误
+    pub fn mask_grid(
+        &self,
+        epsg: u16,
+        grid: &GeoTransform,
+        dimensions: (u32, u32),
+    ) -> Result<Vec<bool>, ProjectionError> {
+        let to = Proj::from_epsg_code(epsg)?;
+        let rings = self.pixel_rings(|x, y| {
+            let mut point = (x, y, 0.0);
+            if epsg != self.epsg {
+                transform(&self.proj, &to, &mut point).ok()?;
+            }
+            grid.invert(point.0, point.1)
+        });
+        Ok(rasterize(&rings, dimensions))
+    }
+
+    /// 计算输入裁剪区域的掩膜
+    ///
+    /// # 参数
+    /// * `projection` - 影像的投影
+    /// * `crop` - 归一化的裁剪区域
+    /// * `dimensions` - 输出尺寸
+    ///
+    /// # 返回
+    /// 按行优先排列,像素中心位于多边形内时为 true
+    pub fn mask_crop(
+        &self,
+        projection: &Projection,
+        crop: &Region<UnitFloat>,
+        dimensions: (u32, u32),
+    ) -> Vec<bool> {
+        let (left, top, right, bottom) = crop.to_f64();
+        let (width, height) = (dimensions.0 as f64, dimensions.1 as f64);
+        let rings = self.pixel_rings(|x, y| {
+            let (u, v, _) = projection.transform_from_proj(&self.proj, x, y, 0.0).ok()?;
+            Some((
+                (u - left) / (right - left) * width,
+                (v - top) / (bottom - top) * height,
+            ))
+        });
+        rasterize(&rings, dimensions)
+    }
+
+    /// 将所有环投影到像素坐标并自适应加密
+    ///
+    /// # 参数
+    /// * `to_pixel` - 将多边形坐标(投影的原生单位)转换为像素坐标,失败时返回 None
+    fn pixel_rings<F>(&self, to_pixel: F) -> Vec<Vec<(f64, f64)>>
+    where
+        F: Fn(f64, f64) -> Option<(f64, f64)>,
+    {
+        // 地理坐标系的多边形使用度,投影使用弧度
+        let native = if self.proj.is_latlong() {
+            1_f64.to_radians()
+        } else {
+            1.0
+        };
+        let to_pixel = |(x, y): (f64, f64)| to_pixel(x * native, y * native);
+
+        let mut rings = vec![];
+        let mut failed = 0;
+        for ring in self.polygons.iter().flatten() {
+            let mut pixels = vec![];
+            // 闭合环,首尾顶点相同时多出的边长度为零
+            for (&a, &b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+                match (to_pixel(a), to_pixel(b)) {
+                    (Some(pa), Some(pb)) => {
+                        pixels.push(pa);
+                        densify(&to_pixel, (a, pa), (b, pb), DENSIFY_MAX_DEPTH, &mut pixels);
+                    }
+                    (Some(pa), None) => pixels.push(pa),
+                    _ => failed += 1,
+                }
+            }
+            if pixels.len() >= 3 {
+                rings.push(pixels);
+            }
+        }
+        if failed > 0 {
+            warn!("裁切多边形有 {failed} 个顶点投影失败");
+        }
+        rings
+    }
+}
+
+/// 在边的两个端点之间插入顶点,使投影后的边与真实曲线的偏差不超过容限
+///
+/// 插入的顶点(不包括两个端点)按顺序追加到 `pixels`
+fn densify<F>(
+    to_pixel: &F,
+    (a, pa): ((f64, f64), (f64, f64)),
+    (b, pb): ((f64, f64), (f64, f64)),
+    depth: u32,
+    pixels: &mut Vec<(f64, f64)>,
+) where
+    F: Fn((f64, f64)) -> Option<(f64, f64)>,
+{
+    if depth == 0 {
+        return;
+    }
+    let m = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let Some(pm) = to_pixel(m) else {
+        return;
+    };
+    let linear = ((pa.0 + pb.0) / 2.0, (pa.1 + pb.1) / 2.0);
+    if (pm.0 - linear.0).hypot(pm.1 - linear.1) <= DENSIFY_TOLERANCE {
+        return;
+    }
+    densify(to_pixel, (a, pa), (m, pm), depth - 1, pixels);
+    pixels.push(pm);
+    densify(to_pixel, (m, pm), (b, pb), depth - 1, pixels);
+}
+
+/// 按奇偶规则栅格化像素坐标下的多边形环
+///
+/// # 返回
+/// 按行优先排列,像素中心位于多边形内时为 true
+fn rasterize(rings: &[Vec<(f64, f64)>], dimensions: (u32, u32)) -> Vec<bool> {
+    let (width, height) = (dimensions.0 as usize, dimensions.1 as usize);
+    let mut mask = vec![false; width * height];
+
+    // 每行像素中心所在的水平线与所有边的交点
+    let mut crossings: Vec<Vec<f64>> = vec![vec![]; height];
+    for ring in rings {
+        for (&(x0, y0), &(x1, y1)) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+            if y0 == y1 {
+                continue;
+            }
+            // 与像素中心 y = j + 0.5 相交的行,区间为左闭右开避免在顶点处重复计数
+            let (low, high) = (y0.min(y1), y0.max(y1));
+            let first = (low - 0.5).ceil().max(0.0);
+            let last = ((high - 0.5).ceil() - 1.0).min(height as f64 - 1.0);
+            let mut j = first;
+            while j <= last {
+                let y = j + 0.5;
+                crossings[j as usize].push(x0 + (y - y0) * (x1 - x0) / (y1 - y0));
+                j += 1.0;
+            }
+        }
+    }
+
+    for (j, row) in crossings.iter_mut().enumerate() {
+        row.sort_by(f64::total_cmp);
+        for pair in row.chunks_exact(2) {
+            // 像素中心 x = i + 0.5 位于 [左交点, 右交点) 内
+            let start = (pair[0] - 0.5).ceil().clamp(0.0, width as f64) as usize;
+            let end = (pair[1] - 0.5).ceil().clamp(0.0, width as f64) as usize;
+            for inside in &mut mask[j * width + start..j * width + end.max(start)] {
+                *inside = true;
+            }
+        }
+    }
+    mask
+}
+
+/// 将掩膜外的像素设为无数据值
+///
+/// Alpha 波段设为 0(透明),其他波段设为无数据值;未指定无数据值时浮点波段为 NaN,整数波段为 0
+///
+/// # 参数
+/// * `raster` - 栅格
+/// * `mask` - 按行优先排列的掩膜,false 的像素被清除
+/// * `nodata` - 无数据值
+pub(crate) fn apply_mask(
+    raster: &mut Raster,
+    mask: &[bool],
+    nodata: Option<f64>,
+) -> Result<(), RasterError> {
+    let alpha = raster.alpha_band();
+    let fill: Vec<f64> = (0..raster.band_count())
+        .map(|band| {
+            let is_alpha = Some(band) == alpha;
+            let is_float = raster
+                .band_type(band)
+                .is_some_and(|t| t.format() == SampleFormat::Float);
+            match nodata {
+                _ if is_alpha => 0.0,
+                Some(nodata) => nodata,
+                None if is_float => f64::NAN,
+                None => 0.0,
+            }
+        })
+        .collect();
+
+    let width = raster.dimensions.0 as usize;
+    for (pixel, _) in mask.iter().enumerate().filter(|(_, inside)| !**inside) {
+        let (x, y) = ((pixel % width) as u32, (pixel / width) as u32);
+        for (band, value) in fill.iter().enumerate() {
+            raster.put_sample(x, y, band, *value)?;
+        }
+    }
+    Ok(())
+}
+
+/// WKT 解析
+mod wkt {
+    use super::{CutlineError, Polygon};
+
+    /// 嵌套的坐标列表
+    enum Nested {
+        /// 括号内的列表
+        List(Vec<Nested>),
+        /// 一个顶点的坐标
+        Point(Vec<f64>),
+    }
+
+    /// 解析 `POLYGON` 或 `MULTIPOLYGON`
+    pub(super) fn parse(wkt: &str) -> Result<Vec<Polygon>, CutlineError> {
+        let wkt = wkt.trim();
+        let split = wkt.find(['(', ' ']).unwrap_or(wkt.len());
+        let kind = wkt[..split].to_uppercase();
+        let mut rest = wkt[split..].trim_start();
+        // 跳过维度标识
+        for dimension in ["ZM", "Z", "M"] {
+            if rest.to_uppercase().starts_with(dimension) {
+                rest = rest[dimension.len()..].trim_start();
+                break;
+            }
+        }
+        if rest.to_uppercase() == "EMPTY" {
+            return Ok(vec![]);
+        }
+
+        let mut chars = rest.chars().peekable();
+        let nested = parse_nested(&mut chars)?;
+        if chars.any(|c| !c.is_whitespace()) {
+            return Err(invalid("多余的字符"));
+        }
+        match kind.as_str() {
+            "POLYGON" => Ok(vec![polygon(nested)?]),
+            "MULTIPOLYGON" => list(nested)?.into_iter().map(polygon).collect(),
+            _ => Err(invalid(&format!("不支持的几何类型 {kind}"))),
+        }
+    }
+
+    /// 解析括号内的列表
+    fn parse_nested(
+        chars: &mut std::iter::Peekable<std::str::Chars>,
+    ) -> Result<Nested, CutlineError> {
+        skip_whitespace(chars);
+        if chars.next() != Some('(') {
+            return Err(invalid("缺少左括号"));
+        }
+        let mut items = vec![];
+        loop {
+            skip_whitespace(chars);
+            match chars.peek() {
+                Some('(') => items.push(parse_nested(chars)?),
+                Some(_) => {
+                    // 读取到逗号或右括号为止的顶点坐标
+                    let mut text = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c == ',' || c == ')' {
+                            break;
+                        }
+                        text.push(c);
+                        chars.next();
+                    }
+                    let point = text
+                        .split_whitespace()
+                        .map(|n| n.parse().map_err(|_| invalid(&format!("无效的坐标 {n}"))))
+                        .collect::<Result<Vec<f64>, _>>()?;
+                    if point.len() < 2 {
+                        return Err(invalid(&format!("无效的顶点 {}", text.trim())));
+                    }
+                    items.push(Nested::Point(point));
+                }
+                None => return Err(invalid("缺少右括号")),
+            }
+            skip_whitespace(chars);
+            match chars.next() {
+                Some(',') => continue,
+                Some(')') => return Ok(Nested::List(items)),
+                _ => return Err(invalid("缺少逗号或右括号")),
+            }
+        }
+    }
+
+    /// 跳过空白字符
+    fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+    }
+
+    /// 取出列表的元素
+    fn list(nested: Nested) -> Result<Vec<Nested>, CutlineError> {
+        match nested {
+            Nested::List(items) => Ok(items),
+            Nested::Point(_) => Err(invalid("应为括号内的列表")),
+        }
+    }
+
+    /// 将嵌套列表转换为多边形
+    fn polygon(nested: Nested) -> Result<Polygon, CutlineError> {
+        list(nested)?
+            .into_iter()
+            .map(|ring| {
+                list(ring)?
+                    .into_iter()
+                    .map(|point| match point {
+                        Nested::Point(p) => Ok((p[0], p[1])),
+                        Nested::List(_) => Err(invalid("应为顶点坐标")),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// 创建 WKT 格式错误
+    fn invalid(message: &str) -> CutlineError {
+        CutlineError::InvalidWkt(message.to_string())
+    }
+}
+
+/// GeoJSON 解析
+#[cfg(feature = "json")]
+mod geojson {
+    use super::{CutlineError, Polygon};
+    use serde_json::Value;
+
+    /// 递归收集 GeoJSON 对象中的多边形
+    pub(super) fn collect_polygons(
+        value: &Value,
+        polygons: &mut Vec<Polygon>,
+    ) -> Result<(), CutlineError> {
+        match value["type"].as_str() {
+            Some("Polygon") => polygons.push(polygon(&value["coordinates"])?),
+            Some("MultiPolygon") => {
+                for coordinates in array(&value["coordinates"])? {
+                    polygons.push(polygon(coordinates)?);
+                }
+            }
+            Some("Feature") => {
+                if !value["geometry"].is_null() {
+                    collect_polygons(&value["geometry"], polygons)?;
+                }
+            }
+            Some("FeatureCollection") => {
+                for feature in array(&value["features"])? {
+                    collect_polygons(feature, polygons)?;
+                }
+            }
+            Some("GeometryCollection") => {
+                for geometry in array(&value["geometries"])? {
+                    collect_polygons(geometry, polygons)?;
+                }
+            }
+            Some(_) => {}
+            None => return Err(invalid("缺少 type 字段")),
+        }
+        Ok(())
+    }
+
+    /// 将坐标数组转换为多边形
+    fn polygon(coordinates: &Value) -> Result<Polygon, CutlineError> {
+        array(coordinates)?
+            .iter()
+            .map(|ring| {
+                array(ring)?
+                    .iter()
+                    .map(|point| match array(point)?.as_slice() {
+                        [x, y, ..] => match (x.as_f64(), y.as_f64()) {
+                            (Some(x), Some(y)) => Ok((x, y)),
+                            _ => Err(invalid("坐标不是数字")),
+                        },
+                        _ => Err(invalid("顶点至少需要两个坐标")),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// 获取数组
+    fn array(value: &Value) -> Result<&Vec<Value>, CutlineError> {
+        value.as_array().ok_or_else(|| invalid("应为数组"))
+    }
+
+    /// 创建 GeoJSON 格式错误
+    fn invalid(message: &str) -> CutlineError {
+        CutlineError::InvalidGeoJson(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 将掩膜转换为每行一个字符串,多边形内为 `#`,外为 `.`
+    fn rows(mask: &[bool], width: usize) -> Vec<String> {
+        mask.chunks(width)
+            .map(|row| {
+                row.iter()
+                    .map(|&inside| if inside { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// 轴对齐的矩形环
+    fn rect(left: f64, top: f64, right: f64, bottom: f64) -> Vec<(f64, f64)> {
+        vec![(left, top), (right, top), (right, bottom), (left, bottom)]
+    }
+
+    /// 解析失败时返回错误信息
+    fn wkt_error(wkt: &str) -> String {
+        match Cutline::from_wkt(wkt, 4326) {
+            Err(CutlineError::InvalidWkt(message)) => message,
+            other => panic!("{wkt} 应解析失败: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rasterize_rectangle() {
+        let mask = rasterize(&[rect(1.0, 1.0, 4.0, 3.0)], (5, 4));
+        assert_eq!(rows(&mask, 5), [".....", ".###.", ".###.", "....."]);
+    }
+
+    #[test]
+    fn rasterize_polygon_with_hole() {
+        let rings = [rect(0.0, 0.0, 5.0, 5.0), rect(1.5, 1.5, 3.5, 3.5)];
+        let mask = rasterize(&rings, (5, 5));
+        assert_eq!(
+            rows(&mask, 5),
+            ["#####", "#..##", "#..##", "#####", "#####"]
+        );
+    }
+
+    #[test]
+    fn rasterize_triangle_and_clipping() {
+        // 顶点位于像素中心所在的水平线上时只计数一次,超出网格的部分被裁掉
+        let triangle = vec![(-2.0, 0.5), (3.0, 0.5), (3.0, 5.5)];
+        let mask = rasterize(&[triangle], (4, 4));
+        assert_eq!(rows(&mask, 4), ["###.", "###.", "###.", ".##."]);
+    }
+
+    #[test]
+    fn mask_grid_with_hole() {
+        let cutline = Cutline::from_wkt(
+            "POLYGON ((0 0, 8 0, 8 8, 0 8, 0 0), (2 2, 6 2, 6 6, 2 6, 2 2))",
+            3857,
+        )
+        .unwrap();
+        // 每个输出像素 2 米见方,左上角位于 (0, 8)
+        let grid = GeoTransform::from_region(&Region::new(0.0, 0.0, 8.0, 8.0), (4, 4));
+        let mask = cutline.mask_grid(3857, &grid, (4, 4)).unwrap();
+        assert_eq!(rows(&mask, 4), ["####", "#..#", "#..#", "####"]);
+    }
+
+    #[test]
+    fn parse_ewkt_srid() {
+        let cutline =
+            Cutline::from_wkt("SRID=3857;POLYGON((0 0, 10 0, 10 10, 0 0))", 4326).unwrap();
+        assert_eq!(cutline.epsg(), 3857);
+        assert_eq!(
+            cutline.polygons(),
+            [vec![vec![
+                (0.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 10.0),
+                (0.0, 0.0)
+            ]]]
+        );
+        assert_eq!(cutline.bounds(), Region::new(0.0, 0.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn parse_multipolygon_with_z_and_holes() {
+        let cutline = Cutline::from_wkt(
+            "multipolygon Z (((0 0 1, 4 0 1, 4 4 1, 0 0 1), (1 1 1, 2 1 1, 2 2 1, 1 1 1)), \
+             ((10 10 0, 11 10 0, 11 11 0, 10 10 0)))",
+            4326,
+        )
+        .unwrap();
+        assert_eq!(cutline.epsg(), 4326);
+        let polygons = cutline.polygons();
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].len(), 2);
+        assert_eq!(polygons[0][1][1], (2.0, 1.0));
+        assert_eq!(
+            polygons[1],
+            [vec![(10.0, 10.0), (11.0, 10.0), (11.0, 11.0), (10.0, 10.0)]]
+        );
+    }
+
+    #[test]
+    fn parse_empty_polygon() {
+        let cutline = Cutline::from_wkt("POLYGON EMPTY", 4326).unwrap();
+        assert!(cutline.polygons().is_empty());
+    }
+
+    #[test]
+    fn parse_malformed_wkt() {
+        assert!(wkt_error("SRID=abc;POLYGON((0 0, 1 0, 1 1, 0 0))").contains("SRID"));
+        assert!(wkt_error("LINESTRING (0 0, 1 1)").contains("LINESTRING"));
+        assert!(wkt_error("POLYGON ((0 0, 1 0, 1 1, 0 0)").contains("右括号"));
+        assert!(wkt_error("POLYGON (0 0, 1 0, 1 1, 0 0)").contains("列表"));
+        assert!(wkt_error("POLYGON 0 0, 1 0").contains("左括号"));
+        assert!(wkt_error("POLYGON ((0 0, 1 x, 1 1, 0 0))").contains("x"));
+        assert!(wkt_error("POLYGON ((0 0, 1, 1 1, 0 0))").contains("顶点"));
+        assert!(wkt_error("POLYGON ((0 0, 1 0, 1 1, 0 0)) x").contains("多余"));
+    }
+}
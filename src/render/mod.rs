@@ -4,11 +4,17 @@
 //! - 同步和异步读取器的抽象
 //! - 渲染构建器用于配置渲染参数
 //! - 区域和分辨率控制
+//! - 重投影到任意像素网格
+//! - 按矢量多边形裁切
 
 use crate::cog::{CloudTiff, CloudTiffResult};
 use crate::io::ReadRange;
+use crate::projection::geo_transform::GeoTransform;
 use crate::projection::Projection;
+use crate::raster::{Colormap, Raster, Stretch, Terrain};
 use crate::{Region, UnitFloat};
+use cutline::Cutline;
+use resample::Resampling;
 use std::io::{Read, Seek};
 use std::sync::Mutex;
 
@@ -20,14 +26,22 @@ use {
     tokio::sync::Mutex as AsyncMutex,
 };
 
+pub mod capabilities;
+pub mod cutline;
+pub mod mosaic;
 pub mod renderer;
+pub mod resample;
 pub mod tiles;
+pub mod tms;
 pub mod util;
+pub mod warp;
+pub mod wmts;
 
 /// 表示需要读取器的占位符类型
 pub struct ReaderRequired;
 
 /// 同步读取器包装类型
+#[derive(Clone)]
 pub struct SyncReader(Arc<dyn ReadRange>);
 
 /// 异步读取器包装类型
@@ -50,6 +64,18 @@ pub struct RenderBuilder<'a, R> {
     pub region: RenderRegion,
     /// 输出分辨率
     pub resolution: (u32, u32),
+    /// 重采样方法
+    pub resampling: Resampling,
+    /// 对比度拉伸,设置后输出为每波段 8 位
+    pub stretch: Option<Stretch>,
+    /// 地形分析,设置后对渲染的高程进行山体阴影、坡度或坡向计算
+    pub terrain: Option<Terrain>,
+    /// 颜色映射,设置后输出为 RGBA 8 位
+    pub colormap: Option<Colormap>,
+    /// 近似变换的误差容限(源像素),None 时逐像素精确投影
+    pub transform_tolerance: Option<f64>,
+    /// 裁切多边形,设置后多边形外的像素为无数据值
+    pub cutline: Option<Cutline>,
 }
 
 /// 渲染区域类型
@@ -59,6 +85,8 @@ pub enum RenderRegion {
     InputCrop(Region<UnitFloat>),
     /// 输出区域,包含EPSG代码和实际坐标
     OutputRegion((u16, Region<f64>)),
+    /// 输出像素网格,包含EPSG代码和仿射变换(投影使用的单位,地理坐标系为弧度)
+    OutputGrid((u16, GeoTransform)),
 }
 
 impl CloudTiff {
@@ -70,8 +98,80 @@ impl CloudTiff {
             input_projection: self.projection.clone(),
             region: RenderRegion::InputCrop(Region::unit()),
             resolution: self.full_dimensions(),
+            resampling: Resampling::default(),
+            stretch: None,
+            terrain: None,
+            colormap: None,
+            transform_tolerance: None,
+            cutline: None,
         }
     }
+
+    /// 渲染 Web 墨卡托(EPSG:3857)下的 XYZ 瓦片
+    ///
+    /// 便捷方法,等价于 `self.renderer().with_arc_range_reader(reader).render_tile(z, x, y, size)`
+    ///
+    /// # 参数
+    /// * `reader` - 共享的范围读取器
+    /// * `z`, `x`, `y` - 瓦片的缩放级别和索引
+    /// * `size` - 瓦片边长(像素)
+    ///
+    /// # 返回
+    /// 瓦片栅格,瓦片不与影像相交时返回空白瓦片
+    ///
+    /// # 错误
+    /// 瓦片读取或投影转换失败时返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// # fn example(cog: &cloudtiff::CloudTiff) {
+    /// let reader = Arc::new(Mutex::new(File::open("image.tif").unwrap()));
+    /// let tile = cog.render_tile(reader, 12, 654, 1321, 256).unwrap();
+    /// # }
+    /// ```
+    pub fn render_tile<R: ReadRange + 'static>(
+        &self,
+        reader: Arc<R>,
+        z: u32,
+        x: u32,
+        y: u32,
+        size: u32,
+    ) -> CloudTiffResult<Raster> {
+        self.renderer()
+            .with_arc_range_reader(reader)
+            .render_tile(z, x, y, size)
+    }
+
+    /// 异步渲染 Web 墨卡托(EPSG:3857)下的 XYZ 瓦片
+    ///
+    /// # 参数
+    /// * `reader` - 共享的异步范围读取器
+    /// * `z`, `x`, `y` - 瓦片的缩放级别和索引
+    /// * `size` - 瓦片边长(像素)
+    ///
+    /// # 返回
+    /// 瓦片栅格,瓦片不与影像相交时返回空白瓦片
+    ///
+    /// # 错误
+    /// 瓦片读取或投影转换失败时返回错误
+    #[cfg(feature = "async")]
+    pub async fn render_tile_async<R: AsyncReadRange + 'static>(
+        &self,
+        reader: Arc<R>,
+        z: u32,
+        x: u32,
+        y: u32,
+        size: u32,
+    ) -> CloudTiffResult<Raster> {
+        self.renderer()
+            .with_async_arc_range_reader(reader)
+            .render_tile_async(z, x, y, size)
+            .await
+    }
 }
 
 impl<'a, S> RenderBuilder<'a, S> {
@@ -83,6 +183,12 @@ impl<'a, S> RenderBuilder<'a, S> {
             input_projection,
             region,
             resolution,
+            resampling,
+            stretch,
+            terrain,
+            colormap,
+            transform_tolerance,
+            cutline,
         } = self;
         RenderBuilder {
             cog,
@@ -90,6 +196,12 @@ impl<'a, S> RenderBuilder<'a, S> {
             input_projection,
             region,
             resolution,
+            resampling,
+            stretch,
+            terrain,
+            colormap,
+            transform_tolerance,
+            cutline,
         }
     }
 }
@@ -116,6 +228,14 @@ impl<'a> RenderBuilder<'a, ReaderRequired> {
         self.set_reader(SyncReader(Arc::new(reader)))
     }
 
+    /// 使用Arc包装的实现了ReadRange的读取器,便于在多个渲染之间共享
+    pub fn with_arc_range_reader<R: ReadRange + 'static>(
+        self,
+        reader: Arc<R>,
+    ) -> RenderBuilder<'a, SyncReader> {
+        self.set_reader(SyncReader(reader))
+    }
+
     /// 使用异步读取器
     #[cfg(feature = "async")]
     pub fn with_async_reader<R: AsyncRead + AsyncSeek + Send + Sync + Unpin + 'static>(
@@ -158,6 +278,63 @@ impl<'a, S> RenderBuilder<'a, S> {
         self
     }
 
+    /// 设置重采样方法,默认为最近邻
+    ///
+    /// 输出分辨率低于所选层级时,使用 [`Resampling::Average`] 按覆盖面积积分源像素以避免混叠
+    pub fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    /// 设置对比度拉伸,将渲染结果转换为每波段 8 位
+    ///
+    /// 拉伸参数通常先从概览图计算,再用于所有瓦片,使相邻瓦片的颜色保持一致
+    pub fn with_stretch(mut self, stretch: Stretch) -> Self {
+        self.stretch = Some(stretch);
+        self
+    }
+
+    /// 设置地形分析,对渲染的高程(第一个波段)计算山体阴影、坡度或坡向
+    ///
+    /// 渲染时会在输出区域四周多读取并渲染一个像素,使瓦片边缘的梯度使用相邻瓦片的数据,
+    /// 避免瓦片接缝。像素的地面尺寸根据输出区域自动换算为米。
+    /// 未设置无数据值时使用影像的无数据值,影像范围外的像素同样视为无数据。
+    /// 地形分析在对比度拉伸和颜色映射之前进行
+    pub fn with_terrain(mut self, terrain: Terrain) -> Self {
+        self.terrain = Some(terrain);
+        self
+    }
+
+    /// 设置颜色映射,将渲染结果的第一个波段转换为 RGBA 8 位
+    ///
+    /// 颜色映射在对比度拉伸之后应用,输出可以直接编码为 PNG 瓦片
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = Some(colormap);
+        self
+    }
+
+    /// 使用近似变换计算输出像素对应的源像素
+    ///
+    /// 只精确投影稀疏的控制点,其间的像素双线性插值;插值误差超过容限的区域自适应细分,
+    /// 直到误差满足要求或逐像素精确投影。可以大幅减少大尺寸输出的投影计算量
+    ///
+    /// # 参数
+    /// * `tolerance` - 允许的最大误差(源像素),GDAL 的默认值为 0.125,0 表示精确投影
+    pub fn with_approximate_transform(mut self, tolerance: f64) -> Self {
+        self.transform_tolerance = (tolerance > 0.0).then_some(tolerance);
+        self
+    }
+
+    /// 设置裁切多边形
+    ///
+    /// 像素中心位于多边形外的输出像素设为无数据值(存在 Alpha 波段时为透明),
+    /// 这些像素不会被采样,完全位于多边形外的瓦片不会被读取。
+    /// 裁切在地形分析之后、对比度拉伸和颜色映射之前进行
+    pub fn with_cutline(mut self, cutline: Cutline) -> Self {
+        self.cutline = Some(cutline);
+        self
+    }
+
     /// 设置输入裁剪区域
     pub fn of_crop(mut self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
         self.region = RenderRegion::InputCrop(Region::new_saturated(min_x, min_y, max_x, max_y));
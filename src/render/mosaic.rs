@@ -0,0 +1,731 @@
+//! 镶嵌模块
+//!
+//! 本模块提供将多个相邻或重叠的 COG(例如按图幅切分的 Sentinel-2 影像)合成为一幅影像的功能。
+//! 各数据源可以使用不同的坐标系:渲染时跳过边界与请求区域不相交的数据源,
+//! 其余数据源各自通过 [`RenderBuilder`] 重投影到同一输出网格,再按 [`MosaicMode`] 逐像素合成。
+//!
+//! 影像范围外的像素、无数据值像素和透明像素(Alpha 为 0)不参与合成,
+//! 没有任何有效数据源的输出像素填充为无数据值。
+//! 对比度拉伸和颜色映射在合成之后对整幅结果进行。
+//!
+//! # 示例
+//!
+//! ```no_run
+//! use cloudtiff::render::mosaic::{Mosaic, MosaicMode};
+//! use cloudtiff::CloudTiff;
+//! use std::fs::File;
+//! use std::sync::{Arc, Mutex};
+//!
+//! let mut mosaic = Mosaic::new().with_mode(MosaicMode::Mean);
+//! for path in ["T09UXA.tif", "T09UYA.tif"] {
+//!     let mut file = File::open(path).unwrap();
+//!     let cog = CloudTiff::open(&mut file).unwrap();
+//!     mosaic = mosaic.with_source(cog, Arc::new(Mutex::new(file)));
+//! }
+//! let tile = mosaic.render_tile(10, 163, 332, 256).unwrap();
+//! ```
+
+use super::cutline::Cutline;
+use super::renderer::WEB_MERCATOR_EPSG;
+use super::resample::Resampling;
+use super::tms::{TileMatrix, TileMatrixSet};
+use super::warp::GeoRaster;
+use super::{wmts, CloudTiffResult, RenderBuilder, SyncReader};
+use crate::cog::{CloudTiff, CloudTiffError};
+use crate::io::ReadRange;
+use crate::projection::geo_transform::GeoTransform;
+use crate::projection::ProjectionError;
+use crate::raster::{Colormap, Raster, Stretch};
+use crate::tiff::Endian;
+use crate::Region;
+use proj4rs::Proj;
+use std::sync::Arc;
+
+/// 重叠区域的合成规则
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MosaicMode {
+    /// 使用第一个有效的数据源(按添加顺序)
+    #[default]
+    First,
+    /// 使用最后一个有效的数据源(按添加顺序)
+    Last,
+    /// 逐波段取最小值
+    Min,
+    /// 逐波段取最大值
+    Max,
+    /// 逐波段取平均值
+    Mean,
+}
+
+/// 镶嵌的数据源
+struct MosaicSource<R> {
+    /// COG 影像
+    cog: CloudTiff,
+    /// 读取器
+    reader: R,
+}
+
+/// 多个 COG 的镶嵌
+///
+/// 输出的波段数和样本格式与第一个数据源相同,所有数据源的波段数必须一致
+pub struct Mosaic<R> {
+    /// 数据源,按添加顺序排列
+    sources: Vec<MosaicSource<R>>,
+    /// 重叠区域的合成规则
+    mode: MosaicMode,
+    /// 无数据值,未设置时使用各数据源的 GDAL_NODATA 标签
+    nodata: Option<f64>,
+    /// 重采样方法
+    resampling: Resampling,
+    /// 对比度拉伸
+    stretch: Option<Stretch>,
+    /// 颜色映射
+    colormap: Option<Colormap>,
+    /// 近似变换的误差容限(源像素)
+    transform_tolerance: Option<f64>,
+    /// 裁切多边形
+    cutline: Option<Cutline>,
+}
+
+impl<R> Mosaic<R> {
+    /// 创建没有数据源的镶嵌
+    fn empty() -> Self {
+        Self {
+            sources: vec![],
+            mode: MosaicMode::default(),
+            nodata: None,
+            resampling: Resampling::default(),
+            stretch: None,
+            colormap: None,
+            transform_tolerance: None,
+            cutline: None,
+        }
+    }
+
+    /// 设置重叠区域的合成规则,默认为 [`MosaicMode::First`]
+    pub fn with_mode(mut self, mode: MosaicMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 设置无数据值
+    ///
+    /// 同时作为所有数据源的输入无数据值和输出的填充值。
+    /// 未设置时输入使用各数据源的 GDAL_NODATA 标签,输出使用第一个数据源的标签,都没有时为 0
+    pub fn with_nodata(mut self, nodata: f64) -> Self {
+        self.nodata = Some(nodata);
+        self
+    }
+
+    /// 设置重采样方法,参见 [`RenderBuilder::with_resampling`]
+    pub fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    /// 设置对比度拉伸,在合成后进行,参见 [`RenderBuilder::with_stretch`]
+    pub fn with_stretch(mut self, stretch: Stretch) -> Self {
+        self.stretch = Some(stretch);
+        self
+    }
+
+    /// 设置颜色映射,在合成后进行,参见 [`RenderBuilder::with_colormap`]
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = Some(colormap);
+        self
+    }
+
+    /// 使用近似变换,参见 [`RenderBuilder::with_approximate_transform`]
+    pub fn with_approximate_transform(mut self, tolerance: f64) -> Self {
+        self.transform_tolerance = Some(tolerance);
+        self
+    }
+
+    /// 设置裁切多边形,多边形外的像素为无数据值,参见 [`RenderBuilder::with_cutline`]
+    pub fn with_cutline(mut self, cutline: Cutline) -> Self {
+        self.cutline = Some(cutline);
+        self
+    }
+
+    /// 数据源数量
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// 是否没有数据源
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// 按添加顺序遍历数据源的 COG 影像
+    pub fn cogs(&self) -> impl Iterator<Item = &CloudTiff> {
+        self.sources.iter().map(|source| &source.cog)
+    }
+}
+
+impl<R: Clone> Mosaic<R> {
+    /// 创建数据源的渲染构建器
+    fn builder<'a>(&self, source: &'a MosaicSource<R>) -> RenderBuilder<'a, R> {
+        let mut builder = source
+            .cog
+            .renderer()
+            .set_reader(source.reader.clone())
+            .with_resampling(self.resampling);
+        if let Some(tolerance) = self.transform_tolerance {
+            builder = builder.with_approximate_transform(tolerance);
+        }
+        builder
+    }
+
+    /// 计算输出像素网格的裁切多边形掩膜,所有数据源共用,未设置裁切多边形时返回 None
+    fn cutline_mask(
+        &self,
+        epsg: u16,
+        grid: &GeoTransform,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<Option<Vec<bool>>> {
+        let Some(cutline) = &self.cutline else {
+            return Ok(None);
+        };
+        Ok(Some(cutline.mask_grid(epsg, grid, dimensions)?))
+    }
+
+    /// 选出边界与输出网格相交的数据源
+    ///
+    /// # 参数
+    /// * `epsg` - 输出坐标系 EPSG 代码
+    /// * `grid` - 输出像素网格,使用投影的原生单位
+    /// * `dimensions` - 输出尺寸
+    fn overlapping(
+        &self,
+        epsg: u16,
+        grid: &GeoTransform,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<Vec<RenderBuilder<'_, R>>> {
+        let region = grid.bounds(dimensions);
+        let mut builders = vec![];
+        for source in &self.sources {
+            let builder = self.builder(source);
+            if builder.overlaps(epsg, &region)? {
+                builders.push(builder);
+            }
+        }
+        Ok(builders)
+    }
+
+    /// 创建合成器
+    fn composite(&self, dimensions: (u32, u32)) -> CloudTiffResult<Composite> {
+        let first = self
+            .sources
+            .first()
+            .ok_or_else(|| CloudTiffError::NotSupported("镶嵌没有数据源".into()))?;
+        let level = first.cog.get_level(0)?;
+        let template = Raster::blank(
+            dimensions,
+            level.bits_per_sample.clone(),
+            level.interpretation,
+            level.sample_format.clone(),
+            level.extra_samples.clone(),
+            Endian::native(),
+        );
+        let pixel_count = dimensions.0 as usize * dimensions.1 as usize;
+        Ok(Composite {
+            mode: self.mode,
+            fill: self.output_nodata().unwrap_or(0.0),
+            values: vec![0.0; pixel_count * template.band_count()],
+            counts: vec![0; pixel_count],
+            template,
+        })
+    }
+
+    /// 将一个数据源的渲染结果加入合成器
+    ///
+    /// 不与影像相交的数据源会返回区域越界错误,视为没有覆盖任何像素
+    fn add(
+        &self,
+        composite: &mut Composite,
+        builder: &RenderBuilder<'_, R>,
+        rendered: CloudTiffResult<(Raster, Vec<bool>)>,
+    ) -> CloudTiffResult<()> {
+        match rendered {
+            Ok((raster, mask)) => {
+                let nodata = self
+                    .nodata
+                    .or(builder.cog.levels.first().and_then(|l| l.nodata));
+                composite.add(&raster, &mask, nodata)
+            }
+            Err(CloudTiffError::RegionOutOfBounds(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 合成结果的无数据值
+    ///
+    /// 未设置时使用第一个数据源的无数据值
+    fn output_nodata(&self) -> Option<f64> {
+        self.nodata.or_else(|| {
+            self.sources
+                .first()
+                .and_then(|source| source.cog.levels.first())
+                .and_then(|level| level.nodata)
+        })
+    }
+
+    /// 生成合成结果并进行对比度拉伸和颜色映射
+    ///
+    /// 拉伸参数未指定无数据值时使用合成结果的无数据值
+    fn finish(&self, composite: Composite) -> CloudTiffResult<Raster> {
+        let (mut raster, covered) = composite.finish()?;
+        if self.stretch.is_none() && self.colormap.is_none() {
+            return Ok(raster);
+        }
+        // 没有数据源覆盖和无数据值的像素在拉伸和颜色映射后为透明
+        let mut valid = raster.valid_pixels(self.output_nodata())?;
+        for (valid, covered) in valid.iter_mut().zip(&covered) {
+            *valid &= *covered;
+        }
+        if let Some(stretch) = &self.stretch {
+            let stretch = Stretch {
+                nodata: stretch.nodata.or(self.output_nodata()),
+                ..stretch.clone()
+            };
+            raster = raster.stretch_to_u8_masked(&stretch, Some(&valid))?;
+        }
+        if let Some(colormap) = &self.colormap {
+            raster = raster.apply_colormap_masked(colormap, Some(&valid))?;
+        }
+        Ok(raster)
+    }
+}
+
+impl Mosaic<SyncReader> {
+    /// 创建使用同步读取器的空镶嵌
+    pub fn new() -> Self {
+        Self::empty()
+    }
+
+    /// 添加数据源
+    ///
+    /// # 参数
+    /// * `cog` - COG 影像
+    /// * `reader` - 共享的范围读取器
+    pub fn with_source<R: ReadRange + 'static>(mut self, cog: CloudTiff, reader: Arc<R>) -> Self {
+        self.add_source(cog, reader);
+        self
+    }
+
+    /// 添加数据源
+    ///
+    /// # 参数
+    /// * `cog` - COG 影像
+    /// * `reader` - 共享的范围读取器
+    pub fn add_source<R: ReadRange + 'static>(&mut self, cog: CloudTiff, reader: Arc<R>) {
+        self.sources.push(MosaicSource {
+            cog,
+            reader: SyncReader(reader),
+        });
+    }
+
+    /// 渲染输出区域
+    ///
+    /// # 参数
+    /// * `epsg` - 输出坐标系 EPSG 代码
+    /// * `region` - 输出区域,地理坐标系使用度
+    /// * `dimensions` - 输出尺寸 (宽度, 高度)
+    ///
+    /// # 错误
+    /// 镶嵌没有数据源、数据源波段数不一致、瓦片读取或投影转换失败时返回错误
+    pub fn render_region(
+        &self,
+        epsg: u16,
+        region: Region<f64>,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<Raster> {
+        let grid = GeoTransform::from_region(&region, dimensions);
+        self.render_grid(epsg, &grid, dimensions)
+    }
+
+    /// 将所有数据源重投影到任意像素网格并合成
+    ///
+    /// # 参数
+    /// * `epsg` - 目标坐标系 EPSG 代码
+    /// * `geo_transform` - 目标像素网格的仿射变换,地理坐标系使用度
+    /// * `dimensions` - 输出尺寸 (宽度, 高度)
+    ///
+    /// # 错误
+    /// 与 [`render_region`](Self::render_region) 相同
+    pub fn render_warp(
+        &self,
+        epsg: u16,
+        geo_transform: GeoTransform,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<GeoRaster> {
+        let raster = self.render_grid(epsg, &geo_transform, dimensions)?;
+        Ok(GeoRaster {
+            raster,
+            epsg,
+            geo_transform,
+        })
+    }
+
+    /// 渲染 Web 墨卡托(EPSG:3857)下的 XYZ 瓦片
+    ///
+    /// 瓦片索引无效或瓦片不与任何数据源相交时返回填充无数据值的瓦片
+    ///
+    /// # 参数
+    /// * `z` - 缩放级别
+    /// * `x`, `y` - 瓦片索引,原点位于左上角
+    /// * `size` - 瓦片边长(像素)
+    pub fn render_tile(&self, z: u32, x: u32, y: u32, size: u32) -> CloudTiffResult<Raster> {
+        let tile = wmts::tile_bounds_web_mercator(x, y, z);
+        self.render_tile_bounds(WEB_MERCATOR_EPSG, tile, (size, size))
+    }
+
+    /// 渲染任意瓦片矩阵集中的瓦片
+    ///
+    /// # 参数
+    /// * `tms` - 瓦片矩阵集
+    /// * `z` - 缩放级别
+    /// * `x`, `y` - 瓦片列号和行号
+    ///
+    /// # 错误
+    /// 缩放级别不存在、瓦片读取或投影转换失败时返回错误
+    pub fn render_tms_tile(
+        &self,
+        tms: &TileMatrixSet,
+        z: u32,
+        x: u32,
+        y: u32,
+    ) -> CloudTiffResult<Raster> {
+        let matrix = tile_matrix(tms, z)?;
+        let dimensions = (matrix.tile_width, matrix.tile_height);
+        let tile = matrix.tile_bounds(x, y);
+        self.render_tile_bounds(tms.epsg, tile, dimensions)
+    }
+
+    /// 渲染瓦片边界,瓦片无效时返回填充无数据值的瓦片
+    fn render_tile_bounds(
+        &self,
+        epsg: u16,
+        tile: Option<Region<f64>>,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<Raster> {
+        match tile {
+            Some(region) => self.render_region(epsg, region, dimensions),
+            None => self.finish(self.composite(dimensions)?),
+        }
+    }
+
+    /// 渲染并合成输出像素网格
+    fn render_grid(
+        &self,
+        epsg: u16,
+        geo_transform: &GeoTransform,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<Raster> {
+        let grid = native_grid(epsg, geo_transform)?;
+        let mask = self.cutline_mask(epsg, &grid, dimensions)?;
+        let mut composite = self.composite(dimensions)?;
+        for builder in self.overlapping(epsg, &grid, dimensions)? {
+            // 按顺序取第一个有效值时,所有像素都有值后不再需要读取其余数据源
+            if self.mode == MosaicMode::First && composite.is_full() {
+                break;
+            }
+            let rendered = builder.render_grid(epsg, &grid, dimensions, mask.as_deref());
+            self.add(&mut composite, &builder, rendered)?;
+        }
+        self.finish(composite)
+    }
+}
+
+impl Default for Mosaic<SyncReader> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 获取瓦片矩阵集中的瓦片矩阵
+fn tile_matrix(tms: &TileMatrixSet, z: u32) -> CloudTiffResult<&TileMatrix> {
+    tms.tile_matrix(z)
+        .ok_or(CloudTiffError::TileLevelOutOfRange((
+            z as usize,
+            tms.max_zoom() as usize,
+        )))
+}
+
+/// 将输出像素网格转换为投影使用的单位,地理坐标系从度转换为弧度
+fn native_grid(epsg: u16, geo_transform: &GeoTransform) -> CloudTiffResult<GeoTransform> {
+    let proj = Proj::from_epsg_code(epsg).map_err(ProjectionError::from)?;
+    Ok(if proj.is_latlong() {
+        geo_transform.scaled(1_f64.to_radians())
+    } else {
+        *geo_transform
+    })
+}
+
+/// 逐像素合成多个数据源的渲染结果
+struct Composite {
+    /// 合成规则
+    mode: MosaicMode,
+    /// 没有有效数据的像素的填充值
+    fill: f64,
+    /// 输出格式的空白栅格
+    template: Raster,
+    /// 每个像素每个波段的合成值,平均值模式下为累加值
+    values: Vec<f64>,
+    /// 每个像素的有效数据源数
+    counts: Vec<u32>,
+}
+
+impl Composite {
+    /// 是否所有像素都已有有效数据
+    fn is_full(&self) -> bool {
+        self.counts.iter().all(|count| *count > 0)
+    }
+
+    /// 加入一个数据源的渲染结果
+    ///
+    /// # 参数
+    /// * `raster` - 数据源在输出网格上的渲染结果
+    /// * `mask` - 影像覆盖的输出像素
+    /// * `nodata` - 数据源的无数据值
+    ///
+    /// # 错误
+    /// 波段数与输出不一致或样本类型不受支持时返回错误
+    fn add(&mut self, raster: &Raster, mask: &[bool], nodata: Option<f64>) -> CloudTiffResult<()> {
+        let band_count = self.template.band_count();
+        if raster.band_count() != band_count {
+            return Err(CloudTiffError::NotSupported(format!(
+                "镶嵌数据源的波段数不一致: {} 和 {}",
+                band_count,
+                raster.band_count()
+            )));
+        }
+        let bands = (0..band_count)
+            .map(|band| Ok(raster.band::<f64>(band)?.collect::<Vec<_>>()))
+            .collect::<CloudTiffResult<Vec<_>>>()?;
+        let alpha = raster.alpha_band();
+        let is_nodata = |value: f64| value.is_nan() || nodata == Some(value);
+
+        let mut samples = vec![0.0; band_count];
+        for (pixel, _) in mask.iter().enumerate().filter(|(_, covered)| **covered) {
+            for (sample, band) in samples.iter_mut().zip(&bands) {
+                *sample = band[pixel];
+            }
+            // 透明像素和所有波段都为无数据值的像素无效
+            let transparent = alpha.is_some_and(|alpha| samples[alpha] == 0.0);
+            let empty = samples
+                .iter()
+                .enumerate()
+                .filter(|(band, _)| Some(*band) != alpha)
+                .all(|(_, value)| is_nodata(*value));
+            if transparent || empty {
+                continue;
+            }
+
+            let first = self.counts[pixel] == 0;
+            let values = &mut self.values[pixel * band_count..(pixel + 1) * band_count];
+            for (value, sample) in values.iter_mut().zip(&samples) {
+                *value = match self.mode {
+                    _ if first => *sample,
+                    MosaicMode::First => *value,
+                    MosaicMode::Last => *sample,
+                    MosaicMode::Min => value.min(*sample),
+                    MosaicMode::Max => value.max(*sample),
+                    MosaicMode::Mean => *value + sample,
+                };
+            }
+            self.counts[pixel] += 1;
+        }
+        Ok(())
+    }
+
+    /// 生成合成后的栅格,同时返回被至少一个数据源覆盖的像素掩膜
+    fn finish(self) -> CloudTiffResult<(Raster, Vec<bool>)> {
+        let Self {
+            mode,
+            fill,
+            mut template,
+            values,
+            counts,
+        } = self;
+        let width = template.dimensions.0 as usize;
+        let band_count = template.band_count();
+        let alpha = template.alpha_band();
+        let covered = counts.iter().map(|count| *count > 0).collect();
+        for (pixel, count) in counts.into_iter().enumerate() {
+            let (x, y) = ((pixel % width) as u32, (pixel / width) as u32);
+            for band in 0..band_count {
+                let value = match count {
+                    // 没有有效数据的像素填充无数据值,Alpha 波段为透明
+                    0 if Some(band) == alpha => 0.0,
+                    0 => fill,
+                    _ if mode == MosaicMode::Mean => {
+                        values[pixel * band_count + band] / count as f64
+                    }
+                    _ => values[pixel * band_count + band],
+                };
+                if value != 0.0 {
+                    template.put_sample(x, y, band, value)?;
+                }
+            }
+        }
+        Ok((template, covered))
+    }
+}
+
+#[cfg(feature = "async")]
+mod not_sync {
+    use super::super::AsyncReader;
+    use super::*;
+    use crate::io::AsyncReadRange;
+
+    impl Mosaic<AsyncReader> {
+        /// 创建使用异步读取器的空镶嵌
+        pub fn new_async() -> Self {
+            Self::empty()
+        }
+
+        /// 添加使用异步读取器的数据源
+        ///
+        /// # 参数
+        /// * `cog` - COG 影像
+        /// * `reader` - 共享的异步范围读取器
+        pub fn with_async_source<R: AsyncReadRange + 'static>(
+            mut self,
+            cog: CloudTiff,
+            reader: Arc<R>,
+        ) -> Self {
+            self.add_async_source(cog, reader);
+            self
+        }
+
+        /// 添加使用异步读取器的数据源
+        ///
+        /// # 参数
+        /// * `cog` - COG 影像
+        /// * `reader` - 共享的异步范围读取器
+        pub fn add_async_source<R: AsyncReadRange + 'static>(
+            &mut self,
+            cog: CloudTiff,
+            reader: Arc<R>,
+        ) {
+            self.sources.push(MosaicSource {
+                cog,
+                reader: AsyncReader(reader),
+            });
+        }
+
+        /// 异步渲染输出区域
+        ///
+        /// 与 [`Mosaic::render_region`] 相同,但使用异步IO操作并同时读取所有相交的数据源
+        ///
+        /// # 参数
+        /// * `epsg` - 输出坐标系 EPSG 代码
+        /// * `region` - 输出区域,地理坐标系使用度
+        /// * `dimensions` - 输出尺寸 (宽度, 高度)
+        pub async fn render_region_async(
+            &self,
+            epsg: u16,
+            region: Region<f64>,
+            dimensions: (u32, u32),
+        ) -> CloudTiffResult<Raster> {
+            let grid = GeoTransform::from_region(&region, dimensions);
+            self.render_grid_async(epsg, &grid, dimensions).await
+        }
+
+        /// 异步将所有数据源重投影到任意像素网格并合成
+        ///
+        /// # 参数
+        /// * `epsg` - 目标坐标系 EPSG 代码
+        /// * `geo_transform` - 目标像素网格的仿射变换,地理坐标系使用度
+        /// * `dimensions` - 输出尺寸 (宽度, 高度)
+        pub async fn render_warp_async(
+            &self,
+            epsg: u16,
+            geo_transform: GeoTransform,
+            dimensions: (u32, u32),
+        ) -> CloudTiffResult<GeoRaster> {
+            let raster = self
+                .render_grid_async(epsg, &geo_transform, dimensions)
+                .await?;
+            Ok(GeoRaster {
+                raster,
+                epsg,
+                geo_transform,
+            })
+        }
+
+        /// 异步渲染 Web 墨卡托(EPSG:3857)下的 XYZ 瓦片
+        ///
+        /// # 参数
+        /// * `z` - 缩放级别
+        /// * `x`, `y` - 瓦片索引,原点位于左上角
+        /// * `size` - 瓦片边长(像素)
+        pub async fn render_tile_async(
+            &self,
+            z: u32,
+            x: u32,
+            y: u32,
+            size: u32,
+        ) -> CloudTiffResult<Raster> {
+            let tile = wmts::tile_bounds_web_mercator(x, y, z);
+            self.render_tile_bounds_async(WEB_MERCATOR_EPSG, tile, (size, size))
+                .await
+        }
+
+        /// 异步渲染任意瓦片矩阵集中的瓦片
+        ///
+        /// # 参数
+        /// * `tms` - 瓦片矩阵集
+        /// * `z` - 缩放级别
+        /// * `x`, `y` - 瓦片列号和行号
+        pub async fn render_tms_tile_async(
+            &self,
+            tms: &TileMatrixSet,
+            z: u32,
+            x: u32,
+            y: u32,
+        ) -> CloudTiffResult<Raster> {
+            let matrix = tile_matrix(tms, z)?;
+            let dimensions = (matrix.tile_width, matrix.tile_height);
+            let tile = matrix.tile_bounds(x, y);
+            self.render_tile_bounds_async(tms.epsg, tile, dimensions)
+                .await
+        }
+
+        /// 异步渲染瓦片边界,瓦片无效时返回填充无数据值的瓦片
+        async fn render_tile_bounds_async(
+            &self,
+            epsg: u16,
+            tile: Option<Region<f64>>,
+            dimensions: (u32, u32),
+        ) -> CloudTiffResult<Raster> {
+            match tile {
+                Some(region) => self.render_region_async(epsg, region, dimensions).await,
+                None => self.finish(self.composite(dimensions)?),
+            }
+        }
+
+        /// 异步渲染并合成输出像素网格
+        async fn render_grid_async(
+            &self,
+            epsg: u16,
+            geo_transform: &GeoTransform,
+            dimensions: (u32, u32),
+        ) -> CloudTiffResult<Raster> {
+            let grid = native_grid(epsg, geo_transform)?;
+            let mask = self.cutline_mask(epsg, &grid, dimensions)?;
+            let mut composite = self.composite(dimensions)?;
+            let builders = self.overlapping(epsg, &grid, dimensions)?;
+            let rendered = futures::future::join_all(builders.iter().map(|builder| {
+                builder.render_grid_async(epsg, &grid, dimensions, mask.as_deref())
+            }))
+            .await;
+            for (builder, rendered) in builders.iter().zip(rendered) {
+                self.add(&mut composite, builder, rendered)?;
+            }
+            self.finish(composite)
+        }
+    }
+}
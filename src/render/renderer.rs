@@ -3,58 +3,504 @@
 //! 本模块提供了对云优化地理影像(COG)进行渲染的核心功能实现。
 //! 包括同步和异步渲染、图像裁剪和投影转换等功能。
 
+use super::cutline;
+use super::resample::{self, LevelSampler, Resampling};
+use super::tms::TileMatrixSet;
 use super::CloudTiffResult;
-use super::{tiles, util};
+use super::{tiles, util, wmts};
 use super::{RenderBuilder, RenderRegion, SyncReader};
-use crate::cog::Level;
-use crate::raster::Raster;
+use crate::cog::{CloudTiffError, Level};
+use crate::projection::geo_transform::GeoTransform;
+use crate::projection::{self, ProjectionError};
+use crate::raster::{
+    PhotometricInterpretation, Raster, SampleFormat, Stretch, Terrain, TerrainKind,
+};
+use crate::tiff::Endian;
 use crate::{Region, UnitFloat};
-use std::collections::HashMap;
+use proj4rs::Proj;
+use std::collections::{BTreeSet, HashMap};
 
 impl<'a> RenderBuilder<'a, SyncReader> {
     /// 执行同步渲染操作
     ///
     /// 根据配置的渲染区域类型(输入裁剪或输出区域)执行相应的渲染逻辑
     pub fn render(&self) -> CloudTiffResult<Raster> {
-        let dimensions = self.resolution;
-        match self.region {
+        let mask = self.cutline_mask()?;
+        // 输出完全位于裁切多边形外时不读取任何瓦片
+        if mask.as_ref().is_some_and(|mask| !mask.contains(&true)) {
+            return self.empty_tile();
+        }
+        let (raster, footprint) = match &self.terrain {
+            // 地形分析需要在四周多渲染一个像素,并且需要裁切多边形外相邻像素的高程,
+            // 因此不使用掩膜跳过采样,渲染结果在地形分析之后再裁切
+            Some(terrain) => {
+                let halo = self.halo()?;
+                let (raster, footprint) =
+                    self.render_region(&halo.region, halo.dimensions, None)?;
+                let terrain = self.terrain_with_nodata(terrain);
+                let raster = halo.apply(raster, &footprint, &terrain)?;
+                (raster, halo.window_mask(&footprint))
+            }
+            None => self.render_region(&self.region, self.resolution, mask.as_deref())?,
+        };
+        self.finish(raster, &footprint, mask.as_deref())
+    }
+
+    /// 渲染 Web 墨卡托(EPSG:3857)下的 XYZ 瓦片
+    ///
+    /// 输出的行按墨卡托投影等距分布,可以直接作为地图瓦片使用。
+    /// 瓦片索引无效或瓦片不与影像相交时返回空白(无数据)瓦片,
+    /// 空白瓦片同样经过地形分析、对比度拉伸和颜色映射,与其他瓦片格式一致
+    ///
+    /// # 参数
+    /// * `z` - 缩放级别
+    /// * `x`, `y` - 瓦片索引,原点位于左上角
+    /// * `size` - 瓦片边长(像素)
+    pub fn render_tile(self, z: u32, x: u32, y: u32, size: u32) -> CloudTiffResult<Raster> {
+        let tile = wmts::tile_bounds_web_mercator(x, y, z);
+        self.with_exact_resolution((size, size))
+            .render_tile_bounds(WEB_MERCATOR_EPSG, tile)
+    }
+
+    /// 渲染任意瓦片矩阵集中的瓦片
+    ///
+    /// 输出尺寸为瓦片矩阵的瓦片尺寸。瓦片索引无效或瓦片不与影像相交时返回空白(无数据)瓦片
+    ///
+    /// # 参数
+    /// * `tms` - 瓦片矩阵集
+    /// * `z` - 缩放级别
+    /// * `x`, `y` - 瓦片列号和行号
+    ///
+    /// # 错误
+    /// 缩放级别不存在、瓦片读取或投影转换失败时返回错误
+    pub fn render_tms_tile(
+        self,
+        tms: &TileMatrixSet,
+        z: u32,
+        x: u32,
+        y: u32,
+    ) -> CloudTiffResult<Raster> {
+        let matrix = tms
+            .tile_matrix(z)
+            .ok_or(CloudTiffError::TileLevelOutOfRange((
+                z as usize,
+                tms.max_zoom() as usize,
+            )))?;
+        self.with_exact_resolution((matrix.tile_width, matrix.tile_height))
+            .render_tile_bounds(tms.epsg, matrix.tile_bounds(x, y))
+    }
+
+    /// 渲染瓦片边界,瓦片无效或不与影像相交时返回空白瓦片
+    fn render_tile_bounds(self, epsg: u16, tile: Option<Region<f64>>) -> CloudTiffResult<Raster> {
+        let Some(region) = self.tile_region(epsg, tile)? else {
+            return self.empty_tile();
+        };
+        let builder = RenderBuilder { region, ..self };
+        match builder.render() {
+            Err(CloudTiffError::RegionOutOfBounds(_)) => builder.empty_tile(),
+            result => result,
+        }
+    }
+
+    /// 以指定的尺寸渲染区域,同时返回影像覆盖的输出像素掩膜
+    ///
+    /// `mask` 为 false 的输出像素不需要渲染,只被这些像素使用的瓦片不会被读取
+    fn render_region(
+        &self,
+        region: &RenderRegion,
+        dimensions: (u32, u32),
+        mask: Option<&[bool]>,
+    ) -> CloudTiffResult<(Raster, Vec<bool>)> {
+        let rendered = match *region {
             // 处理输入裁剪模式
             RenderRegion::InputCrop(crop) => {
                 // 确定合适的渲染层级
                 let level = util::render_level_from_crop(self.cog, &crop, &dimensions);
-                // 获取裁剪区域内的瓦片索引
-                let indices = level.tile_indices_within_image_crop(crop);
+                // 获取裁剪区域内(包括插值核所需的相邻)瓦片索引
+                let indices = crop_tile_indices(level, &crop, dimensions, self.resampling, mask);
                 // 读取所需瓦片数据
-                let tile_cache = tiles::get_tiles(&self.reader, level, indices);
-                // 渲染裁剪后的图像
-                Ok(render_image_crop_from_tile_cache(
+                let tile_cache = tiles::get_tiles(&self.reader, level, indices)?;
+                // 渲染裁剪后的图像,输入裁剪区域总是位于影像内
+                let raster = render_image_crop_from_tile_cache(
                     &tile_cache,
                     level,
                     &crop,
                     &dimensions,
-                ))
+                    self.resampling,
+                );
+                (raster, full_coverage(&dimensions))
             }
             // 处理输出区域模式(需要投影转换)
             RenderRegion::OutputRegion((epsg, region)) => {
-                // 确定合适的渲染层级
-                let level = util::render_level_from_region(self.cog, epsg, &region, &dimensions)?;
-                // 计算像素映射关系
-                let pixel_map = util::project_pixel_map(
-                    level,
-                    &self.input_projection,
-                    epsg,
-                    &region,
-                    &dimensions,
+                let grid = GeoTransform::from_region(&region, dimensions);
+                self.render_grid(epsg, &grid, dimensions, mask)?
+            }
+            // 处理输出像素网格模式(需要投影转换)
+            RenderRegion::OutputGrid((epsg, grid)) => {
+                self.render_grid(epsg, &grid, dimensions, mask)?
+            }
+        };
+        Ok(rendered)
+    }
+
+    /// 渲染输出像素网格,同时返回影像覆盖的输出像素掩膜
+    ///
+    /// 不进行地形分析和后处理,掩膜用于区分影像外的像素和值为零的像素。
+    /// `mask` 为 false 的输出像素不采样
+    pub(super) fn render_grid(
+        &self,
+        epsg: u16,
+        grid: &GeoTransform,
+        dimensions: (u32, u32),
+        mask: Option<&[bool]>,
+    ) -> CloudTiffResult<(Raster, Vec<bool>)> {
+        let (level, points, indices) = self.project_grid(epsg, grid, dimensions, mask)?;
+        // 读取瓦片数据
+        let tile_cache = tiles::get_tiles(&self.reader, level, indices)?;
+        // 根据采样点渲染图像
+        let raster =
+            render_sample_points(&points, level, &tile_cache, &dimensions, self.resampling)?;
+        Ok((raster, coverage(&points, &dimensions)))
+    }
+}
+
+/// 带有一个像素外边的渲染区域
+///
+/// 地形分析的 3x3 窗口在输出边缘需要相邻像素,
+/// 因此在四周各多渲染一个像素(图像边界处除外),计算后再裁剪回原尺寸
+struct Halo {
+    /// 扩展后的渲染区域
+    region: RenderRegion,
+    /// 扩展后的输出尺寸
+    dimensions: (u32, u32),
+    /// 原输出区域在扩展后栅格中的位置
+    window: Region<u32>,
+    /// 输出像素的地面尺寸(米)
+    pixel_size: (f64, f64),
+}
+
+impl Halo {
+    /// 对扩展后的渲染结果进行地形分析并裁剪回原尺寸
+    ///
+    /// 影像范围外的像素先设为无数据值,不参与梯度计算
+    fn apply(
+        &self,
+        mut raster: Raster,
+        footprint: &[bool],
+        terrain: &Terrain,
+    ) -> CloudTiffResult<Raster> {
+        cutline::apply_mask(&mut raster, footprint, terrain.nodata)?;
+        let result = raster.terrain(terrain, self.pixel_size)?;
+        Ok(result.get_region(self.window)?)
+    }
+
+    /// 将扩展后的输出像素掩膜裁剪回原尺寸
+    fn window_mask(&self, mask: &[bool]) -> Vec<bool> {
+        let width = self.dimensions.0 as usize;
+        let window = self.window;
+        (window.y.min..window.y.max)
+            .flat_map(|j| (window.x.min..window.x.max).map(move |i| (i, j)))
+            .map(|(i, j)| mask[j as usize * width + i as usize])
+            .collect()
+    }
+}
+
+/// Web 墨卡托投影的 EPSG 代码
+pub(super) const WEB_MERCATOR_EPSG: u16 = 3857;
+
+impl<'a, R> RenderBuilder<'a, R> {
+    /// 计算输出像素网格的渲染层级、采样点和需要读取的瓦片索引
+    ///
+    /// `mask` 为 false 的输出像素(例如裁切多边形外)不采样,只被这些像素使用的瓦片不会被读取
+    fn project_grid(
+        &self,
+        epsg: u16,
+        grid: &GeoTransform,
+        dimensions: (u32, u32),
+        mask: Option<&[bool]>,
+    ) -> CloudTiffResult<(&'a Level, Vec<SamplePoint>, Vec<usize>)> {
+        // 确定合适的渲染层级
+        let region = grid.bounds(dimensions);
+        let level = util::render_level_from_region(self.cog, epsg, &region, &dimensions)?;
+        // 计算像素映射关系
+        let pixel_map = util::project_pixel_map_grid(
+            level,
+            &self.input_projection,
+            epsg,
+            grid,
+            &dimensions,
+            self.transform_tolerance,
+        )?;
+        // 计算采样点和需要的瓦片索引
+        let mut points = sample_points(&pixel_map, level, &dimensions);
+        if let Some(mask) = mask {
+            let width = dimensions.0 as usize;
+            points.retain(|((i, j), ..)| mask[*j as usize * width + *i as usize]);
+        }
+        let indices = point_tile_indices(&points, level, self.resampling);
+        Ok((level, points, indices))
+    }
+
+    /// 计算输出的裁切多边形掩膜,未设置裁切多边形时返回 None
+    fn cutline_mask(&self) -> CloudTiffResult<Option<Vec<bool>>> {
+        let Some(cutline) = &self.cutline else {
+            return Ok(None);
+        };
+        let dimensions = self.resolution;
+        let mask = match self.region {
+            RenderRegion::InputCrop(crop) => {
+                cutline.mask_crop(&self.input_projection, &crop, dimensions)
+            }
+            RenderRegion::OutputRegion((epsg, region)) => {
+                let grid = GeoTransform::from_region(&region, dimensions);
+                cutline.mask_grid(epsg, &grid, dimensions)?
+            }
+            RenderRegion::OutputGrid((epsg, grid)) => cutline.mask_grid(epsg, &grid, dimensions)?,
+        };
+        Ok(Some(mask))
+    }
+
+    /// 影像的无数据值
+    fn cog_nodata(&self) -> Option<f64> {
+        self.cog.levels.first().and_then(|level| level.nodata)
+    }
+
+    /// 地形分析参数,未指定无数据值时使用影像的无数据值
+    fn terrain_with_nodata(&self, terrain: &Terrain) -> Terrain {
+        Terrain {
+            nodata: terrain.nodata.or(self.cog_nodata()),
+            ..*terrain
+        }
+    }
+
+    /// 渲染结果的无数据值
+    ///
+    /// 不使用地形分析时为影像的无数据值;山体阴影的无数据值为 0,坡度和坡向使用 NaN
+    fn output_nodata(&self) -> Option<f64> {
+        match self.terrain {
+            Some(Terrain {
+                kind: TerrainKind::Hillshade { .. },
+                ..
+            }) => Some(0.0),
+            Some(_) => None,
+            None => self.cog_nodata(),
+        }
+    }
+
+    /// 将影像范围外和裁切多边形外的像素设为无数据值,Alpha 波段为透明
+    ///
+    /// 使用渲染结果的无数据值,未指定时浮点结果为 NaN、整数结果为 0
+    ///
+    /// # 返回
+    /// 裁切后的栅格和位于影像范围及裁切多边形内的像素掩膜
+    fn clip(
+        &self,
+        mut raster: Raster,
+        footprint: &[bool],
+        mask: Option<&[bool]>,
+    ) -> CloudTiffResult<(Raster, Vec<bool>)> {
+        let inside: Vec<bool> = match mask {
+            Some(mask) => footprint.iter().zip(mask).map(|(a, b)| *a && *b).collect(),
+            None => footprint.to_vec(),
+        };
+        if inside.contains(&false) {
+            cutline::apply_mask(&mut raster, &inside, self.output_nodata())?;
+        }
+        Ok((raster, inside))
+    }
+
+    /// 计算瓦片的渲染区域
+    ///
+    /// 瓦片边界使用坐标系的原生单位,地理坐标系会从度转换为投影使用的弧度。
+    /// 瓦片无效或与影像边界不相交时返回 None
+    fn tile_region(
+        &self,
+        epsg: u16,
+        tile: Option<Region<f64>>,
+    ) -> CloudTiffResult<Option<RenderRegion>> {
+        let Some(mut tile) = tile else {
+            return Ok(None);
+        };
+        let proj = Proj::from_epsg_code(epsg).map_err(ProjectionError::from)?;
+        if proj.is_latlong() {
+            tile = tile * 1_f64.to_radians();
+        }
+        Ok(self
+            .overlaps(epsg, &tile)?
+            .then_some(RenderRegion::OutputRegion((epsg, tile))))
+    }
+
+    /// 判断区域是否与影像边界相交
+    ///
+    /// # 参数
+    /// * `epsg` - 区域的坐标系 EPSG 代码
+    /// * `region` - 区域,使用投影的原生单位(地理坐标系为弧度)
+    pub(super) fn overlaps(&self, epsg: u16, region: &Region<f64>) -> CloudTiffResult<bool> {
+        // 跨越经度周期边界的影像拆分后分别判断
+        let image = self.input_projection.bounds(epsg);
+        let parts = match projection::wrap_half_period(epsg)? {
+            Some(half_period) => image.split_wrapped(half_period),
+            None => vec![image],
+        };
+        Ok(parts.iter().any(|part| region.intersects(part)))
+    }
+
+    /// 创建与渲染结果格式一致的空白瓦片
+    ///
+    /// 与渲染时影像外的像素一致,所有像素都为无数据值,Alpha 波段为透明。
+    /// 使用地形分析时以 NaN 高程计算,使输出为地形分析的无数据值
+    fn empty_tile(&self) -> CloudTiffResult<Raster> {
+        let raster = match &self.terrain {
+            Some(terrain) => {
+                let (width, height) = self.resolution;
+                let elevation = vec![f32::NAN; width as usize * height as usize];
+                let raster = Raster::new(
+                    self.resolution,
+                    bytemuck::cast_slice(&elevation).to_vec(),
+                    vec![32],
+                    PhotometricInterpretation::BlackIsZero,
+                    vec![SampleFormat::Float],
+                    vec![],
+                    Endian::native(),
                 )?;
-                // 获取需要的瓦片索引
-                let indices = pixel_map.iter().map(|(i, _)| *i).collect();
-                // 读取瓦片数据
-                let tile_cache = tiles::get_tiles(&self.reader, level, indices);
-                // 根据像素映射渲染图像
-                render_pixel_map(&pixel_map, level, &tile_cache, &dimensions)
+                raster.terrain(terrain, (1.0, 1.0))?
+            }
+            None => {
+                let level = self.cog.get_level(0)?;
+                Raster::blank(
+                    self.resolution,
+                    level.bits_per_sample.clone(),
+                    level.interpretation,
+                    level.sample_format.clone(),
+                    level.extra_samples.clone(),
+                    Endian::native(),
+                )
+            }
+        };
+        let (width, height) = self.resolution;
+        let footprint = vec![false; width as usize * height as usize];
+        self.finish(raster, &footprint, None)
+    }
+
+    /// 计算四周各扩展一个输出像素的渲染区域
+    ///
+    /// 输入裁剪模式下不会超出图像边界,超出的一侧不扩展
+    fn halo(&self) -> CloudTiffResult<Halo> {
+        let (width, height) = self.resolution;
+        match self.region {
+            RenderRegion::InputCrop(crop) => {
+                let (left, top, right, bottom) = crop.to_f64();
+                let dx = (right - left) / width as f64;
+                let dy = (bottom - top) / height as f64;
+
+                // 仅在完整的一个像素位于图像内时扩展
+                const EPSILON: f64 = 1e-9;
+                let pad = |inside: bool| if inside { 1 } else { 0 };
+                let (pad_left, pad_top) = (pad(left - dx >= -EPSILON), pad(top - dy >= -EPSILON));
+                let (pad_right, pad_bottom) = (
+                    pad(right + dx <= 1.0 + EPSILON),
+                    pad(bottom + dy <= 1.0 + EPSILON),
+                );
+                let region = RenderRegion::InputCrop(Region::new_saturated(
+                    left - pad_left as f64 * dx,
+                    top - pad_top as f64 * dy,
+                    right + pad_right as f64 * dx,
+                    bottom + pad_bottom as f64 * dy,
+                ));
+
+                // 投影单位下的像素尺寸换算为米
+                let scale = self.input_projection.scale;
+                let pixel_size = self
+                    .input_projection
+                    .ground_pixel_size((dx * scale.0, dy * scale.1), (top + bottom) / 2.0);
+                Ok(Halo {
+                    region,
+                    dimensions: (width + pad_left + pad_right, height + pad_top + pad_bottom),
+                    window: Region::new(pad_left, pad_top, pad_left + width, pad_top + height),
+                    pixel_size,
+                })
+            }
+            RenderRegion::OutputRegion((epsg, region)) => {
+                let dx = region.x.range() / width as f64;
+                let dy = region.y.range() / height as f64;
+                let region_with_halo = Region::new(
+                    region.x.min - dx,
+                    region.y.min - dy,
+                    region.x.max + dx,
+                    region.y.max + dy,
+                );
+                let centre_y = (region.y.min + region.y.max) / 2.0;
+                let pixel_size = projection::ground_pixel_size(epsg, (dx, dy), centre_y)?;
+                Ok(Halo {
+                    region: RenderRegion::OutputRegion((epsg, region_with_halo)),
+                    dimensions: (width + 2, height + 2),
+                    window: Region::new(1, 1, width + 1, height + 1),
+                    pixel_size,
+                })
+            }
+            RenderRegion::OutputGrid((epsg, grid)) => {
+                // 原点移动到左上方一个像素处
+                let origin = grid.apply(-1.0, -1.0);
+                let grid_with_halo = GeoTransform { origin, ..grid };
+                // 行和列方向的像素边长
+                let dx = grid.pixel_size.0.hypot(grid.rotation.1);
+                let dy = grid.pixel_size.1.hypot(grid.rotation.0);
+                let (_, centre_y) = grid.apply(width as f64 / 2.0, height as f64 / 2.0);
+                let pixel_size = projection::ground_pixel_size(epsg, (dx, dy), centre_y)?;
+                Ok(Halo {
+                    region: RenderRegion::OutputGrid((epsg, grid_with_halo)),
+                    dimensions: (width + 2, height + 2),
+                    window: Region::new(1, 1, width + 1, height + 1),
+                    pixel_size,
+                })
             }
         }
     }
+
+    /// 裁切渲染结果并进行后处理
+    ///
+    /// 影像范围外和裁切多边形外的像素设为无数据值。
+    /// 这些像素和无数据值的像素组成无效像素掩膜,
+    /// 对比度拉伸和颜色映射后这些像素为透明(拉伸结果没有 Alpha 波段时数据波段为 0)
+    ///
+    /// # 参数
+    /// * `raster` - 渲染结果
+    /// * `footprint` - 影像覆盖的输出像素
+    /// * `mask` - 裁切多边形掩膜
+    fn finish(
+        &self,
+        raster: Raster,
+        footprint: &[bool],
+        mask: Option<&[bool]>,
+    ) -> CloudTiffResult<Raster> {
+        let (raster, inside) = self.clip(raster, footprint, mask)?;
+        if self.stretch.is_none() && self.colormap.is_none() {
+            return Ok(raster);
+        }
+        let mut valid = raster.valid_pixels(self.output_nodata())?;
+        for (valid, inside) in valid.iter_mut().zip(&inside) {
+            *valid &= *inside;
+        }
+        self.post_process(raster, &valid)
+    }
+
+    /// 对渲染结果进行后处理
+    ///
+    /// 依次应用对比度拉伸(转换为每波段 8 位)和颜色映射(转换为 RGBA),
+    /// `valid` 为 false 的像素为透明
+    fn post_process(&self, mut raster: Raster, valid: &[bool]) -> CloudTiffResult<Raster> {
+        if let Some(stretch) = &self.stretch {
+            let stretch = Stretch {
+                nodata: stretch.nodata.or(self.output_nodata()),
+                ..stretch.clone()
+            };
+            raster = raster.stretch_to_u8_masked(&stretch, Some(valid))?;
+        }
+        if let Some(colormap) = &self.colormap {
+            raster = raster.apply_colormap_masked(colormap, Some(valid))?;
+        }
+        Ok(raster)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -66,38 +512,232 @@ mod not_sync {
         /// 执行异步渲染操作
         ///
         /// 与同步渲染逻辑相同,但使用异步IO操作
-        pub async fn render_async(&'a self) -> CloudTiffResult<Raster> {
-            let dimensions = self.resolution;
-            match self.region {
+        pub async fn render_async(&self) -> CloudTiffResult<Raster> {
+            let mask = self.cutline_mask()?;
+            if mask.as_ref().is_some_and(|mask| !mask.contains(&true)) {
+                return self.empty_tile();
+            }
+            let (raster, footprint) = match &self.terrain {
+                Some(terrain) => {
+                    let halo = self.halo()?;
+                    let (raster, footprint) = self
+                        .render_region_async(&halo.region, halo.dimensions, None)
+                        .await?;
+                    let terrain = self.terrain_with_nodata(terrain);
+                    let raster = halo.apply(raster, &footprint, &terrain)?;
+                    (raster, halo.window_mask(&footprint))
+                }
+                None => {
+                    self.render_region_async(&self.region, self.resolution, mask.as_deref())
+                        .await?
+                }
+            };
+            self.finish(raster, &footprint, mask.as_deref())
+        }
+
+        /// 异步渲染 Web 墨卡托(EPSG:3857)下的 XYZ 瓦片
+        ///
+        /// 与 [`RenderBuilder::render_tile`] 相同,但使用异步IO操作
+        ///
+        /// # 参数
+        /// * `z` - 缩放级别
+        /// * `x`, `y` - 瓦片索引,原点位于左上角
+        /// * `size` - 瓦片边长(像素)
+        pub async fn render_tile_async(
+            self,
+            z: u32,
+            x: u32,
+            y: u32,
+            size: u32,
+        ) -> CloudTiffResult<Raster> {
+            let tile = wmts::tile_bounds_web_mercator(x, y, z);
+            self.with_exact_resolution((size, size))
+                .render_tile_bounds_async(WEB_MERCATOR_EPSG, tile)
+                .await
+        }
+
+        /// 异步渲染任意瓦片矩阵集中的瓦片
+        ///
+        /// 与 [`RenderBuilder::render_tms_tile`] 相同,但使用异步IO操作
+        ///
+        /// # 参数
+        /// * `tms` - 瓦片矩阵集
+        /// * `z` - 缩放级别
+        /// * `x`, `y` - 瓦片列号和行号
+        pub async fn render_tms_tile_async(
+            self,
+            tms: &TileMatrixSet,
+            z: u32,
+            x: u32,
+            y: u32,
+        ) -> CloudTiffResult<Raster> {
+            let matrix = tms
+                .tile_matrix(z)
+                .ok_or(CloudTiffError::TileLevelOutOfRange((
+                    z as usize,
+                    tms.max_zoom() as usize,
+                )))?;
+            self.with_exact_resolution((matrix.tile_width, matrix.tile_height))
+                .render_tile_bounds_async(tms.epsg, matrix.tile_bounds(x, y))
+                .await
+        }
+
+        /// 异步渲染瓦片边界,瓦片无效或不与影像相交时返回空白瓦片
+        async fn render_tile_bounds_async(
+            self,
+            epsg: u16,
+            tile: Option<Region<f64>>,
+        ) -> CloudTiffResult<Raster> {
+            let Some(region) = self.tile_region(epsg, tile)? else {
+                return self.empty_tile();
+            };
+            let builder = RenderBuilder { region, ..self };
+            match builder.render_async().await {
+                Err(CloudTiffError::RegionOutOfBounds(_)) => builder.empty_tile(),
+                result => result,
+            }
+        }
+
+        /// 以指定的尺寸异步渲染区域,同时返回影像覆盖的输出像素掩膜
+        async fn render_region_async(
+            &self,
+            region: &RenderRegion,
+            dimensions: (u32, u32),
+            mask: Option<&[bool]>,
+        ) -> CloudTiffResult<(Raster, Vec<bool>)> {
+            let rendered = match *region {
                 RenderRegion::InputCrop(crop) => {
                     let level = util::render_level_from_crop(self.cog, &crop, &dimensions);
-                    let indices = level.tile_indices_within_image_crop(crop);
+                    let indices =
+                        crop_tile_indices(level, &crop, dimensions, self.resampling, mask);
                     let tile_cache: HashMap<usize, Raster> =
-                        tiles::get_tiles_async(&self.reader, level, indices).await;
-                    Ok(render_image_crop_from_tile_cache(
+                        tiles::get_tiles_async(&self.reader, level, indices).await?;
+                    let raster = render_image_crop_from_tile_cache(
                         &tile_cache,
                         level,
                         &crop,
                         &dimensions,
-                    ))
+                        self.resampling,
+                    );
+                    (raster, full_coverage(&dimensions))
                 }
                 RenderRegion::OutputRegion((epsg, region)) => {
-                    let level =
-                        util::render_level_from_region(self.cog, epsg, &region, &dimensions)?;
-                    let pixel_map = util::project_pixel_map(
-                        level,
-                        &self.input_projection,
-                        epsg,
-                        &region,
-                        &dimensions,
-                    )?;
-                    let indices = pixel_map.iter().map(|(i, _)| *i).collect();
-                    let tile_cache = tiles::get_tiles_async(&self.reader, level, indices).await;
-                    render_pixel_map(&pixel_map, level, &tile_cache, &dimensions)
+                    let grid = GeoTransform::from_region(&region, dimensions);
+                    self.render_grid_async(epsg, &grid, dimensions, mask)
+                        .await?
+                }
+                RenderRegion::OutputGrid((epsg, grid)) => {
+                    self.render_grid_async(epsg, &grid, dimensions, mask)
+                        .await?
                 }
+            };
+            Ok(rendered)
+        }
+
+        /// 异步渲染输出像素网格,同时返回影像覆盖的输出像素掩膜
+        pub(in crate::render) async fn render_grid_async(
+            &self,
+            epsg: u16,
+            grid: &GeoTransform,
+            dimensions: (u32, u32),
+            mask: Option<&[bool]>,
+        ) -> CloudTiffResult<(Raster, Vec<bool>)> {
+            let (level, points, indices) = self.project_grid(epsg, grid, dimensions, mask)?;
+            let tile_cache = tiles::get_tiles_async(&self.reader, level, indices).await?;
+            let raster =
+                render_sample_points(&points, level, &tile_cache, &dimensions, self.resampling)?;
+            Ok((raster, coverage(&points, &dimensions)))
+        }
+    }
+}
+
+/// 输出像素的采样点
+///
+/// 包含 (输出坐标, 层级像素坐标, 每个输出像素对应的源像素数)
+type SamplePoint = ((u32, u32), (f64, f64), (f64, f64));
+
+/// 计算输入裁剪模式下需要读取的瓦片索引
+///
+/// 设置掩膜时只读取掩膜内的输出像素需要的瓦片
+///
+/// # 参数
+/// * `level` - 渲染使用的图像层级
+/// * `crop` - 裁剪区域
+/// * `dimensions` - 输出图像尺寸
+/// * `resampling` - 重采样方法
+/// * `mask` - 按行优先排列的输出像素掩膜
+fn crop_tile_indices(
+    level: &Level,
+    crop: &Region<UnitFloat>,
+    dimensions: (u32, u32),
+    resampling: Resampling,
+    mask: Option<&[bool]>,
+) -> Vec<usize> {
+    let Some(mask) = mask else {
+        return padded_crop_tile_indices(level, crop, &dimensions, resampling);
+    };
+    let (left, top, right, bottom) = crop.to_f64();
+    let dx = (right - left) / dimensions.0 as f64;
+    let dy = (bottom - top) / dimensions.1 as f64;
+
+    // 按行合并掩膜内连续的输出像素,每段的尺寸与像素数一致以保持插值核的支撑范围
+    let mut indices = BTreeSet::new();
+    for (j, row) in mask.chunks(dimensions.0 as usize).enumerate() {
+        let mut i = 0;
+        while i < row.len() {
+            if !row[i] {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < row.len() && row[i] {
+                i += 1;
             }
+            let y = top + j as f64 * dy;
+            let run =
+                Region::new_saturated(left + start as f64 * dx, y, left + i as f64 * dx, y + dy);
+            let run_dimensions = ((i - start) as u32, 1);
+            indices.extend(padded_crop_tile_indices(
+                level,
+                &run,
+                &run_dimensions,
+                resampling,
+            ));
         }
     }
+    indices.into_iter().collect()
+}
+
+/// 计算裁剪区域需要读取的瓦片索引
+///
+/// 除了裁剪区域覆盖的瓦片外,还包括插值核跨越瓦片边界时需要的相邻瓦片
+///
+/// # 参数
+/// * `level` - 渲染使用的图像层级
+/// * `crop` - 裁剪区域
+/// * `dimensions` - 输出图像尺寸
+/// * `resampling` - 重采样方法
+fn padded_crop_tile_indices(
+    level: &Level,
+    crop: &Region<UnitFloat>,
+    dimensions: &(u32, u32),
+    resampling: Resampling,
+) -> Vec<usize> {
+    let (width, height) = (level.width() as f64, level.height() as f64);
+    let (left, top, right, bottom) = crop.to_f64();
+
+    // 将采样范围向外扩展插值核的支撑范围
+    let scale_x = (right - left) * width / dimensions.0 as f64;
+    let scale_y = (bottom - top) * height / dimensions.1 as f64;
+    let margin_x = resampling.support(scale_x) / width;
+    let margin_y = resampling.support(scale_y) / height;
+    let padded = Region::new_saturated(
+        left - margin_x,
+        top - margin_y,
+        right + margin_x,
+        bottom + margin_y,
+    );
+    level.tile_indices_within_image_crop(padded)
 }
 
 /// 从瓦片缓存中渲染裁剪后的图像
@@ -107,38 +747,41 @@ mod not_sync {
 /// * `level` - 渲染使用的图像层级
 /// * `crop` - 裁剪区域
 /// * `dimensions` - 输出图像尺寸
+/// * `resampling` - 重采样方法
 pub fn render_image_crop_from_tile_cache(
     tile_cache: &HashMap<usize, Raster>,
     level: &Level,
     crop: &Region<UnitFloat>,
     dimensions: &(u32, u32),
+    resampling: Resampling,
 ) -> Raster {
     // 创建空白输出栅格
     let mut render_raster = Raster::blank(
-        dimensions.clone(),
+        *dimensions,
         level.bits_per_sample.clone(),
         level.interpretation,
         level.sample_format.clone(),
         level.extra_samples.clone(),
-        level.endian,
+        Endian::native(),
     );
+    let sampler = LevelSampler::new(level, tile_cache, resampling);
 
     // 计算采样步长
     let dxdi = crop.x.range().as_f64() / dimensions.0 as f64;
     let dydj = crop.y.range().as_f64() / dimensions.1 as f64;
 
-    // 遍历输出像素
-    let mut y = crop.y.min.as_f64();
+    // 每个输出像素对应的源像素数
+    let (width, height) = (level.width() as f64, level.height() as f64);
+    let scale = (dxdi * width, dydj * height);
+
+    // 遍历输出像素,在像素中心处采样
+    let mut y = crop.y.min.as_f64() + dydj / 2.0;
     for j in 0..dimensions.1 {
-        let mut x = crop.x.min.as_f64();
+        let mut x = crop.x.min.as_f64() + dxdi / 2.0;
         for i in 0..dimensions.0 {
-            // 计算对应的瓦片索引和瓦片内坐标
-            if let Ok((tile_index, u, v)) = level.index_from_image_coords(x, y) {
-                if let Some(tile) = tile_cache.get(&tile_index) {
-                    if let Some(pixel) = tile.get_pixel(u as u32, v as u32) {
-                        let _ = render_raster.put_pixel(i, j, pixel);
-                    }
-                }
+            // 在层级像素坐标下采样
+            if let Some(pixel) = sampler.sample(x * width, y * height, scale) {
+                let _ = render_raster.put_pixel(i, j, pixel);
             }
             x += dxdi;
         }
@@ -147,38 +790,121 @@ pub fn render_image_crop_from_tile_cache(
     render_raster
 }
 
-/// 根据像素映射关系渲染图像
+/// 将像素映射转换为层级像素坐标下的采样点
+///
+/// 每个采样点的缩放比例根据相邻输出像素在源图像中的距离估算
 ///
 /// # 参数
 /// * `pixel_map` - 源图像到目标图像的像素映射关系
 /// * `level` - 渲染使用的图像层级
-/// * `tile_cache` - 瓦片数据缓存
 /// * `dimensions` - 输出图像尺寸
-fn render_pixel_map(
+fn sample_points(
     pixel_map: &util::PixelMap,
     level: &Level,
+    dimensions: &(u32, u32),
+) -> Vec<SamplePoint> {
+    let (width, height) = (dimensions.0 as usize, dimensions.1 as usize);
+    let col_count = level.col_count();
+
+    // 将瓦片内坐标还原为层级像素坐标
+    let mut grid: Vec<Option<(f64, f64)>> = vec![None; width * height];
+    for (tile_index, tile_pixel_map) in pixel_map.iter() {
+        let col = (tile_index % col_count) as f64;
+        let row = (tile_index / col_count) as f64;
+        for (from, to) in tile_pixel_map {
+            let x = col * level.tile_width as f64 + from.0;
+            let y = row * level.tile_height as f64 + from.1;
+            grid[to.1 as usize * width + to.0 as usize] = Some((x, y));
+        }
+    }
+
+    // 相邻输出像素之间的源像素距离
+    let distance = |a: (f64, f64), b: Option<(f64, f64)>| {
+        b.map(|b| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt())
+    };
+
+    let mut points = Vec::with_capacity(pixel_map.values().map(Vec::len).sum());
+    for j in 0..height {
+        for i in 0..width {
+            let Some(p) = grid[j * width + i] else {
+                continue;
+            };
+            let next_x = (i + 1 < width).then(|| grid[j * width + i + 1]).flatten();
+            let prev_x = (i > 0).then(|| grid[j * width + i - 1]).flatten();
+            let next_y = (j + 1 < height)
+                .then(|| grid[(j + 1) * width + i])
+                .flatten();
+            let prev_y = (j > 0).then(|| grid[(j - 1) * width + i]).flatten();
+            let scale_x = distance(p, next_x).or(distance(p, prev_x)).unwrap_or(1.0);
+            let scale_y = distance(p, next_y).or(distance(p, prev_y)).unwrap_or(1.0);
+            points.push(((i as u32, j as u32), p, (scale_x, scale_y)));
+        }
+    }
+    points
+}
+
+/// 计算采样点覆盖的输出像素掩膜
+///
+/// # 参数
+/// * `points` - 采样点
+/// * `dimensions` - 输出图像尺寸
+fn coverage(points: &[SamplePoint], dimensions: &(u32, u32)) -> Vec<bool> {
+    let mut mask = vec![false; dimensions.0 as usize * dimensions.1 as usize];
+    for ((i, j), ..) in points {
+        mask[*j as usize * dimensions.0 as usize + *i as usize] = true;
+    }
+    mask
+}
+
+/// 所有像素都被影像覆盖的掩膜
+fn full_coverage(dimensions: &(u32, u32)) -> Vec<bool> {
+    vec![true; dimensions.0 as usize * dimensions.1 as usize]
+}
+
+/// 计算采样点需要读取的瓦片索引
+///
+/// # 参数
+/// * `points` - 采样点
+/// * `level` - 渲染使用的图像层级
+/// * `resampling` - 重采样方法
+fn point_tile_indices(points: &[SamplePoint], level: &Level, resampling: Resampling) -> Vec<usize> {
+    resample::tile_indices_for_points(
+        level,
+        resampling,
+        points.iter().map(|(_, (x, y), scale)| (*x, *y, *scale)),
+    )
+}
+
+/// 根据采样点渲染图像
+///
+/// # 参数
+/// * `points` - 采样点
+/// * `level` - 渲染使用的图像层级
+/// * `tile_cache` - 瓦片数据缓存
+/// * `dimensions` - 输出图像尺寸
+/// * `resampling` - 重采样方法
+fn render_sample_points(
+    points: &[SamplePoint],
+    level: &Level,
     tile_cache: &HashMap<usize, Raster>,
     dimensions: &(u32, u32),
+    resampling: Resampling,
 ) -> CloudTiffResult<Raster> {
     // 创建空白输出栅格
     let mut render_raster = Raster::blank(
-        dimensions.clone(),
+        *dimensions,
         level.bits_per_sample.clone(),
         level.interpretation,
         level.sample_format.clone(),
         level.extra_samples.clone(),
-        level.endian,
+        Endian::native(),
     );
+    let sampler = LevelSampler::new(level, tile_cache, resampling);
 
-    // 遍历像素映射进行渲染
-    for (tile_index, tile_pixel_map) in pixel_map.iter() {
-        if let Some(tile) = tile_cache.get(tile_index) {
-            for (from, to) in tile_pixel_map {
-                // TODO: 实现更多插值方法,目前仅使用最近邻插值
-                if let Some(pixel) = tile.get_pixel(from.0 as u32, from.1 as u32) {
-                    let _ = render_raster.put_pixel(to.0, to.1, pixel);
-                }
-            }
+    // 遍历采样点进行渲染
+    for (to, from, scale) in points {
+        if let Some(pixel) = sampler.sample(from.0, from.1, *scale) {
+            let _ = render_raster.put_pixel(to.0, to.1, pixel);
         }
     }
     Ok(render_raster)
@@ -0,0 +1,500 @@
+//! 重采样模块
+//!
+//! 本模块实现了渲染时从源层级像素到输出像素的重采样方法:
+//! - 最近邻、双线性、三次卷积和 Lanczos 插值
+//! - 平均值和众数等面积方法
+//!
+//! 采样坐标使用层级的连续像素坐标,像素 `k` 覆盖 `[k, k+1)`,其中心位于 `k + 0.5`。
+//! 采样点是输出像素中心投影到层级上的位置 `p`,所有方法都在 `p - 0.5` 处求值:
+//! 最近邻取中心最接近 `p - 0.5` 的像素(即 `p` 所在的像素),插值核以 `p - 0.5` 为中心,
+//! 面积方法对以 `p` 为中心、边长为 `scale` 的输出像素覆盖范围积分。
+//! 无数据值、NaN 和透明的源像素不参与插值和面积方法的计算,剩余权重会重新归一化。
+//! 插值核在瓦片边界处会读取相邻瓦片的像素,因此调用方需要按照
+//! [`Resampling::support`] 给出的范围获取额外的瓦片。
+
+use super::tiles::TileCache;
+use crate::cog::Level;
+use crate::raster::{ExtraSamples, SampleFormat, SampleType};
+use crate::tiff::Endian;
+use std::collections::{BTreeSet, HashMap};
+use std::f64::consts::PI;
+
+/// 重采样方法
+///
+/// # 变体说明
+///
+/// * `Nearest` - 最近邻,保持原始像素值,速度最快
+/// * `Bilinear` - 双线性插值,使用 2x2 邻域
+/// * `Cubic` - 三次卷积插值(Catmull-Rom),使用 4x4 邻域
+/// * `Lanczos` - Lanczos 插值(a=3),使用 6x6 邻域
+/// * `Average` - 输出像素覆盖范围内源像素按覆盖面积加权的平均值,适合缩小影像
+/// * `Mode` - 输出像素覆盖范围内出现次数最多的像素值,适用于分类数据
+///
+/// 缩小图像时,插值核会按缩放比例展开以避免混叠。
+/// 无数据值不会混入有效像素:插值核和面积方法只使用有效的源像素,
+/// 插值时采样点所在的源像素本身无效则结果为该像素
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Resampling {
+    /// 最近邻
+    #[default]
+    Nearest,
+    /// 双线性插值
+    Bilinear,
+    /// 三次卷积插值
+    Cubic,
+    /// Lanczos 插值
+    Lanczos,
+    /// 面积加权平均
+    Average,
+    /// 众数
+    Mode,
+}
+
+impl Resampling {
+    /// 插值核在源像素单位下的半径(不缩放时)
+    fn kernel_radius(&self) -> f64 {
+        match self {
+            Resampling::Nearest | Resampling::Average | Resampling::Mode => 0.0,
+            Resampling::Bilinear => 1.0,
+            Resampling::Cubic => 2.0,
+            Resampling::Lanczos => 3.0,
+        }
+    }
+
+    /// 插值核权重
+    ///
+    /// # 参数
+    /// * `t` - 到采样点的距离(源像素单位,已按缩放比例归一化)
+    fn weight(&self, t: f64) -> f64 {
+        let t = t.abs();
+        match self {
+            Resampling::Bilinear => (1.0 - t).max(0.0),
+            Resampling::Cubic => {
+                // Catmull-Rom (a = -0.5)
+                let a = -0.5;
+                if t < 1.0 {
+                    ((a + 2.0) * t - (a + 3.0)) * t * t + 1.0
+                } else if t < 2.0 {
+                    ((a * t - 5.0 * a) * t + 8.0 * a) * t - 4.0 * a
+                } else {
+                    0.0
+                }
+            }
+            Resampling::Lanczos => {
+                let a = self.kernel_radius();
+                if t < 1e-12 {
+                    1.0
+                } else if t < a {
+                    let x = PI * t;
+                    a * x.sin() * (x / a).sin() / (x * x)
+                } else {
+                    0.0
+                }
+            }
+            Resampling::Nearest | Resampling::Average | Resampling::Mode => 1.0,
+        }
+    }
+
+    /// 采样时需要读取的源像素范围(相对采样点的像素数)
+    ///
+    /// 用于确定需要获取哪些相邻瓦片
+    ///
+    /// # 参数
+    /// * `scale` - 每个输出像素对应的源像素数
+    pub fn support(&self, scale: f64) -> f64 {
+        match self {
+            Resampling::Nearest => 0.0,
+            Resampling::Average | Resampling::Mode => scale.max(1.0) / 2.0,
+            _ => self.kernel_radius() * scale.max(1.0) + 0.5,
+        }
+    }
+}
+
+/// 像素内单个样本的布局
+#[derive(Clone, Copy, Debug)]
+struct SampleLayout {
+    /// 在像素中的字节偏移
+    offset: usize,
+    /// 样本类型
+    sample_type: SampleType,
+}
+
+/// 在源层级瓦片缓存上进行重采样的采样器
+pub struct LevelSampler<'a> {
+    /// 源层级
+    level: &'a Level,
+    /// 已读取的瓦片
+    tiles: &'a TileCache,
+    /// 重采样方法
+    resampling: Resampling,
+    /// 样本布局,为 None 时(非字节对齐或复数样本)只能使用最近邻
+    layout: Option<Vec<SampleLayout>>,
+    /// 源层级的无数据值
+    nodata: Option<f64>,
+    /// Alpha 波段索引
+    alpha: Option<usize>,
+}
+
+impl<'a> LevelSampler<'a> {
+    /// 创建采样器
+    ///
+    /// 源层级的无数据值和 Alpha 波段用于排除无效的源像素
+    ///
+    /// # 参数
+    /// * `level` - 源层级
+    /// * `tiles` - 已读取的瓦片缓存
+    /// * `resampling` - 重采样方法
+    pub fn new(level: &'a Level, tiles: &'a TileCache, resampling: Resampling) -> Self {
+        Self {
+            level,
+            tiles,
+            resampling,
+            layout: sample_layout(&level.bits_per_sample, &level.sample_format),
+            nodata: level.nodata,
+            alpha: ExtraSamples::alpha_band(level.bits_per_sample.len(), &level.extra_samples),
+        }
+    }
+
+    /// 判断解码后的源像素是否有效
+    ///
+    /// 透明像素和除 Alpha 外所有波段都为无数据值或 NaN 的像素无效
+    fn is_valid(&self, values: &[f64]) -> bool {
+        if self.alpha.is_some_and(|alpha| values[alpha] == 0.0) {
+            return false;
+        }
+        values
+            .iter()
+            .enumerate()
+            .filter(|(band, _)| Some(*band) != self.alpha)
+            .any(|(_, value)| !value.is_nan() && self.nodata != Some(*value))
+    }
+
+    /// 获取并解码源像素,返回像素是否存在且有效
+    fn valid_pixel(&self, layout: &[SampleLayout], x: i64, y: i64, values: &mut [f64]) -> bool {
+        match self.pixel(x, y) {
+            Some(pixel) => {
+                decode_pixel(layout, Endian::native(), &pixel, values);
+                self.is_valid(values)
+            }
+            None => false,
+        }
+    }
+
+    /// 获取层级中指定整数像素的值,坐标会被限制在图像范围内
+    ///
+    /// # 返回
+    /// 如果对应瓦片不在缓存中则返回 None
+    fn pixel(&self, x: i64, y: i64) -> Option<Vec<u8>> {
+        let (width, height) = self.level.dimensions;
+        let x = x.clamp(0, width as i64 - 1) as u32;
+        let y = y.clamp(0, height as i64 - 1) as u32;
+        let (tile_width, tile_height) = (self.level.tile_width, self.level.tile_height);
+        let index = (y / tile_height) as usize * self.level.col_count() + (x / tile_width) as usize;
+        self.tiles
+            .get(&index)?
+            .get_pixel(x % tile_width, y % tile_height)
+    }
+
+    /// 在连续像素坐标处采样
+    ///
+    /// # 参数
+    /// * `x`, `y` - 层级像素坐标
+    /// * `scale` - 每个输出像素在 x 和 y 方向上对应的源像素数
+    ///
+    /// # 返回
+    /// 采样得到的像素值,坐标超出图像或所需瓦片缺失时返回 None
+    pub fn sample(&self, x: f64, y: f64, scale: (f64, f64)) -> Option<Vec<u8>> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        let nearest = || self.pixel(x.floor() as i64, y.floor() as i64);
+        let Some(layout) = &self.layout else {
+            return nearest();
+        };
+        let encode = |values: Vec<f64>| encode_pixel(layout, Endian::native(), &values);
+        match self.resampling {
+            Resampling::Nearest => nearest(),
+            Resampling::Average => self
+                .average(layout, x, y, scale)
+                .map(encode)
+                .or_else(nearest),
+            Resampling::Mode => self.mode(layout, x, y, scale).or_else(nearest),
+            _ => self
+                .convolve(layout, x, y, scale)
+                .map(encode)
+                .or_else(nearest),
+        }
+    }
+
+    /// 在连续像素坐标处采样,返回每个样本的 f64 值
+    ///
+    /// 与 [`sample`](Self::sample) 相同,但插值结果不会按样本类型取整,适用于高程等数值查询。
+    /// 采样单个点时 `scale` 可以为 `(0.0, 0.0)`,此时插值核在 `(x, y)` 处求值
+    ///
+    /// # 返回
+    /// 坐标超出图像、所需瓦片缺失或样本类型不受支持时返回 None
+    pub fn sample_values(&self, x: f64, y: f64, scale: (f64, f64)) -> Option<Vec<f64>> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        let layout = self.layout.as_ref()?;
+        let decode = |pixel: Vec<u8>| {
+            let mut values = vec![0.0; layout.len()];
+            decode_pixel(layout, Endian::native(), &pixel, &mut values);
+            values
+        };
+        let nearest = || self.pixel(x.floor() as i64, y.floor() as i64).map(decode);
+        match self.resampling {
+            Resampling::Nearest => nearest(),
+            Resampling::Average => self.average(layout, x, y, scale).or_else(nearest),
+            Resampling::Mode => self.mode(layout, x, y, scale).map(decode).or_else(nearest),
+            _ => self.convolve(layout, x, y, scale).or_else(nearest),
+        }
+    }
+
+    /// 判断坐标是否位于图像范围内
+    fn contains(&self, x: f64, y: f64) -> bool {
+        let (width, height) = self.level.dimensions;
+        (0.0..=width as f64).contains(&x) && (0.0..=height as f64).contains(&y)
+    }
+
+    /// 使用可分离的插值核进行卷积
+    ///
+    /// 采样点所在的源像素无效时返回 None,由调用方取最近邻的像素,避免插值扩大有效范围
+    fn convolve(
+        &self,
+        layout: &[SampleLayout],
+        x: f64,
+        y: f64,
+        scale: (f64, f64),
+    ) -> Option<Vec<f64>> {
+        let mut values = vec![0.0; layout.len()];
+        if !self.valid_pixel(layout, x.floor() as i64, y.floor() as i64, &mut values) {
+            return None;
+        }
+        let taps_x = self.taps(x, scale.0);
+        let taps_y = self.taps(y, scale.1);
+
+        let mut sums = vec![0.0; layout.len()];
+        let mut total = 0.0;
+        for &(v, wy) in &taps_y {
+            for &(u, wx) in &taps_x {
+                let weight = wx * wy;
+                if weight == 0.0 {
+                    continue;
+                }
+                // 缺失和无效的像素不参与计算,剩余权重会重新归一化
+                if self.valid_pixel(layout, u, v, &mut values) {
+                    for (sum, value) in sums.iter_mut().zip(&values) {
+                        *sum += weight * value;
+                    }
+                    total += weight;
+                }
+            }
+        }
+        if total.abs() < 1e-12 {
+            return None;
+        }
+        for sum in sums.iter_mut() {
+            *sum /= total;
+        }
+        Some(sums)
+    }
+
+    /// 计算一个方向上的插值点和权重
+    ///
+    /// 插值核以 `p - 0.5` 为中心求值
+    ///
+    /// # 参数
+    /// * `p` - 采样坐标(输出像素中心)
+    /// * `scale` - 该方向上的缩放比例
+    fn taps(&self, p: f64, scale: f64) -> Vec<(i64, f64)> {
+        // 缩小时按比例展开插值核
+        let stretch = scale.max(1.0);
+        let radius = self.resampling.kernel_radius() * stretch;
+        // 源像素中心位于 k + 0.5
+        let center = p - 0.5;
+        let first = (center - radius).ceil() as i64;
+        let last = (center + radius).floor() as i64;
+        (first..=last)
+            .map(|k| (k, self.resampling.weight((k as f64 - center) / stretch)))
+            .collect()
+    }
+
+    /// 输出像素覆盖的源像素范围 `[min, max)`
+    ///
+    /// 输出像素以 `p` 为中心、边长为 `scale`(至少为一个源像素),
+    /// 中心落在该范围内的源像素被覆盖
+    fn footprint(p: f64, scale: f64) -> (i64, i64) {
+        let half = scale.max(1.0) / 2.0;
+        let min = (p - half - 0.5).ceil() as i64;
+        let max = ((p + half - 0.5).ceil() as i64).max(min + 1);
+        (min, max)
+    }
+
+    /// 计算一个方向上输出像素覆盖的源像素及其覆盖比例
+    ///
+    /// 输出像素覆盖 `[p - scale / 2, p + scale / 2)`,每个源像素的权重为其与该区间重叠的长度
+    ///
+    /// # 参数
+    /// * `p` - 采样坐标(输出像素中心)
+    /// * `scale` - 该方向上的缩放比例
+    /// * `size` - 该方向上的层级尺寸
+    fn coverage(p: f64, scale: f64, size: u32) -> Vec<(i64, f64)> {
+        let half = scale.max(1e-6) / 2.0;
+        let start = (p - half).max(0.0);
+        let end = (p + half).min(size as f64);
+        (start.floor() as i64..end.ceil() as i64)
+            .filter_map(|k| {
+                let overlap = end.min(k as f64 + 1.0) - start.max(k as f64);
+                (overlap > 0.0).then_some((k, overlap))
+            })
+            .collect()
+    }
+
+    /// 计算覆盖范围内按覆盖面积加权的平均值
+    ///
+    /// 部分落在输出像素内的源像素按重叠面积计入,缩小图像时可以避免混叠和摩尔纹。
+    /// 没有有效源像素时返回 None
+    fn average(
+        &self,
+        layout: &[SampleLayout],
+        x: f64,
+        y: f64,
+        scale: (f64, f64),
+    ) -> Option<Vec<f64>> {
+        let (width, height) = self.level.dimensions;
+        let cover_x = Self::coverage(x, scale.0, width);
+        let cover_y = Self::coverage(y, scale.1, height);
+
+        let mut sums = vec![0.0; layout.len()];
+        let mut values = vec![0.0; layout.len()];
+        let mut total = 0.0;
+        for &(v, wy) in &cover_y {
+            for &(u, wx) in &cover_x {
+                // 缺失和无效的像素不参与计算
+                if self.valid_pixel(layout, u, v, &mut values) {
+                    let weight = wx * wy;
+                    for (sum, value) in sums.iter_mut().zip(&values) {
+                        *sum += weight * value;
+                    }
+                    total += weight;
+                }
+            }
+        }
+        if total <= 0.0 {
+            return None;
+        }
+        for sum in sums.iter_mut() {
+            *sum /= total;
+        }
+        Some(sums)
+    }
+
+    /// 计算覆盖范围内出现次数最多的像素值
+    ///
+    /// 次数相同时取最先达到该次数的像素,无效的像素不参与计数。
+    /// 没有有效源像素时返回 None
+    fn mode(&self, layout: &[SampleLayout], x: f64, y: f64, scale: (f64, f64)) -> Option<Vec<u8>> {
+        let (x_min, x_max) = Self::footprint(x, scale.0);
+        let (y_min, y_max) = Self::footprint(y, scale.1);
+        let (width, height) = self.level.dimensions;
+
+        let mut counts: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut best: Option<(Vec<u8>, usize)> = None;
+        let mut values = vec![0.0; layout.len()];
+        for v in y_min.max(0)..y_max.min(height as i64) {
+            for u in x_min.max(0)..x_max.min(width as i64) {
+                if let Some(pixel) = self.pixel(u, v) {
+                    decode_pixel(layout, Endian::native(), &pixel, &mut values);
+                    if !self.is_valid(&values) {
+                        continue;
+                    }
+                    let count = counts.entry(pixel.clone()).or_insert(0);
+                    *count += 1;
+                    if best.as_ref().is_none_or(|(_, n)| *count > *n) {
+                        best = Some((pixel, *count));
+                    }
+                }
+            }
+        }
+        best.map(|(pixel, _)| pixel)
+    }
+}
+
+/// 计算给定采样点所需的瓦片索引
+///
+/// # 参数
+/// * `level` - 源层级
+/// * `resampling` - 重采样方法
+/// * `points` - 采样点 `(x, y, (scale_x, scale_y))`,使用层级像素坐标
+///
+/// # 返回
+/// 按索引排序的瓦片列表
+pub fn tile_indices_for_points(
+    level: &Level,
+    resampling: Resampling,
+    points: impl IntoIterator<Item = (f64, f64, (f64, f64))>,
+) -> Vec<usize> {
+    let (width, height) = level.dimensions;
+    let col_count = level.col_count();
+    let tile_col = |x: f64| (x.clamp(0.0, width as f64 - 1.0) as u32 / level.tile_width) as usize;
+    let tile_row = |y: f64| (y.clamp(0.0, height as f64 - 1.0) as u32 / level.tile_height) as usize;
+
+    let mut indices = BTreeSet::new();
+    for (x, y, (scale_x, scale_y)) in points {
+        let (rx, ry) = (resampling.support(scale_x), resampling.support(scale_y));
+        for row in tile_row(y - ry)..=tile_row(y + ry) {
+            for col in tile_col(x - rx)..=tile_col(x + rx) {
+                indices.insert(row * col_count + col);
+            }
+        }
+    }
+    indices.into_iter().collect()
+}
+
+/// 根据位深度和样本格式计算像素内各样本的布局
+///
+/// 只支持 [`SampleType`] 能表示的字节对齐样本
+fn sample_layout(
+    bits_per_sample: &[u16],
+    sample_format: &[SampleFormat],
+) -> Option<Vec<SampleLayout>> {
+    let mut offset = 0;
+    let mut layout = Vec::with_capacity(bits_per_sample.len());
+    for (i, bits) in bits_per_sample.iter().enumerate() {
+        let format = sample_format
+            .get(i)
+            .or(sample_format.first())
+            .copied()
+            .unwrap_or(SampleFormat::Unsigned);
+        let sample_type = SampleType::from_format(format, *bits)?;
+        layout.push(SampleLayout {
+            offset,
+            sample_type,
+        });
+        offset += sample_type.size();
+    }
+    Some(layout)
+}
+
+/// 将像素的各样本解码为 f64
+fn decode_pixel(layout: &[SampleLayout], endian: Endian, pixel: &[u8], values: &mut [f64]) {
+    for (sample, value) in layout.iter().zip(values.iter_mut()) {
+        let bytes = &pixel[sample.offset..sample.offset + sample.sample_type.size()];
+        *value = sample.sample_type.decode_f64(bytes, endian);
+    }
+}
+
+/// 将各样本的 f64 值编码为像素字节
+///
+/// 整数样本会四舍五入并限制在类型范围内
+fn encode_pixel(layout: &[SampleLayout], endian: Endian, values: &[f64]) -> Vec<u8> {
+    let size = layout.last().map_or(0, |s| s.offset + s.sample_type.size());
+    let mut pixel = vec![0; size];
+    for (sample, value) in layout.iter().zip(values) {
+        let bytes = &mut pixel[sample.offset..sample.offset + sample.sample_type.size()];
+        sample.sample_type.encode_f64(*value, endian, bytes);
+    }
+    pixel
+}
@@ -2,9 +2,12 @@
 //!
 //! 本模块提供了从COG图像中读取和处理瓦片数据的功能。
 //! 包括同步和异步两种读取方式。
+//!
+//! 任何瓦片读取或解压失败都会作为错误返回,而不是在输出图像中留下空洞。
 
 use super::SyncReader;
-use crate::cog::Level;
+use crate::cog::{CloudTiffResult, Level};
+use crate::io::ReadRange;
 use crate::raster::Raster;
 use std::collections::HashMap;
 use tracing::*;
@@ -22,36 +25,52 @@ use super::util;
 /// * `indices` - 需要读取的瓦片索引列表
 ///
 /// # 返回
-/// 返回包含瓦片数据的缓存映射
-pub fn get_tiles(reader: &SyncReader, level: &Level, indices: Vec<usize>) -> TileCache {
+/// 返回包含瓦片数据的缓存映射,任一瓦片读取或解压失败时返回错误
+pub fn get_tiles(
+    reader: &SyncReader,
+    level: &Level,
+    indices: Vec<usize>,
+) -> CloudTiffResult<TileCache> {
+    get_tiles_from(&*reader.0, level, indices)
+}
+
+/// 使用任意范围读取器同步读取瓦片数据
+///
+/// # 参数
+/// * `reader` - 范围读取器
+/// * `level` - COG图像层级
+/// * `indices` - 需要读取的瓦片索引列表
+///
+/// # 返回
+/// 返回包含瓦片数据的缓存映射,任一瓦片读取或解压失败时返回错误
+pub fn get_tiles_from<R: ReadRange + ?Sized>(
+    reader: &R,
+    level: &Level,
+    indices: Vec<usize>,
+) -> CloudTiffResult<TileCache> {
     // 获取瓦片的位置信息
     let tile_infos = util::tile_info_from_indices(level, indices);
 
     // 同步读取和解压瓦片数据
     tile_infos
         .into_iter()
-        .filter_map(|(index, (start, end))| {
+        .map(|(index, (start, end))| {
             // 计算瓦片大小并分配缓冲区
             let n = (end - start) as usize;
             let mut buf = vec![0; n];
 
             // 读取瓦片字节数据
-            match reader.0.read_range_exact(start, &mut buf) {
-                Ok(_) => {
-                    // 从字节数据中解压提取瓦片
-                    match level.extract_tile_from_bytes(&buf) {
-                        Ok(tile) => Some((index, tile)),
-                        Err(e) => {
-                            warn!("瓦片解压失败: {e:?}");
-                            None
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("瓦片读取失败: {e:?}");
-                    None
-                }
-            }
+            reader.read_range_exact(start, &mut buf).map_err(|e| {
+                warn!("瓦片读取失败: {e:?}");
+                e
+            })?;
+
+            // 从字节数据中解压提取瓦片
+            let tile = level.extract_tile_from_bytes(&buf).map_err(|e| {
+                warn!("瓦片解压失败: {e:?}");
+                e
+            })?;
+            Ok((index, tile))
         })
         .collect()
 }
@@ -63,8 +82,14 @@ pub use not_sync::*;
 mod not_sync {
     use super::super::AsyncReader;
     use super::*;
+    use crate::io::AsyncReadRange;
+    use crate::CloudTiffError;
+    use futures::StreamExt;
     use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
+    /// 读取器未给出建议时,同时进行的最大瓦片请求数
+    pub const DEFAULT_MAX_IN_FLIGHT: usize = 32;
+
     /// 异步读取瓦片数据
     ///
     /// # 参数
@@ -73,18 +98,25 @@ mod not_sync {
     /// * `indices` - 需要读取的瓦片索引列表
     ///
     /// # 返回
-    /// 返回包含瓦片数据的缓存映射
+    /// 返回包含瓦片数据的缓存映射,任一瓦片读取或解压失败时返回错误
     pub async fn get_tiles_async(
         reader: &AsyncReader,
         level: &Level,
         indices: Vec<usize>,
-    ) -> TileCache {
+    ) -> CloudTiffResult<TileCache> {
         // 获取瓦片位置信息
         let tile_infos = util::tile_info_from_indices(level, indices);
 
-        // 使用futures::future::join_all并发执行多个异步任务
+        // 同时进行的请求数由读取器的策略决定
+        let max_in_flight = reader
+            .0
+            .max_in_flight()
+            .unwrap_or(DEFAULT_MAX_IN_FLIGHT)
+            .max(1);
+
+        // 并发执行多个异步任务,但同时最多只有 max_in_flight 个任务在运行
         // 每个任务负责读取一个瓦片的字节数据
-        let byte_results: Vec<_> = futures::future::join_all(
+        let byte_results: Vec<_> = futures::stream::iter(
             tile_infos
                 .into_iter()
                 // 为每个瓦片信息克隆reader以支持并发
@@ -104,24 +136,26 @@ mod not_sync {
                     })
                 }),
         )
+        .buffer_unordered(max_in_flight)
+        .collect::<Vec<_>>()
         .await
         // 处理读取结果
         .into_iter()
-        .filter_map(|result| match result {
+        .map(|result| match result {
             // 读取成功
-            Ok(Ok(tile_bytes)) => Some(tile_bytes),
+            Ok(Ok(tile_bytes)) => Ok(tile_bytes),
             // 读取字节失败
             Ok(Err(e)) => {
                 warn!("瓦片字节读取失败: {e:?}");
-                None
+                Err(CloudTiffError::from(e))
             }
             // 任务执行失败
             Err(e) => {
                 warn!("瓦片读取任务失败: {e:?}");
-                None
+                Err(CloudTiffError::AsyncJoinError(e))
             }
         })
-        .collect();
+        .collect::<CloudTiffResult<_>>()?;
 
         // 使用rayon并行解压瓦片数据
         let tile_results: Vec<_> = byte_results
@@ -146,12 +180,72 @@ mod not_sync {
                     tile_cache.insert(index, tile);
                 }
                 Err(e) => {
-                    // 解压失败时记录警告日志
-                    warn!("瓦片解压失败: {e:?}")
+                    // 解压失败时记录警告日志并返回错误
+                    warn!("瓦片解压失败: {e:?}");
+                    return Err(e);
                 }
             }
         }
 
-        tile_cache
+        Ok(tile_cache)
+    }
+
+    /// 使用任意异步范围读取器读取瓦片数据
+    ///
+    /// 与 [`get_tiles_async`] 相同,但不要求读取器为 `'static`,
+    /// 请求在当前任务中并发执行
+    ///
+    /// # 参数
+    /// * `reader` - 异步范围读取器
+    /// * `level` - COG图像层级
+    /// * `indices` - 需要读取的瓦片索引列表
+    ///
+    /// # 返回
+    /// 返回包含瓦片数据的缓存映射,任一瓦片读取或解压失败时返回错误
+    pub async fn get_tiles_from_async<R: AsyncReadRange + ?Sized>(
+        reader: &R,
+        level: &Level,
+        indices: Vec<usize>,
+    ) -> CloudTiffResult<TileCache> {
+        let tile_infos = util::tile_info_from_indices(level, indices);
+        let max_in_flight = reader
+            .max_in_flight()
+            .unwrap_or(DEFAULT_MAX_IN_FLIGHT)
+            .max(1);
+
+        // 并发读取瓦片字节数据
+        let byte_results: Vec<(usize, Vec<u8>)> = futures::stream::iter(
+            tile_infos
+                .into_iter()
+                .map(|(index, (start, end))| async move {
+                    reader
+                        .read_range_to_vec_async(start, end)
+                        .await
+                        .map(|bytes| (index, bytes))
+                        .map_err(|e| {
+                            warn!("瓦片字节读取失败: {e:?}");
+                            CloudTiffError::from(e)
+                        })
+                }),
+        )
+        .buffer_unordered(max_in_flight)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<CloudTiffResult<_>>()?;
+
+        // 使用rayon并行解压瓦片数据
+        byte_results
+            .into_par_iter()
+            .map(|(index, bytes)| {
+                level
+                    .extract_tile_from_bytes(&bytes)
+                    .map(|tile| (index, tile))
+                    .map_err(|e| {
+                        warn!("瓦片解压失败: {e:?}");
+                        e
+                    })
+            })
+            .collect()
     }
 }
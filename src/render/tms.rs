@@ -0,0 +1,731 @@
+//! 瓦片矩阵集 (OGC TileMatrixSet 2.0) 模块
+//!
+//! 本模块提供了与投影无关的瓦片坐标系统,用于 WebMercatorQuad 以外的瓦片方案,
+//! 例如 WorldCRS84Quad、基于 UTM 的瓦片和各国的国家格网:
+//! - 内置 WebMercatorQuad 和 WorldCRS84Quad
+//! - 从 OGC TMS 2.0 JSON 加载自定义瓦片矩阵集(需要 `json` 特性)
+//! - 瓦片边界、点所在瓦片和区域覆盖的瓦片范围计算
+//! - 影像的缩放级别范围和瓦片树计算
+//!
+//! 瓦片矩阵集的坐标使用其坐标系的原生单位,地理坐标系为度(经度, 纬度),
+//! 投影坐标系为 (东, 北)。不支持可变矩阵宽度(variableMatrixWidths)。
+
+use crate::cog::CloudTiff;
+use crate::projection::{self, ProjectionError};
+use crate::Region;
+use proj4rs::Proj;
+use std::fmt;
+
+/// Web墨卡托投影 x 和 y 方向的最大坐标(米)
+const WEB_MERCATOR_EXTENT: f64 = super::wmts::WEB_MERCATOR_EXTENT;
+
+/// 浮点比较使用的相对容差
+const EPSILON: f64 = 1e-9;
+
+/// 瓦片矩阵集错误
+#[derive(Debug)]
+pub enum TileMatrixSetError {
+    /// JSON 解析错误
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+    /// 无法识别的坐标系,包含坐标系描述
+    UnsupportedCrs(String),
+    /// 没有瓦片矩阵
+    NoTileMatrices,
+    /// 无效的瓦片矩阵,包含矩阵标识
+    InvalidTileMatrix(String),
+}
+
+impl fmt::Display for TileMatrixSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for TileMatrixSetError {}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for TileMatrixSetError {
+    fn from(e: serde_json::Error) -> Self {
+        TileMatrixSetError::Json(e)
+    }
+}
+
+/// 瓦片矩阵原点所在的角
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CornerOfOrigin {
+    /// 左上角,行号向下(南)增加
+    #[default]
+    TopLeft,
+    /// 左下角,行号向上(北)增加
+    BottomLeft,
+}
+
+/// 瓦片矩阵,即瓦片矩阵集中的一个缩放级别
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileMatrix {
+    /// 矩阵标识
+    pub id: String,
+    /// 像素尺寸(坐标系单位)
+    pub cell_size: f64,
+    /// 原点坐标 (x, y),地理坐标系为 (经度, 纬度)
+    pub origin: (f64, f64),
+    /// 原点所在的角
+    pub corner_of_origin: CornerOfOrigin,
+    /// 瓦片宽度(像素)
+    pub tile_width: u32,
+    /// 瓦片高度(像素)
+    pub tile_height: u32,
+    /// 矩阵宽度(瓦片数)
+    pub matrix_width: u32,
+    /// 矩阵高度(瓦片数)
+    pub matrix_height: u32,
+}
+
+impl TileMatrix {
+    /// 单个瓦片覆盖的坐标范围 (宽, 高)
+    pub fn tile_span(&self) -> (f64, f64) {
+        (
+            self.cell_size * self.tile_width as f64,
+            self.cell_size * self.tile_height as f64,
+        )
+    }
+
+    /// 矩阵覆盖的坐标范围
+    pub fn bounds(&self) -> Region<f64> {
+        let (span_x, span_y) = self.tile_span();
+        let width = span_x * self.matrix_width as f64;
+        let height = span_y * self.matrix_height as f64;
+        let (x, y) = self.origin;
+        match self.corner_of_origin {
+            CornerOfOrigin::TopLeft => Region::new(x, y - height, x + width, y),
+            CornerOfOrigin::BottomLeft => Region::new(x, y, x + width, y + height),
+        }
+    }
+
+    /// 计算瓦片边界
+    ///
+    /// # 参数
+    /// * `x` - 瓦片列号
+    /// * `y` - 瓦片行号
+    ///
+    /// # 返回值
+    /// 返回瓦片的边界区域,如果索引超出矩阵范围则返回None
+    pub fn tile_bounds(&self, x: u32, y: u32) -> Option<Region<f64>> {
+        if x >= self.matrix_width || y >= self.matrix_height {
+            return None;
+        }
+        let (span_x, span_y) = self.tile_span();
+        let min_x = self.origin.0 + x as f64 * span_x;
+        Some(match self.corner_of_origin {
+            CornerOfOrigin::TopLeft => {
+                let max_y = self.origin.1 - y as f64 * span_y;
+                Region::new(min_x, max_y - span_y, min_x + span_x, max_y)
+            }
+            CornerOfOrigin::BottomLeft => {
+                let min_y = self.origin.1 + y as f64 * span_y;
+                Region::new(min_x, min_y, min_x + span_x, min_y + span_y)
+            }
+        })
+    }
+
+    /// 计算坐标点所在的瓦片
+    ///
+    /// # 参数
+    /// * `x`, `y` - 坐标系下的坐标
+    ///
+    /// # 返回值
+    /// 返回瓦片索引 (列, 行),如果坐标位于矩阵范围外则返回None
+    pub fn tile_for_point(&self, x: f64, y: f64) -> Option<(u32, u32)> {
+        let (col, row) = self.tile_coords(x, y);
+        let inside = (0.0..self.matrix_width as f64).contains(&col)
+            && (0.0..self.matrix_height as f64).contains(&row);
+        inside.then_some((col.floor() as u32, row.floor() as u32))
+    }
+
+    /// 计算区域覆盖的瓦片范围
+    ///
+    /// # 参数
+    /// * `bounds` - 坐标系下的区域
+    ///
+    /// # 返回值
+    /// 返回 `[min, max)` 区间的瓦片列号和行号范围,如果区域与矩阵不相交则返回None
+    pub fn tile_range(&self, bounds: &Region<f64>) -> Option<Region<u32>> {
+        let (col_a, row_a) = self.tile_coords(bounds.x.min, bounds.y.min);
+        let (col_b, row_b) = self.tile_coords(bounds.x.max, bounds.y.max);
+        // 恰好落在瓦片边界上的区域不包含相邻瓦片
+        let first = |v: f64| (v + EPSILON).floor();
+        let last = |v: f64| (v - EPSILON).ceil();
+        let min_col = first(col_a.min(col_b)).max(0.0);
+        let max_col = last(col_a.max(col_b)).min(self.matrix_width as f64);
+        let min_row = first(row_a.min(row_b)).max(0.0);
+        let max_row = last(row_a.max(row_b)).min(self.matrix_height as f64);
+        (min_col < max_col && min_row < max_row).then(|| {
+            Region::new(
+                min_col as u32,
+                min_row as u32,
+                max_col as u32,
+                max_row as u32,
+            )
+        })
+    }
+
+    /// 将坐标转换为连续的瓦片坐标 (列, 行)
+    fn tile_coords(&self, x: f64, y: f64) -> (f64, f64) {
+        let (span_x, span_y) = self.tile_span();
+        let col = (x - self.origin.0) / span_x;
+        let row = match self.corner_of_origin {
+            CornerOfOrigin::TopLeft => (self.origin.1 - y) / span_y,
+            CornerOfOrigin::BottomLeft => (y - self.origin.1) / span_y,
+        };
+        (col, row)
+    }
+}
+
+/// 瓦片矩阵集
+///
+/// 缩放级别 z 对应 `tile_matrices` 中的第 z 个矩阵,矩阵按从粗到细排列
+///
+/// # 示例
+///
+/// ```no_run
+/// use cloudtiff::render::tms::TileMatrixSet;
+///
+/// let tms = TileMatrixSet::world_crs84_quad();
+/// // 包含柏林的 8 级瓦片
+/// let (x, y) = tms.tile_for_point(8, 13.4, 52.5).unwrap();
+/// let bounds = tms.tile_bounds(8, x, y).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileMatrixSet {
+    /// 瓦片矩阵集标识
+    pub id: String,
+    /// 坐标系的 EPSG 代码
+    pub epsg: u16,
+    /// 按从粗到细排列的瓦片矩阵
+    pub tile_matrices: Vec<TileMatrix>,
+}
+
+impl TileMatrixSet {
+    /// Web 墨卡托瓦片矩阵集(GoogleMapsCompatible),0~24 级,256 像素瓦片
+    pub fn web_mercator_quad() -> Self {
+        let tile_matrices = (0..=24)
+            .map(|z| {
+                let n = 1_u32 << z;
+                TileMatrix {
+                    id: z.to_string(),
+                    cell_size: 2.0 * WEB_MERCATOR_EXTENT / 256.0 / n as f64,
+                    origin: (-WEB_MERCATOR_EXTENT, WEB_MERCATOR_EXTENT),
+                    corner_of_origin: CornerOfOrigin::TopLeft,
+                    tile_width: 256,
+                    tile_height: 256,
+                    matrix_width: n,
+                    matrix_height: n,
+                }
+            })
+            .collect();
+        Self {
+            id: "WebMercatorQuad".to_string(),
+            epsg: 3857,
+            tile_matrices,
+        }
+    }
+
+    /// 经纬度瓦片矩阵集,0~23 级,256 像素瓦片,0 级为东西两个瓦片
+    pub fn world_crs84_quad() -> Self {
+        let tile_matrices = (0..=23)
+            .map(|z| {
+                let n = 1_u32 << z;
+                TileMatrix {
+                    id: z.to_string(),
+                    cell_size: 180.0 / 256.0 / n as f64,
+                    origin: (-180.0, 90.0),
+                    corner_of_origin: CornerOfOrigin::TopLeft,
+                    tile_width: 256,
+                    tile_height: 256,
+                    matrix_width: 2 * n,
+                    matrix_height: n,
+                }
+            })
+            .collect();
+        Self {
+            id: "WorldCRS84Quad".to_string(),
+            epsg: 4326,
+            tile_matrices,
+        }
+    }
+
+    /// 获取指定缩放级别的瓦片矩阵
+    pub fn tile_matrix(&self, z: u32) -> Option<&TileMatrix> {
+        self.tile_matrices.get(z as usize)
+    }
+
+    /// 最大缩放级别
+    pub fn max_zoom(&self) -> u32 {
+        self.tile_matrices.len().saturating_sub(1) as u32
+    }
+
+    /// 判断坐标系是否为地理坐标系(单位为度)
+    pub fn is_geographic(&self) -> Result<bool, ProjectionError> {
+        Ok(Proj::from_epsg_code(self.epsg)?.is_latlong())
+    }
+
+    /// 计算瓦片边界
+    ///
+    /// # 参数
+    /// * `z` - 缩放级别
+    /// * `x` - 瓦片列号
+    /// * `y` - 瓦片行号
+    ///
+    /// # 返回值
+    /// 返回瓦片在坐标系下的边界,如果索引无效则返回None
+    pub fn tile_bounds(&self, z: u32, x: u32, y: u32) -> Option<Region<f64>> {
+        self.tile_matrix(z)?.tile_bounds(x, y)
+    }
+
+    /// 计算坐标点所在的瓦片
+    ///
+    /// # 参数
+    /// * `z` - 缩放级别
+    /// * `x`, `y` - 坐标系下的坐标
+    ///
+    /// # 返回值
+    /// 返回瓦片索引 (列, 行),如果缩放级别无效或坐标位于矩阵范围外则返回None
+    pub fn tile_for_point(&self, z: u32, x: f64, y: f64) -> Option<(u32, u32)> {
+        self.tile_matrix(z)?.tile_for_point(x, y)
+    }
+
+    /// 计算区域在指定缩放级别覆盖的瓦片范围
+    ///
+    /// # 参数
+    /// * `z` - 缩放级别
+    /// * `bounds` - 坐标系下的区域
+    ///
+    /// # 返回值
+    /// 返回 `[min, max)` 区间的瓦片范围,如果缩放级别无效或区域与矩阵不相交则返回None
+    pub fn tile_range(&self, z: u32, bounds: &Region<f64>) -> Option<Region<u32>> {
+        self.tile_matrix(z)?.tile_range(bounds)
+    }
+
+    /// 计算区域在指定缩放级别覆盖的瓦片范围,支持环绕的区域
+    ///
+    /// 地理坐标系和 Web 墨卡托中跨越 ±180° 经线的区域(x 轴最大值超过周期边界)
+    /// 拆分为东西两部分分别计算
+    ///
+    /// # 参数
+    /// * `z` - 缩放级别
+    /// * `bounds` - 坐标系下的区域
+    ///
+    /// # 返回值
+    /// 返回 `[min, max)` 区间的瓦片范围列表,缩放级别无效或区域与矩阵不相交时为空
+    pub fn tile_ranges(&self, z: u32, bounds: &Region<f64>) -> Vec<Region<u32>> {
+        let parts = match self.wrap_half_period() {
+            Some(half_period) => bounds.split_wrapped(half_period),
+            None => vec![*bounds],
+        };
+        parts
+            .iter()
+            .filter_map(|part| self.tile_range(z, part))
+            .collect()
+    }
+
+    /// 坐标系 x 轴的半个周期(坐标系单位),不循环时返回None
+    fn wrap_half_period(&self) -> Option<f64> {
+        let half_period = projection::wrap_half_period(self.epsg).ok()??;
+        if self.is_geographic().ok()? {
+            Some(half_period.to_degrees())
+        } else {
+            Some(half_period)
+        }
+    }
+
+    /// 计算区域的缩放级别范围
+    ///
+    /// 最小级别为整个区域仍位于一个瓦片内的最大级别,
+    /// 最大级别为像素尺寸不大于原始分辨率的最小级别
+    ///
+    /// # 参数
+    /// * `bounds` - 坐标系下的区域
+    /// * `cell_size` - 原始分辨率(坐标系单位)
+    ///
+    /// # 返回值
+    /// 返回 (最小缩放级别, 最大缩放级别)
+    pub fn zoom_range(&self, bounds: &Region<f64>, cell_size: f64) -> (u32, u32) {
+        let max_z = self
+            .tile_matrices
+            .iter()
+            .position(|m| m.cell_size <= cell_size * (1.0 + EPSILON))
+            .unwrap_or(self.tile_matrices.len().saturating_sub(1)) as u32;
+
+        let single_tile = |z: u32| {
+            let ranges = self.tile_ranges(z, bounds);
+            ranges.first().is_some_and(|first| {
+                ranges.iter().all(|r| r == first)
+                    && first.x.max - first.x.min == 1
+                    && first.y.max - first.y.min == 1
+            })
+        };
+        let min_z = (0..=max_z)
+            .take_while(|z| single_tile(*z))
+            .last()
+            .unwrap_or(0);
+        (min_z, max_z)
+    }
+
+    /// 计算区域在缩放级别范围内的所有瓦片索引
+    ///
+    /// # 参数
+    /// * `bounds` - 坐标系下的区域
+    /// * `cell_size` - 原始分辨率(坐标系单位)
+    ///
+    /// # 返回值
+    /// 返回包含所有瓦片索引的向量,每个索引为(x, y, z)元组
+    pub fn tile_tree_indices(&self, bounds: &Region<f64>, cell_size: f64) -> Vec<(u32, u32, u32)> {
+        let (min_z, max_z) = self.zoom_range(bounds, cell_size);
+        let mut tree = vec![];
+        for z in min_z..=max_z {
+            for range in self.tile_ranges(z, bounds) {
+                for y in range.y.min..range.y.max {
+                    for x in range.x.min..range.x.max {
+                        tree.push((x, y, z));
+                    }
+                }
+            }
+        }
+        tree
+    }
+
+    /// 从 OGC TMS 2.0 JSON 加载瓦片矩阵集
+    ///
+    /// 坐标系支持 EPSG 和 OGC CRS84 的 URI 或 URN,原点坐标按 `orderedAxes` 的轴顺序解析
+    ///
+    /// # 参数
+    /// * `json` - TileMatrixSet JSON 文本
+    ///
+    /// # 错误
+    /// JSON 格式错误、坐标系无法识别或瓦片矩阵无效时返回错误
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, TileMatrixSetError> {
+        let raw: json::RawTileMatrixSet = serde_json::from_str(json)?;
+        raw.try_into()
+    }
+}
+
+/// 从坐标系 URI 或 URN 中解析 EPSG 代码
+///
+/// # 返回值
+/// 返回 (EPSG 代码, 是否为纬度在前的轴顺序)
+#[cfg(feature = "json")]
+fn parse_crs(crs: &str) -> Result<(u16, bool), TileMatrixSetError> {
+    if crs.contains("CRS84") {
+        return Ok((4326, false));
+    }
+    let code = crs
+        .rsplit(['/', ':'])
+        .next()
+        .and_then(|code| code.parse::<u16>().ok())
+        .filter(|_| crs.to_uppercase().contains("EPSG"))
+        .ok_or_else(|| TileMatrixSetError::UnsupportedCrs(crs.to_string()))?;
+    // EPSG:4326 的定义轴顺序为 (纬度, 经度)
+    Ok((code, code == 4326))
+}
+
+impl CloudTiff {
+    /// 计算影像在瓦片矩阵集中的边界和缩放级别范围
+    ///
+    /// # 参数
+    /// * `tms` - 瓦片矩阵集
+    ///
+    /// # 返回
+    /// 返回元组 (瓦片矩阵集坐标系下的边界, (最小缩放级别, 最大缩放级别))
+    ///
+    /// # 错误
+    /// 坐标系无法识别时返回错误
+    pub fn bounds_tms(
+        &self,
+        tms: &TileMatrixSet,
+    ) -> Result<(Region<f64>, (u32, u32)), ProjectionError> {
+        let (bounds, cell_size) = self.footprint_tms(tms)?;
+        Ok((bounds, tms.zoom_range(&bounds, cell_size)))
+    }
+
+    /// 计算影像在瓦片矩阵集中所有缩放级别的瓦片索引
+    ///
+    /// # 参数
+    /// * `tms` - 瓦片矩阵集
+    ///
+    /// # 返回
+    /// 返回包含所有瓦片索引的向量,每个索引为(x, y, z)元组
+    ///
+    /// # 错误
+    /// 坐标系无法识别时返回错误
+    pub fn tile_tree_indices(
+        &self,
+        tms: &TileMatrixSet,
+    ) -> Result<Vec<(u32, u32, u32)>, ProjectionError> {
+        let (bounds, cell_size) = self.footprint_tms(tms)?;
+        Ok(tms.tile_tree_indices(&bounds, cell_size))
+    }
+
+    /// 计算影像在瓦片矩阵集坐标系下的边界和原始分辨率
+    fn footprint_tms(&self, tms: &TileMatrixSet) -> Result<(Region<f64>, f64), ProjectionError> {
+        let mut bounds = self.projection.bounds(tms.epsg);
+        // 投影使用弧度,瓦片矩阵集使用度
+        if tms.is_geographic()? {
+            bounds = bounds * 1_f64.to_degrees();
+        }
+        let (width, height) = self.full_dimensions();
+        let cell_size = (bounds.x.range() / width as f64).min(bounds.y.range() / height as f64);
+        Ok((bounds, cell_size))
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::*;
+    use serde::Deserialize;
+
+    /// TMS 2.0 JSON 中的坐标系,可以是 URI 字符串或包含 URI 的对象
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum RawCrs {
+        Uri(String),
+        Object { uri: String },
+    }
+
+    /// TMS 2.0 JSON 中的瓦片矩阵
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct RawTileMatrix {
+        id: String,
+        cell_size: f64,
+        #[serde(default)]
+        corner_of_origin: Option<String>,
+        point_of_origin: [f64; 2],
+        tile_width: u32,
+        tile_height: u32,
+        matrix_width: u32,
+        matrix_height: u32,
+    }
+
+    /// TMS 2.0 JSON 中的瓦片矩阵集
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct RawTileMatrixSet {
+        #[serde(default)]
+        id: Option<String>,
+        crs: RawCrs,
+        #[serde(default)]
+        ordered_axes: Option<Vec<String>>,
+        tile_matrices: Vec<RawTileMatrix>,
+    }
+
+    impl TryFrom<RawTileMatrixSet> for TileMatrixSet {
+        type Error = TileMatrixSetError;
+
+        fn try_from(raw: RawTileMatrixSet) -> Result<Self, Self::Error> {
+            let crs = match raw.crs {
+                RawCrs::Uri(uri) | RawCrs::Object { uri } => uri,
+            };
+            let (epsg, lat_first) = parse_crs(&crs)?;
+            // 显式的轴顺序优先于坐标系的默认轴顺序
+            let northing_first = match &raw.ordered_axes {
+                Some(axes) => axes.first().is_some_and(|axis| {
+                    matches!(
+                        axis.to_uppercase().as_str(),
+                        "LAT" | "LATITUDE" | "N" | "NORTHING" | "Y"
+                    )
+                }),
+                None => lat_first,
+            };
+
+            if raw.tile_matrices.is_empty() {
+                return Err(TileMatrixSetError::NoTileMatrices);
+            }
+            let tile_matrices = raw
+                .tile_matrices
+                .into_iter()
+                .map(|m| {
+                    let corner_of_origin = match m.corner_of_origin.as_deref() {
+                        None | Some("topLeft") => CornerOfOrigin::TopLeft,
+                        Some("bottomLeft") => CornerOfOrigin::BottomLeft,
+                        Some(_) => return Err(TileMatrixSetError::InvalidTileMatrix(m.id)),
+                    };
+                    let valid = m.cell_size > 0.0
+                        && m.tile_width > 0
+                        && m.tile_height > 0
+                        && m.matrix_width > 0
+                        && m.matrix_height > 0;
+                    if !valid {
+                        return Err(TileMatrixSetError::InvalidTileMatrix(m.id));
+                    }
+                    let [a, b] = m.point_of_origin;
+                    Ok(TileMatrix {
+                        id: m.id,
+                        cell_size: m.cell_size,
+                        origin: if northing_first { (b, a) } else { (a, b) },
+                        corner_of_origin,
+                        tile_width: m.tile_width,
+                        tile_height: m.tile_height,
+                        matrix_width: m.matrix_width,
+                        matrix_height: m.matrix_height,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(TileMatrixSet {
+                id: raw.id.unwrap_or_default(),
+                epsg,
+                tile_matrices,
+            })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    /// 只有一个瓦片矩阵的 TMS 2.0 JSON
+    fn tms_json(crs: &str, extra: &str, matrix: &str) -> String {
+        format!(
+            r#"{{
+                "id": "Test",
+                "crs": "{crs}",
+                {extra}
+                "tileMatrices": [{{
+                    "id": "0",
+                    "tileWidth": 256,
+                    "tileHeight": 256,
+                    {matrix}
+                }}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn geographic_origin_is_swapped_to_lon_lat() {
+        // EPSG:4326 的定义轴顺序为 (纬度, 经度)
+        let json = tms_json(
+            "http://www.opengis.net/def/crs/EPSG/0/4326",
+            "",
+            r#""cellSize": 0.703125, "pointOfOrigin": [90, -180],
+               "matrixWidth": 2, "matrixHeight": 1"#,
+        );
+        let tms = TileMatrixSet::from_json(&json).unwrap();
+        assert_eq!(tms.id, "Test");
+        assert_eq!(tms.epsg, 4326);
+        assert_eq!(tms.tile_matrices[0].origin, (-180.0, 90.0));
+        assert_eq!(
+            tms.tile_bounds(0, 1, 0),
+            Some(Region::new(0.0, -90.0, 180.0, 90.0))
+        );
+        assert_eq!(tms.tile_for_point(0, -10.0, 45.0), Some((0, 0)));
+    }
+
+    #[test]
+    fn crs84_keeps_lon_lat_order() {
+        let json = tms_json(
+            "http://www.opengis.net/def/crs/OGC/1.3/CRS84",
+            "",
+            r#""cellSize": 0.703125, "pointOfOrigin": [-180, 90],
+               "matrixWidth": 2, "matrixHeight": 1"#,
+        );
+        let tms = TileMatrixSet::from_json(&json).unwrap();
+        assert_eq!(tms.epsg, 4326);
+        assert_eq!(tms.tile_matrices[0].origin, (-180.0, 90.0));
+    }
+
+    #[test]
+    fn ordered_axes_override_default_order() {
+        // 显式的 (东, 北) 轴顺序不交换
+        let json = tms_json(
+            "urn:ogc:def:crs:EPSG::32633",
+            r#""orderedAxes": ["E", "N"],"#,
+            r#""cellSize": 10, "pointOfOrigin": [500000, 6000000],
+               "matrixWidth": 4, "matrixHeight": 4"#,
+        );
+        let tms = TileMatrixSet::from_json(&json).unwrap();
+        assert_eq!(tms.epsg, 32633);
+        assert_eq!(tms.tile_matrices[0].origin, (500000.0, 6000000.0));
+
+        // (北, 东) 轴顺序交换
+        let json = tms_json(
+            "urn:ogc:def:crs:EPSG::32633",
+            r#""orderedAxes": ["N", "E"],"#,
+            r#""cellSize": 10, "pointOfOrigin": [6000000, 500000],
+               "matrixWidth": 4, "matrixHeight": 4"#,
+        );
+        let tms = TileMatrixSet::from_json(&json).unwrap();
+        assert_eq!(tms.tile_matrices[0].origin, (500000.0, 6000000.0));
+    }
+
+    #[test]
+    fn bottom_left_origin() {
+        let json = tms_json(
+            "http://www.opengis.net/def/crs/EPSG/0/32633",
+            "",
+            r#""cellSize": 10, "pointOfOrigin": [500000, 6000000],
+               "cornerOfOrigin": "bottomLeft",
+               "matrixWidth": 4, "matrixHeight": 3"#,
+        );
+        let tms = TileMatrixSet::from_json(&json).unwrap();
+        let matrix = &tms.tile_matrices[0];
+        assert_eq!(matrix.corner_of_origin, CornerOfOrigin::BottomLeft);
+        assert_eq!(
+            matrix.bounds(),
+            Region::new(500000.0, 6000000.0, 510240.0, 6007680.0)
+        );
+        // 行号向北增加
+        assert_eq!(
+            tms.tile_bounds(0, 1, 2),
+            Some(Region::new(502560.0, 6005120.0, 505120.0, 6007680.0))
+        );
+        assert_eq!(tms.tile_for_point(0, 502561.0, 6005121.0), Some((1, 2)));
+        assert_eq!(tms.tile_for_point(0, 500001.0, 6000001.0), Some((0, 0)));
+        assert_eq!(tms.tile_for_point(0, 500001.0, 5999999.0), None);
+        assert_eq!(tms.tile_bounds(0, 0, 3), None);
+    }
+
+    #[test]
+    fn invalid_tile_matrices_are_rejected() {
+        let invalid = |matrix: &str| {
+            let json = tms_json("http://www.opengis.net/def/crs/EPSG/0/3857", "", matrix);
+            TileMatrixSet::from_json(&json)
+        };
+        assert!(matches!(
+            invalid(
+                r#""cellSize": 0, "pointOfOrigin": [0, 0], "matrixWidth": 1, "matrixHeight": 1"#
+            ),
+            Err(TileMatrixSetError::InvalidTileMatrix(id)) if id == "0"
+        ));
+        assert!(matches!(
+            invalid(
+                r#""cellSize": 1, "pointOfOrigin": [0, 0], "matrixWidth": 0, "matrixHeight": 1"#
+            ),
+            Err(TileMatrixSetError::InvalidTileMatrix(_))
+        ));
+        assert!(matches!(
+            invalid(
+                r#""cellSize": 1, "pointOfOrigin": [0, 0], "cornerOfOrigin": "bottomRight",
+                   "matrixWidth": 1, "matrixHeight": 1"#
+            ),
+            Err(TileMatrixSetError::InvalidTileMatrix(_))
+        ));
+
+        let json = r#"{"crs": "http://www.opengis.net/def/crs/EPSG/0/3857", "tileMatrices": []}"#;
+        assert!(matches!(
+            TileMatrixSet::from_json(json),
+            Err(TileMatrixSetError::NoTileMatrices)
+        ));
+        let json = tms_json(
+            "http://www.opengis.net/def/crs/OGC/0/Unknown",
+            "",
+            r#""cellSize": 1, "pointOfOrigin": [0, 0], "matrixWidth": 1, "matrixHeight": 1"#,
+        );
+        assert!(matches!(
+            TileMatrixSet::from_json(&json),
+            Err(TileMatrixSetError::UnsupportedCrs(_))
+        ));
+    }
+}
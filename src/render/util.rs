@@ -3,11 +3,12 @@
 //! 本模块提供了用于渲染云优化地理影像(COG)的工具函数集合。
 //! 主要包括:
 //! - 渲染层级选择
-//! - 像素映射计算
+//! - 像素映射计算(输出区域或任意仿射像素网格)
 //! - 分辨率控制
 //! - 瓦片信息获取
 
 use crate::cog::{CloudTiff, CloudTiffResult, Level};
+use crate::projection::geo_transform::GeoTransform;
 use crate::projection::{Projection, ProjectionError};
 use crate::CloudTiffError;
 use crate::{Region, UnitFloat};
@@ -158,43 +159,80 @@ pub fn project_pixel_map(
     epsg: u16,
     region: &Region<f64>,
     dimensions: &(u32, u32),
+) -> CloudTiffResult<PixelMap> {
+    let grid = GeoTransform::from_region(region, *dimensions);
+    project_pixel_map_grid(level, projection, epsg, &grid, dimensions, None)
+}
+
+/// 计算任意输出像素网格的像素映射关系
+///
+/// # 参数
+/// * `level` - 图像层级
+/// * `projection` - 输入投影
+/// * `epsg` - 输出投影EPSG代码
+/// * `grid` - 输出像素网格的仿射变换(投影使用的单位,地理坐标系为弧度)
+/// * `dimensions` - 输出尺寸
+/// * `tolerance` - 近似变换的误差容限(层级像素),None 时逐像素精确投影
+///
+/// # 返回
+/// 返回像素映射或错误
+pub fn project_pixel_map_grid(
+    level: &Level,
+    projection: &Projection,
+    epsg: u16,
+    grid: &GeoTransform,
+    dimensions: &(u32, u32),
+    tolerance: Option<f64>,
 ) -> CloudTiffResult<PixelMap> {
     let mut pixel_map = HashMap::new();
     // 创建输出投影
-    let output_proj = Proj::from_epsg_code(epsg).map_err(|e| ProjectionError::from(e))?;
-
-    // 计算像素步长
-    let dxdi = region.x.range() / dimensions.0 as f64;
-    let dydj = region.y.range() / dimensions.1 as f64;
-
-    // 遍历输出像素
-    for j in 0..dimensions.1 {
-        for i in 0..dimensions.0 {
-            // 计算输出坐标
-            let x = region.x.min + dxdi * i as f64;
-            let y = region.y.max - dydj * j as f64;
-
-            // 投影转换并记录映射关系
-            match projection.transform_from_proj(&output_proj, x, y, 0.0) {
-                Ok((u, v, ..)) => {
-                    // 尝试获取图像坐标对应的瓦片索引和瓦片内坐标
-                    if let Ok((tile_index, tile_x, tile_y)) = level.index_from_image_coords(u, v) {
-                        // 获取或创建瓦片的像素映射列表
-                        let tile_pixel_map = pixel_map.entry(tile_index).or_insert(vec![]);
-                        // 添加瓦片内坐标到输出坐标的映射
-                        tile_pixel_map.push(((tile_x, tile_y), (i, j)));
-                    }
-                }
-                Err(e) => warn!("像素转换失败: {e:?}"), // 记录投影转换失败的警告
+    let output_proj = Proj::from_epsg_code(epsg).map_err(ProjectionError::from)?;
+
+    // 输出像素对应的层级像素坐标
+    let (level_width, level_height) = (level.width() as f64, level.height() as f64);
+    let source = |i: u32, j: u32| {
+        // 计算输出像素中心的坐标
+        let (x, y) = grid.apply(i as f64 + 0.5, j as f64 + 0.5);
+        // 投影转换
+        match projection.transform_from_proj(&output_proj, x, y, 0.0) {
+            Ok((u, v, ..)) => Some((u * level_width, v * level_height)),
+            Err(e) => {
+                warn!("像素转换失败: {e:?}"); // 记录投影转换失败的警告
+                None
             }
         }
+    };
+    let coords = match tolerance {
+        Some(tolerance) => ApproxGrid::new(&source, *dimensions, tolerance).coords(),
+        None => (0..dimensions.1)
+            .flat_map(|j| (0..dimensions.0).map(move |i| (i, j)))
+            .map(|(i, j)| source(i, j))
+            .collect(),
+    };
+
+    // 记录映射关系
+    for (index, coord) in coords.into_iter().enumerate() {
+        let Some((x, y)) = coord else {
+            continue;
+        };
+        let i = (index % dimensions.0 as usize) as u32;
+        let j = (index / dimensions.0 as usize) as u32;
+        // 尝试获取图像坐标对应的瓦片索引和瓦片内坐标
+        if let Ok((tile_index, tile_x, tile_y)) =
+            level.index_from_image_coords(x / level_width, y / level_height)
+        {
+            // 获取或创建瓦片的像素映射列表
+            let tile_pixel_map = pixel_map.entry(tile_index).or_insert(vec![]);
+            // 添加瓦片内坐标到输出坐标的映射
+            tile_pixel_map.push(((tile_x, tile_y), (i, j)));
+        }
     }
 
     // 检查映射结果
     // 如果像素映射为空，说明请求的区域完全超出了图像范围
     if pixel_map.is_empty() {
         return Err(CloudTiffError::RegionOutOfBounds((
-            region.as_tuple(),
+            grid.bounds(*dimensions).as_tuple(),
             projection.bounds_in_proj(&output_proj)?.as_tuple(),
         )));
     }
@@ -202,3 +240,199 @@ pub fn project_pixel_map(
     // 映射结果有效，返回像素映射
     Ok(pixel_map)
 }
+
+/// 近似变换的初始块边长(输出像素)
+const APPROX_BLOCK_SIZE: u32 = 64;
+
+/// 近似变换网格
+///
+/// 与 GDAL 的近似变换器类似:对每个块只精确投影四个角点,
+/// 并检查四条边中点和块中心的双线性插值误差。误差不超过容限时块内像素全部插值,
+/// 否则将块四等分后递归处理,块缩小到 2x2 像素时逐像素精确投影。
+/// 任一检查点投影失败时同样细分,使影像边缘和投影有效范围的边界保持精确
+struct ApproxGrid<'a, F> {
+    /// 精确投影函数,输入输出像素坐标,返回层级像素坐标
+    source: &'a F,
+    /// 输出尺寸
+    dimensions: (u32, u32),
+    /// 允许的最大误差(层级像素)
+    tolerance: f64,
+    /// 已精确投影的点
+    exact: HashMap<(u32, u32), Option<(f64, f64)>>,
+    /// 每个输出像素的层级像素坐标
+    coords: Vec<Option<(f64, f64)>>,
+}
+
+impl<'a, F: Fn(u32, u32) -> Option<(f64, f64)>> ApproxGrid<'a, F> {
+    /// 创建近似变换网格
+    fn new(source: &'a F, dimensions: (u32, u32), tolerance: f64) -> Self {
+        Self {
+            source,
+            dimensions,
+            tolerance,
+            exact: HashMap::new(),
+            coords: vec![None; dimensions.0 as usize * dimensions.1 as usize],
+        }
+    }
+
+    /// 计算所有输出像素的层级像素坐标
+    fn coords(mut self) -> Vec<Option<(f64, f64)>> {
+        let (width, height) = self.dimensions;
+        if width == 0 || height == 0 {
+            return self.coords;
+        }
+        // 相邻的块共享边界上的像素
+        for j0 in (0..height).step_by(APPROX_BLOCK_SIZE as usize) {
+            for i0 in (0..width).step_by(APPROX_BLOCK_SIZE as usize) {
+                let i1 = (i0 + APPROX_BLOCK_SIZE).min(width - 1);
+                let j1 = (j0 + APPROX_BLOCK_SIZE).min(height - 1);
+                self.block((i0, j0), (i1, j1));
+            }
+        }
+        self.coords
+    }
+
+    /// 精确投影一个点,结果会被缓存
+    fn exact(&mut self, i: u32, j: u32) -> Option<(f64, f64)> {
+        let source = self.source;
+        *self.exact.entry((i, j)).or_insert_with(|| source(i, j))
+    }
+
+    /// 处理 `[min, max]` 范围内(包含边界)的像素块
+    fn block(&mut self, min: (u32, u32), max: (u32, u32)) {
+        let ((i0, j0), (i1, j1)) = (min, max);
+        // 块足够小时逐像素精确投影
+        if i1 - i0 <= 1 && j1 - j0 <= 1 {
+            for j in j0..=j1 {
+                for i in i0..=i1 {
+                    self.coords[j as usize * self.dimensions.0 as usize + i as usize] =
+                        self.exact(i, j);
+                }
+            }
+            return;
+        }
+
+        let corners = [
+            self.exact(i0, j0),
+            self.exact(i1, j0),
+            self.exact(i0, j1),
+            self.exact(i1, j1),
+        ];
+        if let [Some(a), Some(b), Some(c), Some(d)] = corners {
+            // 块内的双线性插值
+            let interpolate = |i: u32, j: u32| {
+                let s = if i1 > i0 {
+                    (i - i0) as f64 / (i1 - i0) as f64
+                } else {
+                    0.0
+                };
+                let t = if j1 > j0 {
+                    (j - j0) as f64 / (j1 - j0) as f64
+                } else {
+                    0.0
+                };
+                let lerp = |p: (f64, f64), q: (f64, f64), w: f64| {
+                    (p.0 + (q.0 - p.0) * w, p.1 + (q.1 - p.1) * w)
+                };
+                lerp(lerp(a, b, s), lerp(c, d, s), t)
+            };
+
+            // 检查四条边中点和块中心的插值误差
+            let (im, jm) = ((i0 + i1) / 2, (j0 + j1) / 2);
+            let checks = [(im, j0), (im, j1), (i0, jm), (i1, jm), (im, jm)];
+            let within_tolerance = checks.into_iter().all(|(i, j)| {
+                self.exact(i, j).is_some_and(|p| {
+                    let q = interpolate(i, j);
+                    (p.0 - q.0).hypot(p.1 - q.1) <= self.tolerance
+                })
+            });
+            if within_tolerance {
+                for j in j0..=j1 {
+                    for i in i0..=i1 {
+                        self.coords[j as usize * self.dimensions.0 as usize + i as usize] =
+                            Some(interpolate(i, j));
+                    }
+                }
+                return;
+            }
+        }
+
+        // 四等分后递归处理
+        let (im, jm) = ((i0 + i1) / 2, (j0 + j1) / 2);
+        let columns = if i1 - i0 > 1 {
+            vec![(i0, im), (im, i1)]
+        } else {
+            vec![(i0, i1)]
+        };
+        let rows = if j1 - j0 > 1 {
+            vec![(j0, jm), (jm, j1)]
+        } else {
+            vec![(j0, j1)]
+        };
+        for &(j0, j1) in &rows {
+            for &(i0, i1) in &columns {
+                self.block((i0, j0), (i1, j1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proj4rs::transform::transform;
+
+    #[test]
+    fn approx_grid_matches_exact_transform() {
+        // 南极立体投影(EPSG:3031)的输出网格,对应经纬度网格上的像素(每像素 0.1°)
+        let from = Proj::from_epsg_code(3031).unwrap();
+        let to = Proj::from_epsg_code(4326).unwrap();
+        let dimensions = (300, 200);
+        let source = |i: u32, j: u32| {
+            let x = 1_000_000.0 + (i as f64 + 0.5) * 10_000.0;
+            let y = 3_000_000.0 - (j as f64 + 0.5) * 10_000.0;
+            let mut point = (x, y, 0.0);
+            transform(&from, &to, &mut point).ok()?;
+            Some((
+                (point.0.to_degrees() + 180.0) / 0.1,
+                (90.0 - point.1.to_degrees()) / 0.1,
+            ))
+        };
+
+        let tolerance = 0.125;
+        let calls = std::cell::Cell::new(0);
+        let counted = |i: u32, j: u32| {
+            calls.set(calls.get() + 1);
+            source(i, j)
+        };
+        let approx = ApproxGrid::new(&counted, dimensions, tolerance).coords();
+        assert_eq!(approx.len(), 300 * 200);
+        // 大部分像素由插值得到
+        assert!(
+            calls.get() < approx.len() / 10,
+            "{} 次精确投影",
+            calls.get()
+        );
+        for (index, coord) in approx.into_iter().enumerate() {
+            let (i, j) = (index as u32 % dimensions.0, index as u32 / dimensions.0);
+            let p = source(i, j).unwrap();
+            let q = coord.unwrap();
+            assert!(
+                (p.0 - q.0).hypot(p.1 - q.1) <= tolerance,
+                "({i}, {j}): {p:?} != {q:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn approx_grid_empty() {
+        let source = |i: u32, j: u32| Some((i as f64, j as f64));
+        assert!(ApproxGrid::new(&source, (0, 0), 0.125).coords().is_empty());
+        assert!(ApproxGrid::new(&source, (0, 10), 0.125).coords().is_empty());
+        assert!(ApproxGrid::new(&source, (10, 0), 0.125).coords().is_empty());
+        assert_eq!(
+            ApproxGrid::new(&source, (1, 1), 0.125).coords(),
+            [Some((0.0, 0.0))]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! 重投影(warp)模块
+//!
+//! 本模块提供将 COG 重投影到任意像素网格的功能。与输出区域渲染只能指定外包区域和分辨率不同,
+//! 重投影的目标由坐标系、仿射变换(原点、像素尺寸和可选的旋转)和输出尺寸完整描述,
+//! 结果为带有地理参考的栅格,可以直接交给 [`crate::Encoder::from_geo_raster`] 编码为 COG。
+//!
+//! # 示例
+//!
+//! ```no_run
+//! use cloudtiff::{Encoder, GeoTransform, Resampling};
+//! use std::fs::File;
+//!
+//! # fn example(cog: &cloudtiff::CloudTiff) {
+//! // 重投影到 UTM 10N,像素尺寸 30 米
+//! let grid = GeoTransform::new((500_000.0, 5_500_000.0), (30.0, -30.0));
+//! let warped = cog
+//!     .renderer()
+//!     .with_reader(File::open("image.tif").unwrap())
+//!     .with_resampling(Resampling::Bilinear)
+//!     .render_warp(32610, grid, (1024, 1024))
+//!     .unwrap();
+//! let mut output = File::create("utm.tif").unwrap();
+//! Encoder::from_geo_raster(warped).unwrap().encode(&mut output).unwrap();
+//! # }
+//! ```
+
+use super::{CloudTiffResult, RenderBuilder, RenderRegion, SyncReader};
+use crate::projection::geo_transform::GeoTransform;
+use crate::projection::{Projection, ProjectionError};
+use crate::raster::Raster;
+use crate::Region;
+use proj4rs::Proj;
+
+/// 带有地理参考的栅格
+#[derive(Clone, Debug)]
+pub struct GeoRaster {
+    /// 栅格数据
+    pub raster: Raster,
+    /// 坐标系 EPSG 代码
+    pub epsg: u16,
+    /// 像素网格的仿射变换,地理坐标系使用度
+    pub geo_transform: GeoTransform,
+}
+
+impl GeoRaster {
+    /// 获取栅格的投影对象
+    ///
+    /// # 错误
+    /// EPSG 代码不受支持或仿射变换包含旋转项时返回错误
+    pub fn projection(&self) -> Result<Projection, ProjectionError> {
+        self.geo_transform
+            .to_projection(self.epsg, self.raster.dimensions)
+    }
+
+    /// 获取栅格在其坐标系下的外包区域,地理坐标系使用度
+    pub fn bounds(&self) -> Region<f64> {
+        self.geo_transform.bounds(self.raster.dimensions)
+    }
+}
+
+impl<'a> RenderBuilder<'a, SyncReader> {
+    /// 将影像重投影到任意像素网格
+    ///
+    /// 输出像素 `(i, j)` 覆盖仿射变换下 `[i, i+1) x [j, j+1)` 的范围,
+    /// 位于影像外的像素为无数据值(Alpha 波段为透明)。重采样方法、地形分析、对比度拉伸和颜色映射与其他渲染方式相同
+    ///
+    /// # 参数
+    /// * `epsg` - 目标坐标系 EPSG 代码
+    /// * `geo_transform` - 目标像素网格的仿射变换,地理坐标系使用度
+    /// * `dimensions` - 输出尺寸 (宽度, 高度)
+    ///
+    /// # 返回
+    /// 带有地理参考的栅格
+    ///
+    /// # 错误
+    /// 坐标系不受支持、网格与影像不相交、瓦片读取或投影转换失败时返回错误
+    pub fn render_warp(
+        self,
+        epsg: u16,
+        geo_transform: GeoTransform,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<GeoRaster> {
+        let raster = self
+            .with_output_grid(epsg, &geo_transform, dimensions)?
+            .render()?;
+        Ok(GeoRaster {
+            raster,
+            epsg,
+            geo_transform,
+        })
+    }
+}
+
+impl<R> RenderBuilder<'_, R> {
+    /// 设置输出像素网格,地理坐标系的仿射变换从度转换为投影使用的弧度
+    fn with_output_grid(
+        self,
+        epsg: u16,
+        geo_transform: &GeoTransform,
+        dimensions: (u32, u32),
+    ) -> CloudTiffResult<Self> {
+        let proj = Proj::from_epsg_code(epsg).map_err(ProjectionError::from)?;
+        let grid = if proj.is_latlong() {
+            geo_transform.scaled(1_f64.to_radians())
+        } else {
+            *geo_transform
+        };
+        Ok(RenderBuilder {
+            region: RenderRegion::OutputGrid((epsg, grid)),
+            resolution: dimensions,
+            ..self
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+mod not_sync {
+    use super::super::AsyncReader;
+    use super::*;
+
+    impl<'a> RenderBuilder<'a, AsyncReader> {
+        /// 异步将影像重投影到任意像素网格
+        ///
+        /// 与 [`RenderBuilder::render_warp`] 相同,但使用异步IO操作
+        ///
+        /// # 参数
+        /// * `epsg` - 目标坐标系 EPSG 代码
+        /// * `geo_transform` - 目标像素网格的仿射变换,地理坐标系使用度
+        /// * `dimensions` - 输出尺寸 (宽度, 高度)
+        pub async fn render_warp_async(
+            self,
+            epsg: u16,
+            geo_transform: GeoTransform,
+            dimensions: (u32, u32),
+        ) -> CloudTiffResult<GeoRaster> {
+            let raster = self
+                .with_output_grid(epsg, &geo_transform, dimensions)?
+                .render_async()
+                .await?;
+            Ok(GeoRaster {
+                raster,
+                epsg,
+                geo_transform,
+            })
+        }
+    }
+}
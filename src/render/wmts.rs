@@ -1,14 +1,19 @@
 //! WMTS (Web Map Tile Service) 相关功能模块
-//! 
+//!
 //! 本模块提供了处理 WMTS 瓦片坐标系统的功能,包括坐标转换和瓦片索引计算等。
+//!
+//! 本模块的函数固定使用 GoogleMapsCompatible (WebMercatorQuad) 瓦片方案,
+//! 其他瓦片方案请使用 [`super::tms::TileMatrixSet`]。
 
 use crate::{Point2D, Region};
 use std::f64::consts::{PI, TAU};
 
 /// Web墨卡托投影支持的最大纬度(度)
 pub const MAX_LAT_DEG: f64 = 85.06;
-/// Web墨卡托投影支持的最小纬度(度) 
+/// Web墨卡托投影支持的最小纬度(度)
 pub const MIN_LAT_DEG: f64 = -85.06;
+/// Web墨卡托投影 x 和 y 方向的最大坐标(米),即赤道周长的一半
+pub const WEB_MERCATOR_EXTENT: f64 = 20_037_508.342_789_244;
 
 /// 计算给定边界范围内的所有瓦片索引
 ///
@@ -24,19 +29,37 @@ pub fn tile_tree_indices(
     dimensions: (u32, u32),
     tile_dim: (u32, u32),
 ) -> Vec<(u32, u32, u32)> {
-    let mut tree = vec![];
     // 获取WMTS边界和缩放级别范围
     let (bounds, (min_z, max_z)) = bounds_wmts(bounds_lat_lon_deg, dimensions, tile_dim);
+    tile_indices_in_zoom_range(bounds, (min_z, max_z))
+}
 
+/// 计算缩放级别0的边界在指定缩放级别范围内覆盖的所有瓦片索引
+///
+/// # 参数
+/// * `z0_bounds` - 缩放级别0时的边界(瓦片坐标),参见 [`bounds_wmts`],
+///   x 轴最大值可以超过 1(跨越 ±180° 经线)
+/// * `zoom_range` - (最小缩放级别, 最大缩放级别)
+///
+/// # 返回值
+/// 返回包含所有瓦片索引的向量,每个索引为(x, y, z)元组
+pub fn tile_indices_in_zoom_range(
+    z0_bounds: Region<f64>,
+    zoom_range: (u32, u32),
+) -> Vec<(u32, u32, u32)> {
+    let mut tree = vec![];
     // 遍历每个缩放级别
-    for z in min_z..=max_z {
+    for z in zoom_range.0..=zoom_range.1 {
         // 计算当前缩放级别的瓦片边界
-        let tile_bounds = bounds * 2_f64.powi(z as i32);
+        let tile_bounds = z0_bounds * 2_f64.powi(z as i32);
+        // 跨越 ±180° 经线的边界超出瓦片范围,超出部分绕回西侧,每列最多出现一次
+        let n = 1_u32 << z;
+        let columns = tile_bounds.x.min.floor() as u32..tile_bounds.x.max.ceil() as u32;
         // 遍历y轴瓦片索引
         for y in tile_bounds.y.min.floor() as u32..tile_bounds.y.max.ceil() as u32 {
             // 遍历x轴瓦片索引
-            for x in tile_bounds.x.min.floor() as u32..tile_bounds.x.max.ceil() as u32 {
-                tree.push((x, y, z));
+            for x in columns.clone().take(n as usize) {
+                tree.push((x % n, y, z));
             }
         }
     }
@@ -46,7 +69,7 @@ pub fn tile_tree_indices(
 /// 计算WMTS边界和缩放级别范围
 ///
 /// # 参数
-/// * `bounds_lat_lon_deg` - 经纬度边界范围(度)
+/// * `bounds_lat_lon_deg` - 经纬度边界范围(度),跨越 ±180° 经线时最大经度大于 180
 /// * `dimensions` - 输出图像尺寸
 /// * `tile_dim` - 瓦片尺寸
 ///
@@ -67,11 +90,11 @@ pub fn bounds_wmts(
     // 定义西北角和东南角坐标点
     let north_west = Point2D {
         x: bounds.x.min,
-        y: bounds.y.max,
+        y: max_lat,
     };
     let south_east = Point2D {
         x: bounds.x.max,
-        y: bounds.y.min,
+        y: min_lat,
     };
     // 将经纬度坐标转换为缩放级别0的瓦片索引
     let (min_x, min_y, _) = lat_lon_deg_to_tile_index(north_west, 0.0);
@@ -91,7 +114,7 @@ pub fn bounds_wmts(
     if (z_min_bounds.x.min.floor() != z_min_bounds.x.max.floor())
         || (z_min_bounds.y.min.floor() != z_min_bounds.y.max.floor())
     {
-        min_z -= 1;
+        min_z = min_z.saturating_sub(1);
     }
 
     // 计算最大缩放级别(瓦片分辨率>=原始分辨率)
@@ -131,6 +154,27 @@ pub fn tile_bounds_lat_lon_deg(x: u32, y: u32, z: u32) -> Option<Region<f64>> {
     Some(Region::new(nw.x, se.y, se.x, nw.y))
 }
 
+/// 计算瓦片在 Web 墨卡托(EPSG:3857)下的边界
+///
+/// # 参数
+/// * `x` - 瓦片X索引
+/// * `y` - 瓦片Y索引
+/// * `z` - 缩放级别
+///
+/// # 返回值
+/// 返回瓦片的边界区域(米),如果索引超出该缩放级别的范围则返回None
+pub fn tile_bounds_web_mercator(x: u32, y: u32, z: u32) -> Option<Region<f64>> {
+    let n = 2_f64.powi(z as i32);
+    if x as f64 >= n || y as f64 >= n {
+        return None;
+    }
+    // 每个瓦片的边长(米)
+    let size = 2.0 * WEB_MERCATOR_EXTENT / n;
+    let min_x = -WEB_MERCATOR_EXTENT + x as f64 * size;
+    let max_y = WEB_MERCATOR_EXTENT - y as f64 * size;
+    Some(Region::new(min_x, max_y - size, min_x + size, max_y))
+}
+
 /// 将瓦片索引转换为经纬度坐标
 ///
 /// # 参数
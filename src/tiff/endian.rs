@@ -22,6 +22,15 @@ pub enum Endian {
 }
 
 impl Endian {
+    /// 当前平台的字节序
+    pub const fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Endian::Big
+        } else {
+            Endian::Little
+        }
+    }
+
     /// 从流中读取指定大小的数据并按字节序解码
     ///
     /// # 参数
@@ -0,0 +1,232 @@
+//! 集成测试共用的本地 HTTP 服务器
+//!
+//! 用于在不访问网络的情况下测试远程读取器:每个请求交给测试提供的处理函数生成响应,
+//! 服务器记录收到的请求以及同时处理的最大请求数。
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 收到的 HTTP 请求
+#[derive(Clone, Debug)]
+pub struct Request {
+    /// 请求方法
+    pub method: String,
+    /// 请求路径(包括查询字符串)
+    pub path: String,
+    /// 请求头,名称为小写
+    pub headers: HashMap<String, String>,
+}
+
+impl Request {
+    /// 获取请求头
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(String::as_str)
+    }
+
+    /// 解析 `Range: bytes=start-end` 请求头
+    pub fn range(&self) -> Option<(u64, u64)> {
+        let range = self.header("range")?.strip_prefix("bytes=")?;
+        let (start, end) = range.split_once('-')?;
+        Some((start.parse().ok()?, end.parse().ok()?))
+    }
+}
+
+/// 要返回的 HTTP 响应
+#[derive(Clone, Debug)]
+pub struct Response {
+    /// 状态码
+    pub status: u16,
+    /// 响应头
+    pub headers: Vec<(String, String)>,
+    /// 响应体
+    pub body: Vec<u8>,
+    /// 发送响应前的等待时间
+    pub delay: Duration,
+}
+
+impl Response {
+    /// 创建没有响应体的响应
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: vec![],
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// 添加响应头
+    pub fn with_header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// 设置响应体
+    pub fn with_body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// 设置发送响应前的等待时间
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// 按请求的 Range 返回数据,没有 Range 时返回整个对象
+pub fn range_response(data: &[u8], request: &Request) -> Response {
+    match request.range() {
+        Some((start, end)) if (start as usize) < data.len() => {
+            let end = (end as usize).min(data.len() - 1);
+            Response::new(206)
+                .with_header(
+                    "Content-Range",
+                    format!("bytes {start}-{end}/{}", data.len()),
+                )
+                .with_body(data[start as usize..=end].to_vec())
+        }
+        Some(_) => {
+            Response::new(416).with_header("Content-Range", format!("bytes */{}", data.len()))
+        }
+        None => Response::new(200).with_body(data.to_vec()),
+    }
+}
+
+/// 请求处理函数
+type Handler = dyn Fn(&Request) -> Response + Send + Sync;
+
+/// 服务器的共享状态
+struct State {
+    /// 请求处理函数
+    handler: Box<Handler>,
+    /// 收到的请求
+    requests: Mutex<Vec<Request>>,
+    /// 正在处理的请求数
+    in_flight: AtomicUsize,
+    /// 同时处理的最大请求数
+    max_in_flight: AtomicUsize,
+}
+
+/// 本地 HTTP 服务器,在当前 tokio 运行时中运行
+pub struct TestServer {
+    /// 监听地址
+    addr: SocketAddr,
+    /// 共享状态
+    state: Arc<State>,
+}
+
+impl TestServer {
+    /// 在随机端口上启动服务器
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(State {
+            handler: Box::new(handler),
+            requests: Mutex::new(vec![]),
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        });
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                tokio::spawn(serve(stream, server_state.clone()));
+            }
+        });
+        Self { addr, state }
+    }
+
+    /// 服务器上指定路径的 URL
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.addr)
+    }
+
+    /// 服务器的根 URL
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// 已收到的请求
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// 已收到的请求数
+    pub fn request_count(&self) -> usize {
+        self.state.requests.lock().unwrap().len()
+    }
+
+    /// 同时处理的最大请求数
+    pub fn max_in_flight(&self) -> usize {
+        self.state.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// 处理一个连接上的一个请求,响应后关闭连接
+async fn serve(mut stream: TcpStream, state: Arc<State>) {
+    let Some(request) = read_request(&mut stream).await else {
+        return;
+    };
+    let in_flight = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    state.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+    state.requests.lock().unwrap().push(request.clone());
+
+    let response = (state.handler)(&request);
+    tokio::time::sleep(response.delay).await;
+
+    let mut head = format!("HTTP/1.1 {} Test\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body.len()
+    ));
+    let _ = stream.write_all(head.as_bytes()).await;
+    if request.method != "HEAD" {
+        let _ = stream.write_all(&response.body).await;
+    }
+    let _ = stream.shutdown().await;
+    state.in_flight.fetch_sub(1, Ordering::SeqCst);
+}
+
+/// 读取并解析请求行和请求头,忽略请求体
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = vec![];
+    let mut chunk = [0; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines = text.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    Some(Request {
+        method,
+        path,
+        headers,
+    })
+}
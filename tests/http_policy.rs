@@ -0,0 +1,215 @@
+//! HTTP 读取器请求策略的集成测试
+//!
+//! 使用本地 HTTP 服务器模拟临时错误、慢响应和并发请求
+
+#![cfg(feature = "http")]
+
+mod common;
+
+use cloudtiff::{AsyncReadRange, CloudTiff, Encoder, HttpReader, Region, RequestPolicy};
+use common::{range_response, Response, TestServer};
+use image::{DynamicImage, ImageBuffer, Luma};
+use std::io::{Cursor, ErrorKind};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 测试用的对象内容
+fn object() -> Vec<u8> {
+    (0..=255).cycle().take(4096).collect()
+}
+
+/// 编码一个 512x512、128 像素分块的 COG
+fn cog_bytes() -> Vec<u8> {
+    let image = ImageBuffer::from_fn(512, 512, |x, y| Luma([(x + y) as u16]));
+    let mut cursor = Cursor::new(vec![]);
+    Encoder::from_image(&DynamicImage::ImageLuma16(image))
+        .unwrap()
+        .with_projection(32609, Region::new(499980.0, 6090000.0, 505100.0, 6095120.0))
+        .with_tile_size(128)
+        .encode(&mut cursor)
+        .unwrap();
+    cursor.into_inner()
+}
+
+/// 创建使用指定策略、不检查对象版本的读取器
+fn reader(server: &TestServer, policy: RequestPolicy) -> HttpReader {
+    HttpReader::builder(server.url("/object.tif"))
+        .with_policy(policy)
+        .with_version_pinning(false)
+        .build()
+        .unwrap()
+}
+
+/// 前 `failures` 个请求返回 `status`,之后正常返回数据的服务器
+async fn flaky_server(status: u16, failures: usize) -> TestServer {
+    let data = object();
+    let count = AtomicUsize::new(0);
+    TestServer::start(move |request| {
+        if count.fetch_add(1, Ordering::SeqCst) < failures {
+            Response::new(status)
+        } else {
+            range_response(&data, request)
+        }
+    })
+    .await
+}
+
+/// 重试策略:最多重试 3 次,退避时间 50ms、100ms、200ms
+fn retry_policy() -> RequestPolicy {
+    RequestPolicy::default()
+        .with_max_retries(3)
+        .with_backoff(Duration::from_millis(50), Duration::from_secs(1))
+}
+
+#[tokio::test]
+async fn retries_after_503_with_backoff() {
+    let server = flaky_server(503, 2).await;
+    let reader = reader(&server, retry_policy());
+
+    let start = Instant::now();
+    let mut buf = [0; 16];
+    let n = reader.read_range_async(100, &mut buf).await.unwrap();
+
+    assert_eq!(n, 16);
+    assert_eq!(&buf[..], &object()[100..116]);
+    assert_eq!(server.request_count(), 3);
+    // 两次重试前分别等待 50ms 和 100ms
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn retries_after_429_with_backoff() {
+    let server = flaky_server(429, 1).await;
+    let reader = reader(&server, retry_policy());
+
+    let start = Instant::now();
+    let mut buf = [0; 8];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+
+    assert_eq!(&buf[..], &object()[..8]);
+    assert_eq!(server.request_count(), 2);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let server = flaky_server(503, usize::MAX).await;
+    let reader = reader(&server, retry_policy().with_max_retries(1));
+
+    let mut buf = [0; 8];
+    let error = reader.read_range_async(0, &mut buf).await.unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Other);
+    assert!(error.to_string().contains("503"));
+    assert_eq!(server.request_count(), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = flaky_server(404, usize::MAX).await;
+    let reader = reader(&server, retry_policy());
+
+    let mut buf = [0; 8];
+    let error = reader.read_range_async(0, &mut buf).await.unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert_eq!(server.request_count(), 1);
+}
+
+#[tokio::test]
+async fn timeout_returns_error() {
+    let data = object();
+    let server = TestServer::start(move |request| {
+        range_response(&data, request).with_delay(Duration::from_millis(500))
+    })
+    .await;
+    let policy = RequestPolicy::default()
+        .with_timeout(Some(Duration::from_millis(50)))
+        .with_max_retries(0);
+    let reader = reader(&server, policy);
+
+    let start = Instant::now();
+    let mut buf = [0; 8];
+    let error = reader.read_range_async(0, &mut buf).await.unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_millis(400));
+}
+
+#[tokio::test]
+async fn max_in_flight_limits_concurrent_requests() {
+    let data = object();
+    let server = TestServer::start(move |request| {
+        range_response(&data, request).with_delay(Duration::from_millis(50))
+    })
+    .await;
+    let reader = reader(&server, RequestPolicy::default().with_max_in_flight(2));
+
+    let reads = (0..8).map(|i| {
+        let reader = &reader;
+        async move {
+            let mut buf = [0; 16];
+            reader.read_range_async(i * 16, &mut buf).await.map(|_| buf)
+        }
+    });
+    let results = futures::future::join_all(reads).await;
+
+    for (i, result) in results.into_iter().enumerate() {
+        assert_eq!(&result.unwrap()[..], &object()[i * 16..(i + 1) * 16]);
+    }
+    assert_eq!(server.request_count(), 8);
+    assert_eq!(server.max_in_flight(), 2);
+}
+
+/// 打开 COG 后第三个请求返回 500 的服务器
+async fn failing_tile_server() -> (TestServer, Arc<AtomicBool>) {
+    let data = cog_bytes();
+    let opened = Arc::new(AtomicBool::new(false));
+    let failing = opened.clone();
+    let count = AtomicUsize::new(0);
+    let server = TestServer::start(move |request| {
+        if failing.load(Ordering::SeqCst) && count.fetch_add(1, Ordering::SeqCst) == 2 {
+            Response::new(500)
+        } else {
+            range_response(&data, request)
+        }
+    })
+    .await;
+    (server, opened)
+}
+
+#[tokio::test]
+async fn get_tiles_fails_on_failed_tile() {
+    let (server, opened) = failing_tile_server().await;
+    let reader = reader(&server, RequestPolicy::default().with_max_retries(0));
+    let cog = CloudTiff::open_from_async_range_reader(&reader)
+        .await
+        .unwrap();
+    opened.store(true, Ordering::SeqCst);
+
+    let level = cog.get_level(0).unwrap();
+    let indices = (0..level.col_count() * level.row_count()).collect();
+    let result = cloudtiff::tiles::get_tiles_from_async(&reader, level, indices).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn render_fails_on_failed_tile() {
+    let (server, opened) = failing_tile_server().await;
+    let reader = reader(&server, RequestPolicy::default().with_max_retries(0));
+    let cog = CloudTiff::open_from_async_range_reader(&reader)
+        .await
+        .unwrap();
+    opened.store(true, Ordering::SeqCst);
+
+    // 整幅渲染读取 16 个瓦片,其中一个失败时不能返回留有空洞的图像
+    let result = cog
+        .renderer()
+        .with_async_range_reader(reader)
+        .render_async()
+        .await;
+
+    assert!(result.is_err());
+}
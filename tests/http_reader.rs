@@ -0,0 +1,295 @@
+//! HTTP 读取器请求头和响应校验的集成测试
+
+#![cfg(feature = "http")]
+
+mod common;
+
+use cloudtiff::io::ObjectVersion;
+use cloudtiff::{
+    AsyncReadRange, BlockingHttpReader, CloudTiffError, HttpReader, ReadRange, RequestPolicy,
+};
+use common::{range_response, Response, TestServer};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 测试用的对象内容
+fn object() -> Vec<u8> {
+    (0..=255).cycle().take(4096).collect()
+}
+
+/// 只接受最新令牌的服务器,旧令牌返回 503
+async fn token_server() -> TestServer {
+    let data = object();
+    TestServer::start(move |request| match request.header("authorization") {
+        Some("Bearer token-2") => range_response(&data, request),
+        _ => Response::new(503),
+    })
+    .await
+}
+
+/// 每次调用生成新令牌的请求头钩子
+fn refreshing_hook() -> impl Fn(&reqwest::Url, &mut reqwest::header::HeaderMap) + Send + Sync {
+    let issued = Arc::new(AtomicUsize::new(0));
+    move |_, headers| {
+        let token = issued.fetch_add(1, Ordering::SeqCst) + 1;
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer token-{token}").parse().unwrap(),
+        );
+    }
+}
+
+/// 重试一次、退避时间很短的策略
+fn retry_once() -> RequestPolicy {
+    RequestPolicy::default()
+        .with_max_retries(1)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(1))
+}
+
+#[tokio::test]
+async fn request_hook_runs_for_every_attempt() {
+    let server = token_server().await;
+    let reader = HttpReader::builder(server.url("/object.tif"))
+        .with_policy(retry_once())
+        .with_request_hook(refreshing_hook())
+        .build()
+        .unwrap();
+
+    let mut buf = [0; 8];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+
+    let tokens: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|request| request.header("authorization").unwrap().to_string())
+        .collect();
+    assert_eq!(tokens, ["Bearer token-1", "Bearer token-2"]);
+}
+
+#[tokio::test]
+async fn blocking_request_hook_runs_for_every_attempt() {
+    let server = token_server().await;
+    let url = server.url("/object.tif");
+    let result = tokio::task::spawn_blocking(move || {
+        let reader = BlockingHttpReader::builder(url)
+            .with_policy(retry_once())
+            .with_request_hook(refreshing_hook())
+            .build_blocking()
+            .unwrap();
+        let mut buf = [0; 8];
+        reader.read_range(0, &mut buf).map(|_| buf)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(&result.unwrap()[..], &object()[..8]);
+    assert_eq!(server.request_count(), 2);
+}
+
+#[tokio::test]
+async fn default_headers_are_sent() {
+    let data = object();
+    let server = TestServer::start(move |request| range_response(&data, request)).await;
+    let reader = HttpReader::builder(server.url("/object.tif"))
+        .with_bearer_auth("secret")
+        .with_user_agent("cloudtiff-test")
+        .with_header("x-api-key", "key")
+        .build()
+        .unwrap();
+
+    let mut buf = [0; 8];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.header("authorization"), Some("Bearer secret"));
+    assert_eq!(request.header("user-agent"), Some("cloudtiff-test"));
+    assert_eq!(request.header("x-api-key"), Some("key"));
+    assert_eq!(request.header("range"), Some("bytes=0-7"));
+}
+
+/// 忽略 Range、总是返回整个对象的服务器
+async fn range_ignoring_server(size: usize) -> TestServer {
+    let data: Vec<u8> = (0..=255).cycle().take(size).collect();
+    TestServer::start(move |_| Response::new(200).with_body(data.clone())).await
+}
+
+#[tokio::test]
+async fn range_ignored_reads_only_requested_bytes() {
+    let server = range_ignoring_server(16 << 20).await;
+    let reader = HttpReader::new(server.url("/object.tif")).unwrap();
+
+    let mut buf = [0; 16];
+    let n = reader.read_range_async(0, &mut buf).await.unwrap();
+
+    assert_eq!(n, 16);
+    assert_eq!(&buf[..], &object()[..16]);
+}
+
+#[tokio::test]
+async fn blocking_range_ignored_reads_only_requested_bytes() {
+    let server = range_ignoring_server(16 << 20).await;
+    let url = server.url("/object.tif");
+    let result = tokio::task::spawn_blocking(move || {
+        let reader = BlockingHttpReader::new(url).unwrap();
+        let mut buf = [0; 16];
+        reader.read_range(0, &mut buf).map(|n| (n, buf))
+    })
+    .await
+    .unwrap();
+
+    let (n, buf) = result.unwrap();
+    assert_eq!(n, 16);
+    assert_eq!(&buf[..], &object()[..16]);
+}
+
+#[tokio::test]
+async fn range_ignored_rejected_past_start() {
+    let server = range_ignoring_server(4096).await;
+    let reader = HttpReader::new(server.url("/object.tif")).unwrap();
+
+    let mut buf = [0; 16];
+    let error = reader.read_range_async(100, &mut buf).await.unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// 每个响应都带有指定版本响应头的服务器
+///
+/// `version` 根据已处理的请求数返回响应头 (名称, 值),
+/// `precondition` 为 Some 时请求带有该条件请求头则返回 412
+async fn versioned_server<F>(version: F, precondition: Option<&'static str>) -> TestServer
+where
+    F: Fn(usize) -> (&'static str, String) + Send + Sync + 'static,
+{
+    let data = object();
+    let served = AtomicUsize::new(0);
+    TestServer::start(move |request| {
+        if precondition.is_some_and(|name| request.header(name).is_some()) {
+            return Response::new(412);
+        }
+        let (name, value) = version(served.fetch_add(1, Ordering::SeqCst));
+        range_response(&data, request).with_header(name, value)
+    })
+    .await
+}
+
+#[tokio::test]
+async fn etag_is_pinned_and_sent_as_if_match() {
+    let server = versioned_server(|_| ("ETag", "\"v1\"".into()), None).await;
+    let reader = HttpReader::new(server.url("/object.tif")).unwrap();
+
+    let mut buf = [0; 8];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+    reader.read_range_async(8, &mut buf).await.unwrap();
+
+    assert_eq!(
+        reader.version(),
+        Some(&ObjectVersion {
+            etag: Some("\"v1\"".into()),
+            last_modified: None,
+        })
+    );
+    let requests = server.requests();
+    assert_eq!(requests[0].header("if-match"), None);
+    assert_eq!(requests[1].header("if-match"), Some("\"v1\""));
+}
+
+#[tokio::test]
+async fn last_modified_is_sent_as_if_unmodified_since() {
+    let last_modified = "Wed, 21 Oct 2015 07:28:00 GMT";
+    let server = versioned_server(move |_| ("Last-Modified", last_modified.into()), None).await;
+    let reader = HttpReader::new(server.url("/object.tif")).unwrap();
+
+    let mut buf = [0; 8];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+    reader.read_range_async(8, &mut buf).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[1].header("if-match"), None);
+    assert_eq!(
+        requests[1].header("if-unmodified-since"),
+        Some(last_modified)
+    );
+}
+
+#[tokio::test]
+async fn precondition_failed_is_remote_object_changed() {
+    let server = versioned_server(|_| ("ETag", "\"v1\"".into()), Some("if-match")).await;
+    let reader = HttpReader::new(server.url("/object.tif")).unwrap();
+
+    let mut buf = [0; 8];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+    let error = reader.read_range_async(8, &mut buf).await.unwrap_err();
+
+    match CloudTiffError::from(error) {
+        CloudTiffError::RemoteObjectChanged(changed) => {
+            assert_eq!(changed.expected.etag.as_deref(), Some("\"v1\""));
+            assert!(changed.found.is_none());
+        }
+        other => panic!("应为 RemoteObjectChanged: {other:?}"),
+    }
+    // 对象变更不重试
+    assert_eq!(server.request_count(), 2);
+}
+
+#[tokio::test]
+async fn changed_etag_is_rejected() {
+    // 服务器忽略 If-Match,第二个响应返回新版本
+    let server = versioned_server(|served| ("ETag", format!("\"v{}\"", served + 1)), None).await;
+    let reader = HttpReader::new(server.url("/object.tif")).unwrap();
+
+    let mut buf = [0; 8];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+    let error = reader.read_range_async(8, &mut buf).await.unwrap_err();
+
+    match CloudTiffError::from(error) {
+        CloudTiffError::RemoteObjectChanged(changed) => {
+            assert_eq!(changed.expected.etag.as_deref(), Some("\"v1\""));
+            assert_eq!(changed.found.unwrap().etag.as_deref(), Some("\"v2\""));
+        }
+        other => panic!("应为 RemoteObjectChanged: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn blocking_changed_etag_is_rejected() {
+    let server = versioned_server(|served| ("ETag", format!("\"v{}\"", served + 1)), None).await;
+    let url = server.url("/object.tif");
+    let result = tokio::task::spawn_blocking(move || {
+        let reader = BlockingHttpReader::new(url).unwrap();
+        let mut buf = [0; 8];
+        reader.read_range(0, &mut buf)?;
+        reader.read_range(8, &mut buf)
+    })
+    .await
+    .unwrap();
+
+    assert!(matches!(
+        CloudTiffError::from(result.unwrap_err()),
+        CloudTiffError::RemoteObjectChanged(_)
+    ));
+}
+
+#[tokio::test]
+async fn mismatched_content_range_is_rejected() {
+    // 返回的内容比请求的范围晚 4 个字节开始
+    let data = object();
+    let server = TestServer::start(move |request| {
+        let (start, end) = request.range().unwrap();
+        Response::new(206)
+            .with_header(
+                "Content-Range",
+                format!("bytes {}-{}/{}", start + 4, end + 4, data.len()),
+            )
+            .with_body(data[start as usize + 4..=end as usize + 4].to_vec())
+    })
+    .await;
+    let reader = HttpReader::new(server.url("/object.tif")).unwrap();
+
+    let mut buf = [0; 8];
+    let error = reader.read_range_async(0, &mut buf).await.unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("Content-Range"));
+}
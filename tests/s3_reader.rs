@@ -0,0 +1,189 @@
+//! S3 读取器的集成测试
+//!
+//! 使用本地 HTTP 服务器模拟 S3 的 HeadObject 和 GetObject 接口
+
+#![cfg(feature = "s3")]
+
+mod common;
+
+use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
+use aws_sdk_s3::Client;
+use cloudtiff::io::ObjectChangedError;
+use cloudtiff::{AsyncReadRange, S3Reader};
+use common::{range_response, Request, Response, TestServer};
+use std::sync::{Arc, Mutex};
+
+/// 对象的最后修改时间
+const LAST_MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+/// 测试用的对象内容
+fn object() -> Vec<u8> {
+    (0..=255).cycle().take(4096).collect()
+}
+
+/// 模拟的 S3 对象状态
+struct MockObject {
+    /// 当前的 ETag
+    etag: String,
+    /// 启用版本控制时的版本 ID
+    version_id: Option<String>,
+}
+
+/// 启动模拟 S3 服务器
+///
+/// HeadObject 返回大小和版本信息,GetObject 按 Range 返回数据,
+/// `If-Match` 与当前 ETag 不一致时返回 412
+async fn mock_s3(object_state: Arc<Mutex<MockObject>>) -> TestServer {
+    let data = object();
+    TestServer::start(move |request| {
+        let state = object_state.lock().unwrap();
+        if !request.path.starts_with("/bucket/image.tif") {
+            return Response::new(404);
+        }
+        if request
+            .header("if-match")
+            .is_some_and(|etag| etag != state.etag)
+        {
+            return Response::new(412);
+        }
+        let mut response = match request.method.as_str() {
+            "HEAD" => Response::new(200).with_body(data.clone()),
+            _ => range_response(&data, request),
+        };
+        response = response
+            .with_header("ETag", &state.etag)
+            .with_header("Last-Modified", LAST_MODIFIED);
+        if let Some(version_id) = &state.version_id {
+            response = response.with_header("x-amz-version-id", version_id);
+        }
+        response
+    })
+    .await
+}
+
+/// 连接到模拟服务器的 S3 客户端
+fn client(server: &TestServer) -> Client {
+    let config = aws_sdk_s3::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-1"))
+        .endpoint_url(server.endpoint())
+        .force_path_style(true)
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .build();
+    Client::from_conf(config)
+}
+
+/// 对象状态
+fn object_state(version_id: Option<&str>) -> Arc<Mutex<MockObject>> {
+    Arc::new(Mutex::new(MockObject {
+        etag: "\"etag-1\"".to_string(),
+        version_id: version_id.map(str::to_string),
+    }))
+}
+
+/// 最后一个 GET 请求
+fn last_get(server: &TestServer) -> Request {
+    server
+        .requests()
+        .into_iter()
+        .filter(|request| request.method == "GET")
+        .last()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn head_probes_object_size() {
+    let server = mock_s3(object_state(None)).await;
+    let reader = S3Reader::new(client(&server), "bucket", "image.tif");
+
+    let info = reader.head_async().await.unwrap();
+
+    assert_eq!(info.size, 4096);
+    assert_eq!(info.version_id, None);
+    assert_eq!(info.version.etag.as_deref(), Some("\"etag-1\""));
+    assert_eq!(info.version.last_modified.as_deref(), Some(LAST_MODIFIED));
+    assert_eq!(reader.object_size_async().await.unwrap(), 4096);
+    assert!(server.requests().iter().all(|r| r.method == "HEAD"));
+}
+
+#[tokio::test]
+async fn pin_version_uses_version_id() {
+    let server = mock_s3(object_state(Some("v1"))).await;
+    let reader = S3Reader::new(client(&server), "bucket", "image.tif")
+        .pin_version_async()
+        .await
+        .unwrap();
+
+    let mut buf = [0; 16];
+    reader.read_range_async(32, &mut buf).await.unwrap();
+
+    assert_eq!(&buf[..], &object()[32..48]);
+    assert_eq!(reader.version_id(), Some("v1"));
+    let get = last_get(&server);
+    assert!(get.path.contains("versionId=v1"), "{}", get.path);
+    assert_eq!(get.header("if-match"), None);
+    assert_eq!(get.header("range"), Some("bytes=32-47"));
+}
+
+#[tokio::test]
+async fn pin_version_falls_back_to_if_match() {
+    let state = object_state(None);
+    let server = mock_s3(state.clone()).await;
+    let reader = S3Reader::new(client(&server), "bucket", "image.tif")
+        .pin_version_async()
+        .await
+        .unwrap();
+
+    let mut buf = [0; 16];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+
+    assert_eq!(reader.version_id(), None);
+    let get = last_get(&server);
+    assert!(!get.path.contains("versionId"), "{}", get.path);
+    assert_eq!(get.header("if-match"), Some("\"etag-1\""));
+
+    // 对象被替换后读取失败
+    state.lock().unwrap().etag = "\"etag-2\"".to_string();
+    let error = reader.read_range_async(0, &mut buf).await.unwrap_err();
+    let changed = error
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<ObjectChangedError>())
+        .unwrap();
+    assert_eq!(changed.expected.etag.as_deref(), Some("\"etag-1\""));
+}
+
+#[tokio::test]
+async fn requester_pays_header_is_sent() {
+    let server = mock_s3(object_state(None)).await;
+    let reader = S3Reader::new(client(&server), "bucket", "image.tif")
+        .with_requester_pays(true)
+        .pin_version_async()
+        .await
+        .unwrap();
+
+    let mut buf = [0; 16];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        assert_eq!(
+            request.header("x-amz-request-payer"),
+            Some("requester"),
+            "{} {}",
+            request.method,
+            request.path
+        );
+    }
+}
+
+#[tokio::test]
+async fn requester_pays_is_off_by_default() {
+    let server = mock_s3(object_state(None)).await;
+    let reader = S3Reader::new(client(&server), "bucket", "image.tif");
+
+    let mut buf = [0; 16];
+    reader.read_range_async(0, &mut buf).await.unwrap();
+
+    assert_eq!(last_get(&server).header("x-amz-request-payer"), None);
+}